@@ -1,7 +1,9 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use gitgold_challenge::challenge::Challenge;
 use gitgold_challenge::proof::ChallengeProof;
-use gitgold_challenge::validator::validate_challenge_response;
+use gitgold_challenge::validator::{
+    validate_challenge_response, validate_challenge_responses, ChallengeResponse,
+};
 use gitgold_core::config::GitGoldConfig;
 use gitgold_crypto::keys::KeyPair;
 
@@ -65,6 +67,49 @@ fn validate_response(c: &mut Criterion) {
     });
 }
 
+fn validate_response_batch(c: &mut Criterion) {
+    const BATCH: usize = 64;
+    let config = GitGoldConfig::default();
+    let fragment_data = vec![0xABu8; FRAGMENT_SIZE];
+    let kp = KeyPair::generate();
+    let pk = kp.public_key();
+    let challenges: Vec<Challenge> = (0..BATCH)
+        .map(|i| Challenge::generate("repo_hash", i as u32, 1, FRAGMENT_SIZE, &config).unwrap())
+        .collect();
+    let proofs: Vec<ChallengeProof> = challenges
+        .iter()
+        .map(|challenge| {
+            ChallengeProof::create(challenge, &fragment_data, 100, |msg| {
+                hex::encode(kp.sign(msg))
+            })
+        })
+        .collect();
+    let responses: Vec<ChallengeResponse> = challenges
+        .iter()
+        .zip(&proofs)
+        .map(|(challenge, proof)| ChallengeResponse {
+            challenge,
+            proof,
+            fragment_data: &fragment_data,
+            node_pubkey: &pk,
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("validate_responses");
+    group.bench_function(BenchmarkId::new("sequential", BATCH), |bench| {
+        bench.iter(|| {
+            for r in &responses {
+                validate_challenge_response(r.challenge, r.proof, r.fragment_data, &pk, &config)
+                    .unwrap();
+            }
+        })
+    });
+    group.bench_function(BenchmarkId::new("batch", BATCH), |bench| {
+        bench.iter(|| validate_challenge_responses(black_box(&responses), &config).unwrap())
+    });
+    group.finish();
+}
+
 fn full_challenge_cycle(c: &mut Criterion) {
     let config = GitGoldConfig::default();
     let fragment_data = vec![0xABu8; FRAGMENT_SIZE];
@@ -88,6 +133,7 @@ criterion_group!(
     challenge_generate,
     proof_create,
     validate_response,
+    validate_response_batch,
     full_challenge_cycle,
 );
 criterion_main!(benches);
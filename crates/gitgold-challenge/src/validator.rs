@@ -2,7 +2,7 @@ use gitgold_core::config::GitGoldConfig;
 use gitgold_core::error::ChallengeError;
 use gitgold_core::types::MicroGitGold;
 use gitgold_crypto::hash::sha256_pair;
-use gitgold_crypto::keys::PublicKey;
+use gitgold_crypto::keys::{self, BatchItem, PublicKey};
 
 use crate::challenge::Challenge;
 use crate::proof::ChallengeProof;
@@ -20,6 +20,15 @@ pub struct ValidationResult {
     pub reason: Option<String>,
 }
 
+/// One challenge response to check with [`validate_challenge_responses`].
+#[derive(Debug, Clone, Copy)]
+pub struct ChallengeResponse<'a> {
+    pub challenge: &'a Challenge,
+    pub proof: &'a ChallengeProof,
+    pub fragment_data: &'a [u8],
+    pub node_pubkey: &'a PublicKey,
+}
+
 /// Validate a challenge proof against expected data.
 ///
 /// Checks:
@@ -37,9 +46,79 @@ pub fn validate_challenge_response(
     node_pubkey: &PublicKey,
     config: &GitGoldConfig,
 ) -> Result<ValidationResult, ChallengeError> {
+    if let Some(rejected) = check_timing_and_hash(challenge, proof, fragment_data)? {
+        return Ok(rejected);
+    }
+
+    // 3. Verify signature
+    let sig_bytes = hex::decode(&proof.signature).unwrap_or_default();
+    if !node_pubkey.verify(&signable_bytes(challenge, proof), &sig_bytes) {
+        return Ok(invalid_signature());
+    }
+
+    Ok(accepted(challenge, proof, config))
+}
+
+/// Validate a batch of challenge responses, e.g. everything a validator
+/// received in one round.
+///
+/// Same checks and results as [`validate_challenge_response`] for each
+/// item, but the signatures of all responses that pass the timing and
+/// hash checks are verified together with [`keys::verify_batch`], which
+/// accepts exactly the signatures [`PublicKey::verify`] does.
+pub fn validate_challenge_responses(
+    responses: &[ChallengeResponse],
+    config: &GitGoldConfig,
+) -> Result<Vec<ValidationResult>, ChallengeError> {
+    let mut results = Vec::with_capacity(responses.len());
+    let mut pending = Vec::new();
+    for (i, response) in responses.iter().enumerate() {
+        let rejected =
+            check_timing_and_hash(response.challenge, response.proof, response.fragment_data)?;
+        if rejected.is_none() {
+            pending.push((
+                i,
+                signable_bytes(response.challenge, response.proof),
+                hex::decode(&response.proof.signature).unwrap_or_default(),
+            ));
+        }
+        results.push(rejected);
+    }
+
+    let items: Vec<BatchItem> = pending
+        .iter()
+        .map(|(i, message, signature)| BatchItem {
+            pubkey: responses[*i].node_pubkey,
+            message,
+            signature,
+        })
+        .collect();
+    let invalid = keys::verify_batch(&items).err().unwrap_or_default();
+
+    for (n, (i, _, _)) in pending.iter().enumerate() {
+        results[*i] = Some(if invalid.binary_search(&n).is_ok() {
+            invalid_signature()
+        } else {
+            accepted(responses[*i].challenge, responses[*i].proof, config)
+        });
+    }
+
+    Ok(results
+        .into_iter()
+        .map(|result| result.expect("every response was decided"))
+        .collect())
+}
+
+/// Checks 1 and 2: returns the rejection if the proof is late or its hash
+/// is wrong, `None` if it passes.
+fn check_timing_and_hash(
+    challenge: &Challenge,
+    proof: &ChallengeProof,
+    fragment_data: &[u8],
+) -> Result<Option<ValidationResult>, ChallengeError> {
     // 1. Check timeout
     if proof.response_time_ms > challenge.timeout_ms {
-        return Ok(ValidationResult {
+        return Ok(Some(ValidationResult {
             valid: false,
             reward: 0,
             speed_bonus: 0.0,
@@ -47,7 +126,7 @@ pub fn validate_challenge_response(
                 "timeout: {}ms > {}ms",
                 proof.response_time_ms, challenge.timeout_ms
             )),
-        });
+        }));
     }
 
     // 2. Check hash
@@ -63,7 +142,7 @@ pub fn validate_challenge_response(
     let expected_hash = sha256_pair(range_data, &challenge.nonce);
 
     if proof.hash != expected_hash {
-        return Ok(ValidationResult {
+        return Ok(Some(ValidationResult {
             valid: false,
             reward: 0,
             speed_bonus: 0.0,
@@ -72,23 +151,34 @@ pub fn validate_challenge_response(
                 hex::encode(expected_hash),
                 hex::encode(proof.hash)
             )),
-        });
+        }));
     }
 
-    // 3. Verify signature
+    Ok(None)
+}
+
+/// Message the node signs: challenge_id || hash.
+fn signable_bytes(challenge: &Challenge, proof: &ChallengeProof) -> Vec<u8> {
     let mut signable = challenge.id.as_bytes().to_vec();
     signable.extend_from_slice(&proof.hash);
+    signable
+}
 
-    let sig_bytes = hex::decode(&proof.signature).unwrap_or_default();
-    if !node_pubkey.verify(&signable, &sig_bytes) {
-        return Ok(ValidationResult {
-            valid: false,
-            reward: 0,
-            speed_bonus: 0.0,
-            reason: Some("invalid signature".to_string()),
-        });
+fn invalid_signature() -> ValidationResult {
+    ValidationResult {
+        valid: false,
+        reward: 0,
+        speed_bonus: 0.0,
+        reason: Some("invalid signature".to_string()),
     }
+}
 
+/// Result for a proof that passed every check, with its reward.
+fn accepted(
+    challenge: &Challenge,
+    proof: &ChallengeProof,
+    config: &GitGoldConfig,
+) -> ValidationResult {
     // Compute speed bonus: max(0, 1 - response_time/timeout) * 0.5
     let speed_bonus =
         (1.0 - proof.response_time_ms as f64 / challenge.timeout_ms as f64).max(0.0) * 0.5;
@@ -96,12 +186,12 @@ pub fn validate_challenge_response(
     // reward = challenge_bonus * (1 + speed_bonus)
     let reward = (config.challenge_bonus as f64 * (1.0 + speed_bonus)) as MicroGitGold;
 
-    Ok(ValidationResult {
+    ValidationResult {
         valid: true,
         reward,
         speed_bonus,
         reason: None,
-    })
+    }
 }
 
 #[cfg(test)]
@@ -214,4 +304,52 @@ mod tests {
             validate_challenge_response(&challenge, &proof, &data, &other_pk, &config).unwrap();
         assert!(!result.valid);
     }
+
+    #[test]
+    fn test_batch_matches_individual_results() {
+        let (data, challenge, kp, config) = setup();
+        let other_kp = KeyPair::generate();
+        let pk = kp.public_key();
+        let other_pk = other_kp.public_key();
+
+        let good = make_valid_proof(&challenge, &data, &kp);
+        let late = ChallengeProof::create(&challenge, &data, 999_999, |msg| {
+            hex::encode(kp.sign(msg))
+        });
+        let mut bad_hash = make_valid_proof(&challenge, &data, &kp);
+        bad_hash.hash = [0xFF; 32];
+        let slow = ChallengeProof::create(&challenge, &data, 25_000, |msg| {
+            hex::encode(kp.sign(msg))
+        });
+
+        let cases = [
+            (&good, &pk),
+            (&late, &pk),
+            (&good, &other_pk), // wrong key
+            (&bad_hash, &pk),
+            (&slow, &pk),
+        ];
+        let responses: Vec<_> = cases
+            .iter()
+            .map(|(proof, node_pubkey)| ChallengeResponse {
+                challenge: &challenge,
+                proof,
+                fragment_data: &data,
+                node_pubkey,
+            })
+            .collect();
+
+        let batch = validate_challenge_responses(&responses, &config).unwrap();
+        assert_eq!(batch.len(), cases.len());
+        for ((proof, node_pubkey), result) in cases.iter().zip(&batch) {
+            let single =
+                validate_challenge_response(&challenge, proof, &data, node_pubkey, &config)
+                    .unwrap();
+            assert_eq!(result.valid, single.valid);
+            assert_eq!(result.reward, single.reward);
+            assert_eq!(result.reason, single.reason);
+        }
+        let valid: Vec<bool> = batch.iter().map(|r| r.valid).collect();
+        assert_eq!(valid, [true, false, false, false, true]);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::types::{MicroGitGold, MICRO_PER_COIN};
+use crate::types::{MicroGitGold, RedundancyMode, MICRO_PER_COIN};
 
 /// Configuration with whitepaper defaults.
 #[derive(Debug, Clone)]
@@ -7,8 +7,17 @@ pub struct GitGoldConfig {
     pub k: usize,
     /// Shamir total shares per chunk.
     pub n: usize,
+    /// Redundancy scheme used to encode chunks into shares.
+    pub redundancy: RedundancyMode,
     /// Chunk size in bytes (default 512 KB).
     pub chunk_size: usize,
+    /// Minimum content-defined chunk size in bytes (default 128 KB).
+    pub cdc_min_size: usize,
+    /// Average content-defined chunk size in bytes, rounded down to a
+    /// power of two (default 512 KB).
+    pub cdc_avg_size: usize,
+    /// Maximum content-defined chunk size in bytes (default 2 MB).
+    pub cdc_max_size: usize,
     /// Challenge timeout in seconds.
     pub challenge_timeout_secs: u64,
     /// Push fee rate in micro-GC per MB.
@@ -40,7 +49,11 @@ impl Default for GitGoldConfig {
         Self {
             k: 5,
             n: 9,
+            redundancy: RedundancyMode::Confidential,
             chunk_size: 512 * 1024, // 512 KB
+            cdc_min_size: 128 * 1024,                          // 128 KB
+            cdc_avg_size: 512 * 1024,                          // 512 KB
+            cdc_max_size: 2 * 1024 * 1024,                     // 2 MB
             challenge_timeout_secs: 30,
             push_fee_rate: 1_000,                              // 0.001 GC/MB
             pull_fee_rate: 500,                                // 0.0005 GC/MB
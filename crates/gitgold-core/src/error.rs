@@ -12,6 +12,32 @@ pub enum ShamirError {
     EmptySecret,
     #[error("duplicate share id: {0}")]
     DuplicateShareId(u32),
+    #[error("too many shares: n={n} exceeds scheme maximum {max}")]
+    TooManyShares { n: usize, max: usize },
+    #[error("invalid share id {0} for this scheme")]
+    InvalidShareId(u32),
+    #[error("shares were produced by different schemes")]
+    MixedSchemes,
+    #[error("shares disagree on threshold, share count or secret length")]
+    InconsistentShares,
+    #[error("invalid share encoding: {0}")]
+    InvalidEncoding(String),
+    #[error("unsupported share format version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("share checksum mismatch")]
+    ChecksumMismatch,
+    #[error("too many faulty shares among {shares} to reconstruct at threshold {k}")]
+    TooManyFaultyShares { shares: usize, k: usize },
+    #[error("robust reconstruction from {shares} shares at threshold {k} would search more than {max} subsets")]
+    SearchTooLarge { shares: usize, k: usize, max: u64 },
+    #[error("invalid share commitments: {0}")]
+    InvalidCommitments(String),
+    #[error("share {id} does not match the dealer's commitment at block {block}")]
+    ShareVerificationFailed { id: u32, block: usize },
+    #[error("operation not supported for {0} shares")]
+    UnsupportedScheme(String),
+    #[error("chunk decryption failed: wrong key or tampered ciphertext")]
+    DecryptionFailed,
 }
 
 #[derive(Debug, Error)]
@@ -27,6 +53,23 @@ pub enum StorageError {
     DataTooLarge { size: usize, max: usize },
     #[error("invalid chunk index: {index} (total: {total})")]
     InvalidChunkIndex { index: u32, total: u32 },
+    #[error("invalid share: {0}")]
+    InvalidShare(String),
+    #[error("chunk {found} arrived out of order, expected {expected}")]
+    OutOfOrderChunk { expected: u32, found: u32 },
+    #[error("invalid chunking parameters: {0}")]
+    InvalidChunkParams(String),
+    #[error("stale generation {generation} for repo={repo_hash}, fragment={fragment_id} (current: {current})")]
+    StaleGeneration {
+        repo_hash: String,
+        fragment_id: u32,
+        generation: u32,
+        current: u32,
+    },
+    #[error("I/O error: {0}")]
+    Io(String),
+    #[error("secret sharing failed: {0}")]
+    Sharing(#[from] ShamirError),
 }
 
 #[derive(Debug, Error)]
@@ -43,6 +86,18 @@ pub enum LedgerError {
     InvalidTransaction(String),
     #[error("supply exceeded: attempted to mint {attempted}, remaining {remaining}")]
     SupplyExceeded { attempted: u64, remaining: u64 },
+    #[error("invalid nonce for {address}: expected {expected}, got {actual}")]
+    InvalidNonce {
+        address: String,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("invalid block: {0}")]
+    InvalidBlock(String),
+    #[error("block not found: {0}")]
+    BlockNotFound(u64),
+    #[error("transaction not in any block: {0}")]
+    NotInBlock(String),
 }
 
 #[derive(Debug, Error)]
@@ -62,3 +117,47 @@ pub enum ChallengeError {
     #[error("challenge not found: {0}")]
     ChallengeNotFound(String),
 }
+
+#[derive(Debug, Error)]
+pub enum KeystoreError {
+    #[error("I/O error: {0}")]
+    Io(String),
+    #[error("malformed keystore: {0}")]
+    InvalidFormat(String),
+    #[error("unsupported keystore version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("unsupported keystore algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("invalid key derivation parameters: {0}")]
+    InvalidKdfParams(String),
+    #[error("keystore checksum mismatch: file is corrupted")]
+    Corrupted,
+    #[error("wrong password")]
+    WrongPassword,
+    #[error("keystore address mismatch: file says {expected}, key derives {actual}")]
+    AddressMismatch { expected: String, actual: String },
+}
+
+#[derive(Debug, Error)]
+pub enum KeyDerivationError {
+    #[error("invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+    #[error("unsupported mnemonic length: {0} words (expected 12, 15, 18, 21 or 24)")]
+    InvalidWordCount(usize),
+    #[error("invalid derivation path: {0}")]
+    InvalidPath(String),
+    #[error("wallet has no seed to derive child keys from")]
+    NotHierarchical,
+}
+
+#[derive(Debug, Error)]
+pub enum MultisigError {
+    #[error("threshold {threshold} invalid for {keys} keys")]
+    InvalidThreshold { threshold: usize, keys: usize },
+    #[error("too many keys: {keys} exceeds maximum {max}")]
+    TooManyKeys { keys: usize, max: usize },
+    #[error("duplicate public key in policy")]
+    DuplicateKey,
+    #[error("invalid public key: {0}")]
+    InvalidPublicKey(String),
+}
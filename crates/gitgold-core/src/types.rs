@@ -50,3 +50,19 @@ pub enum TransactionType {
     /// Initial supply minting.
     Mint,
 }
+
+/// How a repository's chunks are made redundant across nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RedundancyMode {
+    /// Shamir secret sharing: fewer than k shares reveal nothing, but
+    /// every share is as large as the chunk (n x storage).
+    #[default]
+    Confidential,
+    /// Reed-Solomon erasure coding: shards are chunk_size / k bytes
+    /// (n/k x storage), but shards expose plaintext.
+    Efficient,
+    /// AES-256-GCM encrypt, erasure-code the ciphertext and Shamir-share
+    /// only the key: about n/k x storage, and fewer than k shares reveal
+    /// nothing short of breaking AES.
+    Hybrid,
+}
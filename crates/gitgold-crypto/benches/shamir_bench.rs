@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use gitgold_crypto::shamir::{reconstruct, split};
+use gitgold_crypto::shamir::{reconstruct, split, split_with, Reconstructor, ShareScheme};
 
 fn shamir_split(c: &mut Criterion) {
     let configs: &[(usize, usize)] = &[(3, 5), (5, 9)];
@@ -55,6 +55,81 @@ fn shamir_reconstruct(c: &mut Criterion) {
     group.finish();
 }
 
+fn shamir_scheme_compare(c: &mut Criterion) {
+    let secret = vec![0xABu8; 32 * 1024];
+    let (k, n) = (5, 9);
+
+    let mut group = c.benchmark_group("shamir_scheme_32KB_k5n9");
+    group.sample_size(10);
+    for (scheme, label) in [(ShareScheme::Prime256, "prime256"), (ShareScheme::Gf256, "gf256")] {
+        group.bench_function(BenchmarkId::new("split", label), |bench| {
+            bench.iter(|| split_with(black_box(&secret), k, n, scheme).unwrap())
+        });
+        let shares = split_with(&secret, k, n, scheme).unwrap();
+        group.bench_function(BenchmarkId::new("reconstruct", label), |bench| {
+            bench.iter(|| reconstruct(black_box(&shares), k).unwrap())
+        });
+    }
+    group.finish();
+}
+
+#[cfg(feature = "parallel")]
+fn shamir_parallel(c: &mut Criterion) {
+    use gitgold_crypto::shamir::{reconstruct_par, split_par};
+
+    let secret = vec![0xABu8; 512 * 1024];
+    let (k, n) = (5, 9);
+
+    let mut group = c.benchmark_group("shamir_512KB_k5n9");
+    group.sample_size(10);
+    group.throughput(criterion::Throughput::Bytes(secret.len() as u64));
+    group.bench_function("split/serial", |bench| {
+        bench.iter(|| split_with(black_box(&secret), k, n, ShareScheme::Prime256).unwrap())
+    });
+    group.bench_function("split/parallel", |bench| {
+        bench.iter(|| split_par(black_box(&secret), k, n, ShareScheme::Prime256).unwrap())
+    });
+    let shares = split(&secret, k, n).unwrap();
+    group.bench_function("reconstruct/serial", |bench| {
+        bench.iter(|| reconstruct(black_box(&shares), k).unwrap())
+    });
+    group.bench_function("reconstruct/parallel", |bench| {
+        bench.iter(|| reconstruct_par(black_box(&shares), k).unwrap())
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "parallel"))]
+fn shamir_parallel(_: &mut Criterion) {}
+
+fn shamir_reconstructor_reuse(c: &mut Criterion) {
+    // A pull: 16 chunks of 32 KB, all from hosts holding shares 1..=5.
+    let (k, n) = (5, 9);
+    let chunks: Vec<_> = (0..16u8)
+        .map(|i| split(&vec![i; 32 * 1024], k, n).unwrap())
+        .collect();
+
+    let mut group = c.benchmark_group("shamir_pull_16x32KB_k5n9");
+    group.sample_size(10);
+    group.bench_function("reconstruct_each", |bench| {
+        bench.iter(|| {
+            for shares in &chunks {
+                black_box(reconstruct(shares, k).unwrap());
+            }
+        })
+    });
+    group.bench_function("shared_reconstructor", |bench| {
+        bench.iter(|| {
+            let reconstructor =
+                Reconstructor::new(ShareScheme::Prime256, &[1, 2, 3, 4, 5]).unwrap();
+            for shares in &chunks {
+                black_box(reconstructor.reconstruct(shares).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
 fn shamir_roundtrip(c: &mut Criterion) {
     let secret = vec![0x42u8; 32]; // single block
     c.bench_function("shamir_roundtrip_32B_k3n5", |bench| {
@@ -65,5 +140,13 @@ fn shamir_roundtrip(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, shamir_split, shamir_reconstruct, shamir_roundtrip,);
+criterion_group!(
+    benches,
+    shamir_split,
+    shamir_reconstruct,
+    shamir_scheme_compare,
+    shamir_parallel,
+    shamir_reconstructor_reuse,
+    shamir_roundtrip,
+);
 criterion_main!(benches);
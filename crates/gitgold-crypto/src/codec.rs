@@ -0,0 +1,67 @@
+use gitgold_core::error::ShamirError;
+use gitgold_core::types::RedundancyMode;
+
+use crate::hybrid::Hybrid;
+use crate::reed_solomon::ReedSolomon;
+use crate::shamir::{Shamir, Share};
+
+/// Encodes a chunk into `n` shares such that any `k` of them recover it.
+///
+/// Implemented by Shamir secret sharing (confidential, n x storage),
+/// Reed-Solomon erasure coding (not confidential, n/k x storage) and the
+/// hybrid encrypt-then-share scheme (computationally confidential, about
+/// n/k x storage). All produce plain `Share`s, so storage treats them
+/// identically.
+pub trait ShareCodec: Send + Sync {
+    /// Encode `data` into `n` shares with reconstruction threshold `k`.
+    fn encode(&self, data: &[u8], k: usize, n: usize) -> Result<Vec<Share>, ShamirError>;
+
+    /// Recover the (possibly zero-padded) data from at least `k` shares.
+    fn decode(&self, shares: &[Share], k: usize) -> Result<Vec<u8>, ShamirError>;
+}
+
+/// Pick the codec for a repository's redundancy mode.
+pub fn codec_for(mode: RedundancyMode) -> &'static dyn ShareCodec {
+    match mode {
+        RedundancyMode::Confidential => &Shamir,
+        RedundancyMode::Efficient => &ReedSolomon,
+        RedundancyMode::Hybrid => &Hybrid,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codecs_roundtrip() {
+        let data: Vec<u8> = (0..777).map(|i| (i % 256) as u8).collect();
+        for mode in [
+            RedundancyMode::Confidential,
+            RedundancyMode::Efficient,
+            RedundancyMode::Hybrid,
+        ] {
+            let codec = codec_for(mode);
+            let shares = codec.encode(&data, 3, 5).unwrap();
+            let recovered = codec.decode(&shares[2..], 3).unwrap();
+            assert_eq!(&recovered[..data.len()], &data[..]);
+        }
+    }
+
+    #[test]
+    fn test_efficient_mode_uses_less_storage() {
+        let data = vec![0x5Au8; 3000];
+        let total = |mode| -> usize {
+            codec_for(mode)
+                .encode(&data, 3, 5)
+                .unwrap()
+                .iter()
+                .map(|s| s.data.len())
+                .sum()
+        };
+        assert_eq!(total(RedundancyMode::Efficient), 5 * 1000);
+        assert!(total(RedundancyMode::Confidential) >= 5 * 3000);
+        // Key share and nonce/tag overhead on top of the Reed-Solomon size.
+        assert!(total(RedundancyMode::Hybrid) < 5 * 1100);
+    }
+}
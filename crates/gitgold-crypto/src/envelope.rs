@@ -0,0 +1,269 @@
+use gitgold_core::error::ShamirError;
+
+use crate::hash::sha256;
+use crate::shamir::{Share, ShareScheme, BLOCK_SIZE, PAYLOAD_SIZE};
+
+/// Leading bytes of every encoded share.
+pub const SHARE_MAGIC: &[u8; 4] = b"GGSH";
+
+/// Current share format version. Version 1 lacked `block_payload`.
+pub const SHARE_FORMAT_VERSION: u8 = 2;
+
+const HEADER_LEN: usize = 31;
+const V1_HEADER_LEN: usize = 30;
+const CHECKSUM_LEN: usize = 32;
+
+impl ShareScheme {
+    /// Stable numeric id used in the binary encoding.
+    pub fn id(self) -> u8 {
+        match self {
+            ShareScheme::Prime256 => 0,
+            ShareScheme::Gf256 => 1,
+            ShareScheme::ReedSolomon => 2,
+            ShareScheme::Ristretto255 => 3,
+            ShareScheme::Hybrid => 4,
+        }
+    }
+
+    /// Inverse of [`ShareScheme::id`].
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(ShareScheme::Prime256),
+            1 => Some(ShareScheme::Gf256),
+            2 => Some(ShareScheme::ReedSolomon),
+            3 => Some(ShareScheme::Ristretto255),
+            4 => Some(ShareScheme::Hybrid),
+            _ => None,
+        }
+    }
+
+    /// Bytes per encoded block: a field element for `Prime256`, a value and
+    /// blinding scalar for `Ristretto255`, one byte otherwise.
+    pub fn block_size(self) -> usize {
+        match self {
+            ShareScheme::Prime256 => 32,
+            ShareScheme::Ristretto255 => 64,
+            ShareScheme::Gf256 | ShareScheme::ReedSolomon | ShareScheme::Hybrid => 1,
+        }
+    }
+}
+
+impl Share {
+    /// Number of scheme blocks in this share's data.
+    pub fn block_count(&self) -> usize {
+        self.data.len() / self.scheme.block_size()
+    }
+
+    /// Encode into the versioned, checksummed binary format used for
+    /// persistence and the wire. Layout (integers big-endian):
+    ///
+    /// ```text
+    /// magic "GGSH" | version u8 | scheme u8 | k u32 | n u32 | share id u32
+    /// | secret_len u64 | block_count u32 | block_payload u8 | data
+    /// | SHA-256(all preceding bytes)
+    /// ```
+    ///
+    /// Fails if the data is not whole blocks or has more blocks than the
+    /// header can count, so the header always agrees with the data.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ShamirError> {
+        let block_size = self.scheme.block_size();
+        if !self.data.len().is_multiple_of(block_size) {
+            return Err(ShamirError::InvalidEncoding(format!(
+                "{} bytes of share data is not whole {block_size}-byte blocks",
+                self.data.len()
+            )));
+        }
+        let block_count = u32::try_from(self.block_count()).map_err(|_| {
+            ShamirError::InvalidEncoding(format!(
+                "{} blocks exceed the header's block count",
+                self.block_count()
+            ))
+        })?;
+
+        let mut out = Vec::with_capacity(HEADER_LEN + self.data.len() + CHECKSUM_LEN);
+        out.extend_from_slice(SHARE_MAGIC);
+        out.push(SHARE_FORMAT_VERSION);
+        out.push(self.scheme.id());
+        out.extend_from_slice(&self.k.to_be_bytes());
+        out.extend_from_slice(&self.n.to_be_bytes());
+        out.extend_from_slice(&self.id.to_be_bytes());
+        out.extend_from_slice(&self.secret_len.to_be_bytes());
+        out.extend_from_slice(&block_count.to_be_bytes());
+        out.push(self.block_payload);
+        out.extend_from_slice(&self.data);
+        let checksum = sha256(&out);
+        out.extend_from_slice(&checksum);
+        Ok(out)
+    }
+
+    /// Decode and verify a share produced by [`Share::to_bytes`].
+    /// Version 1 shares are still accepted.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ShamirError> {
+        let too_short = || {
+            ShamirError::InvalidEncoding(format!(
+                "{} bytes is shorter than the share header",
+                bytes.len()
+            ))
+        };
+        if bytes.len() < V1_HEADER_LEN + CHECKSUM_LEN {
+            return Err(too_short());
+        }
+        if &bytes[..4] != SHARE_MAGIC {
+            return Err(ShamirError::InvalidEncoding("bad magic".to_string()));
+        }
+        let header_len = match bytes[4] {
+            1 => V1_HEADER_LEN,
+            SHARE_FORMAT_VERSION => HEADER_LEN,
+            other => return Err(ShamirError::UnsupportedVersion(other)),
+        };
+        if bytes.len() < header_len + CHECKSUM_LEN {
+            return Err(too_short());
+        }
+
+        let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if sha256(body) != checksum {
+            return Err(ShamirError::ChecksumMismatch);
+        }
+
+        let scheme = ShareScheme::from_id(bytes[5]).ok_or_else(|| {
+            ShamirError::InvalidEncoding(format!("unknown scheme id {}", bytes[5]))
+        })?;
+        let u32_at = |pos: usize| u32::from_be_bytes(bytes[pos..pos + 4].try_into().unwrap());
+        let k = u32_at(6);
+        let n = u32_at(10);
+        let id = u32_at(14);
+        let secret_len = u64::from_be_bytes(bytes[18..26].try_into().unwrap());
+        let block_count = u32_at(26) as usize;
+        // Version 1 Prime256 shares carry full 32-byte blocks.
+        let block_payload = match (header_len, scheme) {
+            (V1_HEADER_LEN, ShareScheme::Prime256) => BLOCK_SIZE as u8,
+            (V1_HEADER_LEN, _) => 0,
+            _ => bytes[30],
+        };
+        if scheme == ShareScheme::Prime256
+            && ![PAYLOAD_SIZE, BLOCK_SIZE].contains(&(block_payload as usize))
+        {
+            return Err(ShamirError::InvalidEncoding(format!(
+                "unsupported Prime256 block payload of {block_payload} bytes"
+            )));
+        }
+
+        let data = &body[header_len..];
+        if data.len() != block_count * scheme.block_size() {
+            return Err(ShamirError::InvalidEncoding(format!(
+                "header declares {block_count} blocks but data is {} bytes",
+                data.len()
+            )));
+        }
+
+        Ok(Share {
+            id,
+            data: data.to_vec(),
+            scheme,
+            k,
+            n,
+            secret_len,
+            block_payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::{reconstruct, split, split_with};
+
+    #[test]
+    fn test_roundtrip_all_schemes() {
+        let mut secret = b"trailing zeros matter\0\0\0".to_vec();
+        secret.extend_from_slice(&[0xff; 40]);
+        for scheme in [
+            ShareScheme::Prime256,
+            ShareScheme::Gf256,
+            ShareScheme::ReedSolomon,
+            ShareScheme::Ristretto255,
+            ShareScheme::Hybrid,
+        ] {
+            let shares = split_with(&secret, 3, 5, scheme).unwrap();
+            let decoded: Vec<Share> = shares
+                .iter()
+                .map(|s| Share::from_bytes(&s.to_bytes().unwrap()).unwrap())
+                .collect();
+            assert_eq!(decoded[1].id, 2);
+            assert_eq!((decoded[1].k, decoded[1].n), (3, 5));
+            assert_eq!(decoded[1].secret_len, secret.len() as u64);
+            assert_eq!(reconstruct(&decoded[2..], 3).unwrap(), secret);
+        }
+    }
+
+    #[test]
+    fn test_header_layout() {
+        let shares = split(&[1u8; 40], 2, 3).unwrap();
+        let bytes = shares[0].to_bytes().unwrap();
+        assert_eq!(&bytes[..4], b"GGSH");
+        assert_eq!(bytes[4], SHARE_FORMAT_VERSION);
+        assert_eq!(bytes[5], ShareScheme::Prime256.id());
+        assert_eq!(u32::from_be_bytes(bytes[26..30].try_into().unwrap()), 2);
+        assert_eq!(bytes.len(), HEADER_LEN + 64 + CHECKSUM_LEN);
+    }
+
+    #[test]
+    fn test_v1_shares_decode_as_full_blocks() {
+        // Re-encode a share as version 1: no block_payload byte.
+        let shares = crate::shamir::split_field_elements(&[0x42; 64], 2, 3).unwrap();
+        let v2 = shares[0].to_bytes().unwrap();
+        let mut v1 = v2[..HEADER_LEN + 64].to_vec();
+        v1[4] = 1;
+        v1.remove(V1_HEADER_LEN);
+        let checksum = sha256(&v1);
+        v1.extend_from_slice(&checksum);
+
+        let decoded: Vec<Share> = [v1, shares[1].to_bytes().unwrap()]
+            .iter()
+            .map(|bytes| Share::from_bytes(bytes).unwrap())
+            .collect();
+        assert_eq!(decoded[0].block_payload as usize, BLOCK_SIZE);
+        assert_eq!(decoded[0].data, shares[0].data);
+        assert_eq!(reconstruct(&decoded, 2).unwrap(), [0x42; 64]);
+    }
+
+    #[test]
+    fn test_partial_block_not_encoded() {
+        let mut shares = split(b"whole blocks only", 2, 3).unwrap();
+        shares[0].data.pop();
+        assert!(matches!(
+            shares[0].to_bytes(),
+            Err(ShamirError::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_corruption_detected() {
+        let shares = split(b"checksummed", 2, 3).unwrap();
+        let mut bytes = shares[0].to_bytes().unwrap();
+        bytes[HEADER_LEN + 3] ^= 0x01;
+        assert!(matches!(
+            Share::from_bytes(&bytes),
+            Err(ShamirError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let shares = split(b"versioned", 2, 3).unwrap();
+        let mut bytes = shares[0].to_bytes().unwrap();
+        bytes[4] = 99;
+        assert!(matches!(
+            Share::from_bytes(&bytes),
+            Err(ShamirError::UnsupportedVersion(99))
+        ));
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        assert!(matches!(
+            Share::from_bytes(b"GGSH"),
+            Err(ShamirError::InvalidEncoding(_))
+        ));
+    }
+}
@@ -1,7 +1,18 @@
 use num_bigint::BigUint;
-use num_traits::{One, Zero};
+use rand::Rng;
 use std::ops::{Add, Div, Mul, Sub};
 
+/// The prime modulus p = 2^256 - 189 as little-endian 64-bit limbs.
+const P: [u64; 4] = [
+    0xFFFF_FFFF_FFFF_FF43,
+    0xFFFF_FFFF_FFFF_FFFF,
+    0xFFFF_FFFF_FFFF_FFFF,
+    0xFFFF_FFFF_FFFF_FFFF,
+];
+
+/// 2^256 mod p. Used to fold the high half of a product back into range.
+const C: u64 = 189;
+
 /// The prime modulus for GF(p): p = 2^256 - 189
 fn prime() -> BigUint {
     let two = BigUint::from(2u32);
@@ -9,133 +20,262 @@ fn prime() -> BigUint {
 }
 
 /// An element in the finite field GF(p) where p = 2^256 - 189.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Stored as four little-endian 64-bit limbs, always fully reduced.
+/// Add, Sub, Mul and inversion run in constant time with respect to
+/// the operand values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct FieldElement {
-    value: BigUint,
-    modulus: BigUint,
+    limbs: [u64; 4],
 }
 
 impl FieldElement {
     /// Create a new field element, reducing value mod p.
     pub fn new(value: BigUint) -> Self {
-        let modulus = prime();
-        let value = value % &modulus;
-        Self { value, modulus }
+        let reduced = value % prime();
+        let mut limbs = [0u64; 4];
+        for (limb, digit) in limbs.iter_mut().zip(reduced.iter_u64_digits()) {
+            *limb = digit;
+        }
+        Self { limbs }
     }
 
     /// Create a field element from a u64.
     pub fn from_u64(v: u64) -> Self {
-        Self::new(BigUint::from(v))
+        Self {
+            limbs: [v, 0, 0, 0],
+        }
     }
 
     /// Create the zero element.
     pub fn zero() -> Self {
-        Self::new(BigUint::zero())
+        Self { limbs: [0; 4] }
     }
 
     /// Create the one element.
     pub fn one() -> Self {
-        Self::new(BigUint::one())
+        Self::from_u64(1)
+    }
+
+    /// Sample a uniformly random field element.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        // Rejection sampling: a random 256-bit value is >= p with
+        // probability 189 / 2^256, so this loop practically never repeats.
+        loop {
+            let limbs: [u64; 4] = [rng.gen(), rng.gen(), rng.gen(), rng.gen()];
+            let (_, borrow) = sub_limbs(&limbs, &P);
+            if borrow == 1 {
+                return Self { limbs };
+            }
+        }
     }
 
     /// Create a field element from big-endian bytes.
     pub fn from_bytes_be(bytes: &[u8]) -> Self {
-        Self::new(BigUint::from_bytes_be(bytes))
+        if bytes.len() > 32 {
+            return Self::new(BigUint::from_bytes_be(bytes));
+        }
+        let mut buf = [0u8; 32];
+        buf[32 - bytes.len()..].copy_from_slice(bytes);
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let start = 32 - (i + 1) * 8;
+            *limb = u64::from_be_bytes(buf[start..start + 8].try_into().unwrap());
+        }
+        Self {
+            limbs: reduce_once(limbs, 0),
+        }
     }
 
     /// Export to big-endian bytes, zero-padded to 32 bytes.
     pub fn to_bytes_be(&self) -> Vec<u8> {
-        let bytes = self.value.to_bytes_be();
-        if bytes.len() >= 32 {
-            bytes[bytes.len() - 32..].to_vec()
-        } else {
-            let mut padded = vec![0u8; 32 - bytes.len()];
-            padded.extend_from_slice(&bytes);
-            padded
+        let mut bytes = Vec::with_capacity(32);
+        for limb in self.limbs.iter().rev() {
+            bytes.extend_from_slice(&limb.to_be_bytes());
         }
+        bytes
+    }
+
+    /// Returns the value as a BigUint.
+    pub fn value(&self) -> BigUint {
+        BigUint::from_bytes_be(&self.to_bytes_be())
+    }
+
+    /// Whether this is the zero element.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().fold(0, |acc, l| acc | l) == 0
     }
 
-    /// Returns the underlying BigUint value.
-    pub fn value(&self) -> &BigUint {
-        &self.value
+    /// Square this element.
+    pub fn square(&self) -> Self {
+        self * self
     }
 
     /// Modular multiplicative inverse via Fermat's little theorem: a^(p-2) mod p.
     pub fn inv(&self) -> Self {
-        assert!(!self.value.is_zero(), "cannot invert zero");
-        let exp = &self.modulus - BigUint::from(2u32);
-        let result = self.value.modpow(&exp, &self.modulus);
-        Self {
-            value: result,
-            modulus: self.modulus.clone(),
+        assert!(!self.is_zero(), "cannot invert zero");
+        // p - 2 = 2^256 - 191; the exponent is public, so a fixed
+        // square-and-multiply schedule leaks nothing about `self`.
+        let mut exp = P;
+        exp[0] -= 2;
+        let mut result = Self::one();
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                result = result.square();
+                if (limb >> bit) & 1 == 1 {
+                    result = &result * self;
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Add with carry: returns (a + b + carry, carry_out).
+#[inline(always)]
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Subtract with borrow: returns (a - b - borrow, borrow_out).
+#[inline(always)]
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
+
+/// Multiply-accumulate: returns (acc + a * b + carry) split into (low, high).
+#[inline(always)]
+fn mac(acc: u64, a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = acc as u128 + (a as u128) * (b as u128) + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut out = [0u64; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        (out[i], borrow) = sbb(a[i], b[i], borrow);
+    }
+    (out, borrow)
+}
+
+/// Pick `a` when `mask` is all ones, `b` when it is zero.
+#[inline(always)]
+fn select(mask: u64, a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    std::array::from_fn(|i| (a[i] & mask) | (b[i] & !mask))
+}
+
+/// Reduce `carry * 2^256 + limbs` (known to be < 2p) into [0, p).
+#[inline(always)]
+fn reduce_once(limbs: [u64; 4], carry: u64) -> [u64; 4] {
+    let (diff, borrow) = sub_limbs(&limbs, &P);
+    // Take the difference if the value overflowed 2^256 or is >= p.
+    let mask = 0u64.wrapping_sub(carry | (borrow ^ 1));
+    select(mask, &diff, &limbs)
+}
+
+fn add_elems(a: &FieldElement, b: &FieldElement) -> FieldElement {
+    let mut sum = [0u64; 4];
+    let mut carry = 0;
+    for (out, (x, y)) in sum.iter_mut().zip(a.limbs.iter().zip(&b.limbs)) {
+        (*out, carry) = adc(*x, *y, carry);
+    }
+    FieldElement {
+        limbs: reduce_once(sum, carry),
+    }
+}
+
+fn sub_elems(a: &FieldElement, b: &FieldElement) -> FieldElement {
+    let (diff, borrow) = sub_limbs(&a.limbs, &b.limbs);
+    // On underflow add p back (mod 2^256).
+    let mask = 0u64.wrapping_sub(borrow);
+    let mut out = [0u64; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        (out[i], carry) = adc(diff[i], P[i] & mask, carry);
+    }
+    FieldElement { limbs: out }
+}
+
+fn mul_elems(a: &FieldElement, b: &FieldElement) -> FieldElement {
+    // Schoolbook 4x4 limb product into 8 limbs.
+    let mut wide = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0;
+        for j in 0..4 {
+            (wide[i + j], carry) = mac(wide[i + j], a.limbs[i], b.limbs[j], carry);
         }
+        wide[i + 4] = carry;
+    }
+
+    // Since 2^256 ≡ 189 (mod p): lo + hi * 189, giving a 5-limb result.
+    let mut r = [0u64; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        (r[i], carry) = mac(wide[i], wide[i + 4], C, carry);
+    }
+
+    // Fold the fifth limb (at most 189) once more.
+    let (lo, hi) = mac(0, carry, C, 0);
+    let mut c = 0;
+    (r[0], c) = adc(r[0], lo, c);
+    (r[1], c) = adc(r[1], hi, c);
+    (r[2], c) = adc(r[2], 0, c);
+    (r[3], c) = adc(r[3], 0, c);
+
+    // An overflow here leaves a tiny remainder, so adding 189 cannot carry.
+    let mut c2 = 0;
+    (r[0], c2) = adc(r[0], C & 0u64.wrapping_sub(c), c2);
+    (r[1], c2) = adc(r[1], 0, c2);
+    (r[2], c2) = adc(r[2], 0, c2);
+    (r[3], _) = adc(r[3], 0, c2);
+
+    FieldElement {
+        limbs: reduce_once(r, 0),
     }
 }
 
 impl Add for FieldElement {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
-        let value = (&self.value + &rhs.value) % &self.modulus;
-        Self {
-            value,
-            modulus: self.modulus,
-        }
+        add_elems(&self, &rhs)
     }
 }
 
 impl Add for &FieldElement {
     type Output = FieldElement;
     fn add(self, rhs: Self) -> FieldElement {
-        let value = (&self.value + &rhs.value) % &self.modulus;
-        FieldElement {
-            value,
-            modulus: self.modulus.clone(),
-        }
+        add_elems(self, rhs)
     }
 }
 
 impl Sub for FieldElement {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self {
-        // Add modulus to avoid underflow: (a - b + p) mod p
-        let value = (&self.value + &self.modulus - &rhs.value) % &self.modulus;
-        Self {
-            value,
-            modulus: self.modulus,
-        }
+        sub_elems(&self, &rhs)
     }
 }
 
 impl Sub for &FieldElement {
     type Output = FieldElement;
     fn sub(self, rhs: Self) -> FieldElement {
-        let value = (&self.value + &self.modulus - &rhs.value) % &self.modulus;
-        FieldElement {
-            value,
-            modulus: self.modulus.clone(),
-        }
+        sub_elems(self, rhs)
     }
 }
 
 impl Mul for FieldElement {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self {
-        let value = (&self.value * &rhs.value) % &self.modulus;
-        Self {
-            value,
-            modulus: self.modulus,
-        }
+        mul_elems(&self, &rhs)
     }
 }
 
 impl Mul for &FieldElement {
     type Output = FieldElement;
     fn mul(self, rhs: Self) -> FieldElement {
-        let value = (&self.value * &rhs.value) % &self.modulus;
-        FieldElement {
-            value,
-            modulus: self.modulus.clone(),
-        }
+        mul_elems(self, rhs)
     }
 }
 
@@ -158,13 +298,15 @@ impl Div for &FieldElement {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num_traits::One;
+    use proptest::prelude::*;
 
     #[test]
     fn test_add() {
         let a = FieldElement::from_u64(10);
         let b = FieldElement::from_u64(20);
         let c = a + b;
-        assert_eq!(*c.value(), BigUint::from(30u32));
+        assert_eq!(c.value(), BigUint::from(30u32));
     }
 
     #[test]
@@ -172,7 +314,7 @@ mod tests {
         let a = FieldElement::from_u64(30);
         let b = FieldElement::from_u64(10);
         let c = a - b;
-        assert_eq!(*c.value(), BigUint::from(20u32));
+        assert_eq!(c.value(), BigUint::from(20u32));
     }
 
     #[test]
@@ -182,7 +324,7 @@ mod tests {
         let c = a - b;
         // Should wrap: (5 - 10 + p) mod p = p - 5
         let expected = prime() - BigUint::from(5u32);
-        assert_eq!(*c.value(), expected);
+        assert_eq!(c.value(), expected);
     }
 
     #[test]
@@ -190,15 +332,15 @@ mod tests {
         let a = FieldElement::from_u64(7);
         let b = FieldElement::from_u64(6);
         let c = a * b;
-        assert_eq!(*c.value(), BigUint::from(42u32));
+        assert_eq!(c.value(), BigUint::from(42u32));
     }
 
     #[test]
     fn test_inv() {
         let a = FieldElement::from_u64(7);
-        let a_inv = a.clone().inv();
+        let a_inv = a.inv();
         let product = a * a_inv;
-        assert_eq!(*product.value(), BigUint::one());
+        assert_eq!(product.value(), BigUint::one());
     }
 
     #[test]
@@ -206,7 +348,7 @@ mod tests {
         let a = FieldElement::from_u64(42);
         let b = FieldElement::from_u64(7);
         let c = a / b;
-        assert_eq!(*c.value(), BigUint::from(6u32));
+        assert_eq!(c.value(), BigUint::from(6u32));
     }
 
     #[test]
@@ -229,7 +371,7 @@ mod tests {
     fn test_one_times_x_is_x() {
         let x = FieldElement::from_u64(999);
         let one = FieldElement::one();
-        let result = x.clone() * one;
+        let result = x * one;
         assert_eq!(result, x);
     }
 
@@ -239,6 +381,43 @@ mod tests {
         let p = prime();
         let val = &p + BigUint::from(5u32);
         let elem = FieldElement::new(val);
-        assert_eq!(*elem.value(), BigUint::from(5u32));
+        assert_eq!(elem.value(), BigUint::from(5u32));
+    }
+
+    #[test]
+    fn test_from_bytes_at_and_above_p() {
+        let p_bytes = prime().to_bytes_be();
+        assert!(FieldElement::from_bytes_be(&p_bytes).is_zero());
+        assert_eq!(
+            FieldElement::from_bytes_be(&[0xFF; 32]).value(),
+            BigUint::from(188u32)
+        );
+    }
+
+    #[test]
+    fn test_p_minus_one_squared_is_one() {
+        // (-1)^2 = 1 exercises the largest possible product.
+        let minus_one = FieldElement::zero() - FieldElement::one();
+        assert_eq!(minus_one * minus_one, FieldElement::one());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_ops_match_biguint(a in any::<[u8; 32]>(), b in any::<[u8; 32]>()) {
+            let p = prime();
+            let (fa, fb) = (FieldElement::from_bytes_be(&a), FieldElement::from_bytes_be(&b));
+            let (ba, bb) = (BigUint::from_bytes_be(&a) % &p, BigUint::from_bytes_be(&b) % &p);
+
+            prop_assert_eq!((fa + fb).value(), (&ba + &bb) % &p);
+            prop_assert_eq!((fa - fb).value(), (&ba + &p - &bb) % &p);
+            prop_assert_eq!((fa * fb).value(), (&ba * &bb) % &p);
+        }
+
+        #[test]
+        fn prop_inv_roundtrip(a in any::<[u8; 32]>()) {
+            let fa = FieldElement::from_bytes_be(&a);
+            prop_assume!(!fa.is_zero());
+            prop_assert_eq!(fa * fa.inv(), FieldElement::one());
+        }
     }
 }
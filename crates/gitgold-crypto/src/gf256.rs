@@ -0,0 +1,144 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Reduction polynomial x^8 + x^4 + x^3 + x + 1 (the AES field).
+const POLY: u16 = 0x11B;
+
+/// Exponent table for generator 3, doubled so `EXP[a + b]` needs no mod 255.
+const EXP: [u8; 512] = build_exp();
+
+/// Discrete log table (LOG[0] is unused).
+const LOG: [u8; 256] = build_log();
+
+const fn build_exp() -> [u8; 512] {
+    let mut table = [0u8; 512];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        table[i] = x as u8;
+        table[i + 255] = x as u8;
+        // Multiply by the generator 3 = x + 1.
+        x ^= x << 1;
+        if x & 0x100 != 0 {
+            x ^= POLY;
+        }
+        i += 1;
+    }
+    table
+}
+
+const fn build_log() -> [u8; 256] {
+    let exp = build_exp();
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 255 {
+        table[exp[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+/// An element of GF(2^8), used for byte-wise secret sharing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gf256(pub u8);
+
+impl Gf256 {
+    /// Create the zero element.
+    pub fn zero() -> Self {
+        Self(0)
+    }
+
+    /// Create the one element.
+    pub fn one() -> Self {
+        Self(1)
+    }
+
+    /// Multiplicative inverse via the log table.
+    pub fn inv(self) -> Self {
+        assert!(self.0 != 0, "cannot invert zero");
+        Self(EXP[255 - LOG[self.0 as usize] as usize])
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Add for Gf256 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Sub for Gf256 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Gf256 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        if self.0 == 0 || rhs.0 == 0 {
+            return Self(0);
+        }
+        Self(EXP[LOG[self.0 as usize] as usize + LOG[rhs.0 as usize] as usize])
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div for Gf256 {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Carry-less multiply with reduction, as a table-free reference.
+    fn slow_mul(mut a: u8, mut b: u8) -> u8 {
+        let mut p = 0u8;
+        while b != 0 {
+            if b & 1 != 0 {
+                p ^= a;
+            }
+            let hi = a & 0x80;
+            a <<= 1;
+            if hi != 0 {
+                a ^= (POLY & 0xFF) as u8;
+            }
+            b >>= 1;
+        }
+        p
+    }
+
+    #[test]
+    fn test_mul_matches_reference() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                assert_eq!((Gf256(a) * Gf256(b)).0, slow_mul(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_inv() {
+        for a in 1..=255u8 {
+            assert_eq!(Gf256(a) * Gf256(a).inv(), Gf256::one());
+        }
+    }
+
+    #[test]
+    fn test_add_is_xor() {
+        assert_eq!(Gf256(0x53) + Gf256(0xCA), Gf256(0x99));
+        assert_eq!(Gf256(0x53) - Gf256(0x53), Gf256::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot invert zero")]
+    fn test_inv_zero_panics() {
+        Gf256::zero().inv();
+    }
+}
@@ -0,0 +1,207 @@
+use gitgold_core::error::KeyDerivationError;
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+use crate::keys::KeyPair;
+
+/// Offset marking a hardened child index.
+pub const HARDENED: u32 = 0x8000_0000;
+
+/// BIP44 coin type used in GitGold derivation paths (`"GG"` in ASCII; not
+/// registered in SLIP-0044).
+pub const GITGOLD_COIN_TYPE: u32 = 0x4747;
+
+/// HMAC key for the SLIP-0010 Ed25519 master node.
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// Generate a new English BIP39 mnemonic of `word_count` words from OS
+/// randomness.
+pub fn generate_mnemonic(word_count: usize) -> Result<String, KeyDerivationError> {
+    if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+        return Err(KeyDerivationError::InvalidWordCount(word_count));
+    }
+    // 32 bits of entropy per 3 words.
+    let mut entropy = Zeroizing::new(vec![0u8; word_count / 3 * 4]);
+    OsRng.fill_bytes(&mut entropy);
+    let mnemonic = bip39::Mnemonic::from_entropy(&entropy)
+        .map_err(|e| KeyDerivationError::InvalidMnemonic(e.to_string()))?;
+    Ok(mnemonic.to_string())
+}
+
+/// Validate a BIP39 phrase (word list and checksum) and stretch it into
+/// the 64-byte seed, with an optional `passphrase` ("" for none).
+pub fn mnemonic_to_seed(
+    phrase: &str,
+    passphrase: &str,
+) -> Result<Zeroizing<[u8; 64]>, KeyDerivationError> {
+    let mnemonic = bip39::Mnemonic::parse(phrase)
+        .map_err(|e| KeyDerivationError::InvalidMnemonic(e.to_string()))?;
+    Ok(Zeroizing::new(mnemonic.to_seed(passphrase)))
+}
+
+/// A SLIP-0010 Ed25519 node: a secret key and its chain code.
+///
+/// Ed25519 only supports hardened derivation, so every index is hardened
+/// and there are no public-only parent keys.
+#[derive(Clone)]
+pub struct ExtendedKey {
+    secret: Zeroizing<[u8; 32]>,
+    chain_code: Zeroizing<[u8; 32]>,
+}
+
+impl ExtendedKey {
+    /// Master node for `seed`.
+    pub fn master(seed: &[u8]) -> Self {
+        Self::from_hmac(ED25519_SEED_KEY, &[seed])
+    }
+
+    /// Hardened child `index` (the hardened bit is added if missing).
+    pub fn derive_child(&self, index: u32) -> Self {
+        let index = index | HARDENED;
+        Self::from_hmac(
+            self.chain_code.as_slice(),
+            &[&[0u8], self.secret.as_slice(), &index.to_be_bytes()],
+        )
+    }
+
+    /// Follow a path like `m/44'/18247'/0'/3'`. Every segment must be
+    /// hardened (`'` or `h` suffix).
+    pub fn derive_path(&self, path: &str) -> Result<Self, KeyDerivationError> {
+        let invalid = || KeyDerivationError::InvalidPath(path.to_string());
+        let mut segments = path.split('/');
+        if segments.next() != Some("m") {
+            return Err(invalid());
+        }
+        let mut node = self.clone();
+        for segment in segments {
+            let index = segment
+                .strip_suffix('\'')
+                .or_else(|| segment.strip_suffix('h'))
+                .ok_or_else(invalid)?
+                .parse::<u32>()
+                .map_err(|_| invalid())?;
+            if index >= HARDENED {
+                return Err(invalid());
+            }
+            node = node.derive_child(index);
+        }
+        Ok(node)
+    }
+
+    /// Signing key pair for this node.
+    pub fn key_pair(&self) -> KeyPair {
+        KeyPair::from_bytes(&self.secret)
+    }
+
+    /// Chain code, needed only to derive further children.
+    pub fn chain_code(&self) -> [u8; 32] {
+        *self.chain_code
+    }
+
+    /// Split HMAC-SHA512(key, parts) into secret (left) and chain code (right).
+    fn from_hmac(key: &[u8], parts: &[&[u8]]) -> Self {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts any key length");
+        for part in parts {
+            mac.update(part);
+        }
+        let output = Zeroizing::new(<[u8; 64]>::from(mac.finalize().into_bytes()));
+        let mut secret = Zeroizing::new([0u8; 32]);
+        let mut chain_code = Zeroizing::new([0u8; 32]);
+        secret.copy_from_slice(&output[..32]);
+        chain_code.copy_from_slice(&output[32..]);
+        Self { secret, chain_code }
+    }
+}
+
+/// Path of GitGold account `account`: `m/44'/18247'/{account}'`. Child
+/// keys hang off it at `/{index}'`.
+pub fn account_path(account: u32) -> String {
+    format!("m/44'/{GITGOLD_COIN_TYPE}'/{account}'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BIP39 reference vector: all-zero 128-bit entropy, passphrase "TREZOR".
+    const ABANDON: &str = "abandon abandon abandon abandon abandon abandon \
+                           abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_bip39_reference_seed() {
+        let seed = mnemonic_to_seed(ABANDON, "TREZOR").unwrap();
+        assert_eq!(
+            hex::encode(seed.as_slice()),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f\
+             09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+
+    #[test]
+    fn test_slip10_vector_1() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedKey::master(&seed);
+        assert_eq!(
+            hex::encode(master.chain_code()),
+            "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb"
+        );
+        assert_eq!(
+            hex::encode(master.key_pair().secret_bytes()),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+
+        let child = master.derive_path("m/0'").unwrap();
+        assert_eq!(
+            hex::encode(child.chain_code()),
+            "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69"
+        );
+        assert_eq!(
+            hex::encode(child.key_pair().secret_bytes()),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+    }
+
+    #[test]
+    fn test_generated_mnemonic_roundtrips() {
+        for words in [12, 24] {
+            let phrase = generate_mnemonic(words).unwrap();
+            assert_eq!(phrase.split_whitespace().count(), words);
+            assert!(mnemonic_to_seed(&phrase, "").is_ok());
+        }
+        assert!(matches!(
+            generate_mnemonic(13),
+            Err(KeyDerivationError::InvalidWordCount(13))
+        ));
+    }
+
+    #[test]
+    fn test_bad_checksum_rejected() {
+        let broken = ABANDON.replace("about", "abandon");
+        assert!(matches!(
+            mnemonic_to_seed(&broken, ""),
+            Err(KeyDerivationError::InvalidMnemonic(_))
+        ));
+    }
+
+    #[test]
+    fn test_path_parsing() {
+        let master = ExtendedKey::master(&[7u8; 64]);
+        let a = master.derive_path("m/44'/18247h/0'").unwrap();
+        let b = master.derive_child(44).derive_child(18247).derive_child(0);
+        assert_eq!(a.key_pair().address(), b.key_pair().address());
+        assert_eq!(account_path(0), "m/44'/18247'/0'");
+
+        for bad in ["44'/0'", "m/44", "m/x'", "m/2147483648'"] {
+            assert!(
+                matches!(
+                    master.derive_path(bad),
+                    Err(KeyDerivationError::InvalidPath(_))
+                ),
+                "{bad}"
+            );
+        }
+    }
+}
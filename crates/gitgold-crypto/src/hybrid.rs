@@ -0,0 +1,200 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use gitgold_core::error::ShamirError;
+use rand::thread_rng;
+
+use crate::codec::ShareCodec;
+use crate::field::FieldElement;
+use crate::gf256::Gf256;
+use crate::reed_solomon;
+use crate::shamir::{
+    self, interpolate_prime256, truncate_to_secret_len, Share, ShareScheme, BLOCK_SIZE,
+};
+
+/// Bytes of the `Prime256` key share at the front of every hybrid share.
+const KEY_SHARE_LEN: usize = BLOCK_SIZE;
+
+/// AES-GCM authentication tag appended to the ciphertext.
+const TAG_LEN: usize = 16;
+
+/// Encrypt-then-share (SSMS): the chunk is encrypted under a fresh
+/// AES-256-GCM key, the ciphertext is Reed-Solomon coded and only the
+/// 32-byte key is Shamir-shared.
+///
+/// Each share is a key share followed by a ciphertext shard, about
+/// `len / k + 48` bytes. Fewer than k shares learn nothing about the key,
+/// and so nothing about the chunk short of breaking AES; the GCM tag also
+/// catches corrupted shards on decode.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hybrid;
+
+impl ShareCodec for Hybrid {
+    fn encode(&self, data: &[u8], k: usize, n: usize) -> Result<Vec<Share>, ShamirError> {
+        encode(data, k, n)
+    }
+
+    fn decode(&self, shares: &[Share], k: usize) -> Result<Vec<u8>, ShamirError> {
+        shamir::reconstruct(shares, k)
+    }
+}
+
+/// Encrypt `data` and encode it into `n` hybrid shares, any `k` of which
+/// recover it.
+pub fn encode(data: &[u8], k: usize, n: usize) -> Result<Vec<Share>, ShamirError> {
+    if data.is_empty() {
+        return Err(ShamirError::EmptySecret);
+    }
+
+    // A random field element is a uniform 32-byte key that can be shared
+    // as a single Prime256 block.
+    let key = FieldElement::random(&mut thread_rng()).to_bytes_be();
+    let ciphertext = cipher(&key)
+        .encrypt(&nonce(), data)
+        .expect("chunk within AES-GCM length limit");
+
+    let shards = reed_solomon::encode(&ciphertext, k, n)?;
+    let key_shares = shamir::split_field_elements(&key, k, n)?;
+    Ok(key_shares
+        .into_iter()
+        .zip(shards)
+        .map(|(key_share, shard)| {
+            let mut share_data = key_share.data;
+            share_data.extend_from_slice(&shard.data);
+            Share {
+                id: shard.id,
+                data: share_data,
+                scheme: ShareScheme::Hybrid,
+                k: k as u32,
+                n: n as u32,
+                secret_len: data.len() as u64,
+                block_payload: 0,
+            }
+        })
+        .collect())
+}
+
+/// Recover and decrypt the chunk from `selected`, whose ids `key_weights`
+/// (Lagrange weights) and `shard_inverse` (Reed-Solomon decoding matrix)
+/// were built for.
+pub(crate) fn decode_with(
+    selected: &[&Share],
+    key_weights: &[FieldElement],
+    shard_inverse: Option<&[Vec<Gf256>]>,
+) -> Result<Vec<u8>, ShamirError> {
+    let parts = selected
+        .iter()
+        .map(|s| split_parts(s))
+        .collect::<Result<Vec<_>, _>>()?;
+    let key_shares: Vec<&Share> = parts.iter().map(|(key, _)| key).collect();
+    let shards: Vec<&Share> = parts.iter().map(|(_, shard)| shard).collect();
+
+    let key = interpolate_prime256(&key_shares, key_weights, false);
+    let mut ciphertext = reed_solomon::decode_with(&shards, shard_inverse);
+    truncate_to_secret_len(&mut ciphertext, shards[0].secret_len)?;
+
+    cipher(&key)
+        .decrypt(&nonce(), ciphertext.as_slice())
+        .map_err(|_| ShamirError::DecryptionFailed)
+}
+
+/// Split a hybrid share into its `Prime256` key share and its
+/// `ReedSolomon` ciphertext shard.
+pub(crate) fn split_parts(share: &Share) -> Result<(Share, Share), ShamirError> {
+    if share.secret_len == 0 || share.data.len() <= KEY_SHARE_LEN {
+        return Err(ShamirError::InvalidEncoding(format!(
+            "hybrid share {} is missing its key share or ciphertext",
+            share.id
+        )));
+    }
+    let (key, shard) = share.data.split_at(KEY_SHARE_LEN);
+    let part = |data: &[u8], scheme, secret_len| Share {
+        id: share.id,
+        data: data.to_vec(),
+        scheme,
+        k: share.k,
+        n: share.n,
+        secret_len,
+        block_payload: BLOCK_SIZE as u8,
+    };
+    Ok((
+        part(key, ShareScheme::Prime256, KEY_SHARE_LEN as u64),
+        part(
+            shard,
+            ShareScheme::ReedSolomon,
+            share.secret_len + TAG_LEN as u64,
+        ),
+    ))
+}
+
+fn cipher(key: &[u8]) -> Aes256Gcm {
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+}
+
+/// Every key encrypts exactly one chunk, so a constant nonce never repeats
+/// under the same key.
+fn nonce() -> Nonce<aes_gcm::aead::consts::U12> {
+    Nonce::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::{reconstruct, split_with};
+
+    #[test]
+    fn test_roundtrip_any_k_subset() {
+        let data: Vec<u8> = (0..10_000).map(|i| (i * 31 % 256) as u8).collect();
+        let shares = split_with(&data, 3, 5, ShareScheme::Hybrid).unwrap();
+        for subset in [[0, 1, 2], [0, 2, 4], [2, 3, 4], [1, 3, 4]] {
+            let picked: Vec<Share> = subset.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(reconstruct(&picked, 3).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_shares_are_about_one_kth() {
+        let data = vec![0xA5u8; 30_000];
+        let shares = encode(&data, 3, 5).unwrap();
+        let expected = KEY_SHARE_LEN + (data.len() + TAG_LEN).div_ceil(3);
+        assert!(shares.iter().all(|s| s.data.len() == expected));
+        // Same plaintext, fresh key: nothing repeats across encodings.
+        let again = encode(&data, 3, 5).unwrap();
+        assert_ne!(shares[0].data, again[0].data);
+    }
+
+    #[test]
+    fn test_fewer_than_k_rejected() {
+        let shares = encode(b"below threshold", 3, 5).unwrap();
+        assert!(matches!(
+            reconstruct(&shares[..2], 3),
+            Err(ShamirError::NotEnoughShares { have: 2, need: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        let mut shares = encode(b"integrity comes for free with GCM", 2, 4).unwrap();
+        let last = shares[3].data.len() - 1;
+        shares[3].data[last] ^= 1;
+        assert!(matches!(
+            reconstruct(&shares[2..], 2),
+            Err(ShamirError::DecryptionFailed)
+        ));
+
+        // A corrupted key share is caught the same way.
+        shares[1].data[0] ^= 1;
+        assert!(matches!(
+            reconstruct(&shares[..2], 2),
+            Err(ShamirError::DecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_parameter_errors() {
+        assert!(matches!(encode(b"", 2, 3), Err(ShamirError::EmptySecret)));
+        assert!(matches!(
+            encode(b"x", 2, 256),
+            Err(ShamirError::TooManyShares { n: 256, max: 255 })
+        ));
+    }
+}
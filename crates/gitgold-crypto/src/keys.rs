@@ -1,7 +1,9 @@
-use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
 use gitgold_core::types::Address;
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
+use zeroize::ZeroizeOnDrop;
 
 use crate::hash::sha256_hex;
 
@@ -11,6 +13,9 @@ pub struct KeyPair {
     signing_key: SigningKey,
 }
 
+// `SigningKey` wipes its secret bytes when dropped.
+impl ZeroizeOnDrop for KeyPair {}
+
 /// Serializable public key wrapper.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublicKey {
@@ -49,7 +54,8 @@ impl KeyPair {
         signature.to_bytes().to_vec()
     }
 
-    /// Export the secret key bytes.
+    /// Export the secret key bytes. The copy is not zeroized; wrap it in
+    /// `zeroize::Zeroizing` if it outlives a single use.
     pub fn secret_bytes(&self) -> [u8; 32] {
         self.signing_key.to_bytes()
     }
@@ -57,18 +63,95 @@ impl KeyPair {
 
 impl PublicKey {
     /// Verify a signature against this public key.
+    ///
+    /// Keys and `R` values outside the prime-order subgroup are rejected
+    /// (see `parse`), so this accepts exactly what [`verify_batch`] does.
     pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
-        let Ok(verifying_key) = VerifyingKey::from_bytes(
-            self.bytes.as_slice().try_into().unwrap_or(&[0u8; 32]),
-        ) else {
-            return false;
-        };
-        let Ok(sig_bytes): Result<&[u8; 64], _> = signature.try_into() else {
+        let Some((verifying_key, sig)) = parse(&self.bytes, signature) else {
             return false;
         };
-        let sig = Signature::from_bytes(sig_bytes);
-        verifying_key.verify(message, &sig).is_ok()
+        verifying_key.verify_strict(message, &sig).is_ok()
+    }
+}
+
+/// One signature to check with [`verify_batch`].
+#[derive(Debug, Clone, Copy)]
+pub struct BatchItem<'a> {
+    pub pubkey: &'a PublicKey,
+    pub message: &'a [u8],
+    pub signature: &'a [u8],
+}
+
+/// Verify many signatures at once.
+///
+/// Well-formed items are checked together with a single randomized
+/// multi-scalar multiplication, which is several times faster than
+/// verifying them one by one. If the batch fails, each item is re-checked
+/// with [`PublicKey::verify`] to find the culprits.
+///
+/// The batch equation weights each item by a random scalar, so a
+/// small-order component in a key or `R` drops out whenever its weight is
+/// a multiple of its order: a signature the single check rejects would
+/// pass some of the time. Such items are treated as malformed and never
+/// reach the batch, which keeps the two paths in agreement.
+///
+/// Returns the indices of all invalid items (malformed keys or
+/// signatures included), in ascending order.
+pub fn verify_batch(items: &[BatchItem]) -> Result<(), Vec<usize>> {
+    let mut invalid = Vec::new();
+    let mut indices = Vec::with_capacity(items.len());
+    let mut messages = Vec::with_capacity(items.len());
+    let mut signatures = Vec::with_capacity(items.len());
+    let mut keys = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        match parse(&item.pubkey.bytes, item.signature) {
+            Some((key, sig)) => {
+                indices.push(i);
+                messages.push(item.message);
+                signatures.push(sig);
+                keys.push(key);
+            }
+            None => invalid.push(i),
+        }
+    }
+
+    if ed25519_dalek::verify_batch(&messages, &signatures, &keys).is_err() {
+        invalid.extend(
+            indices
+                .into_iter()
+                .filter(|&i| !items[i].pubkey.verify(items[i].message, items[i].signature)),
+        );
+        invalid.sort_unstable();
+    }
+
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid)
+    }
+}
+
+/// Decode a raw public key and signature, or `None` if either is malformed.
+///
+/// The key and the signature's `R` must be canonically encoded points in
+/// the prime-order subgroup. Honest keys and signatures always are; on
+/// anything else batch and single verification can disagree.
+fn parse(pubkey: &[u8], signature: &[u8]) -> Option<(VerifyingKey, Signature)> {
+    let verifying_key = VerifyingKey::from_bytes(pubkey.try_into().ok()?).ok()?;
+    let sig_bytes: &[u8; 64] = signature.try_into().ok()?;
+    let r = CompressedEdwardsY(sig_bytes[..32].try_into().ok()?);
+    let r_point = r.decompress()?;
+    if r_point.compress() != r
+        || !in_prime_subgroup(&r_point)
+        || !in_prime_subgroup(&verifying_key.to_edwards())
+    {
+        return None;
     }
+    Some((verifying_key, Signature::from_bytes(sig_bytes)))
+}
+
+fn in_prime_subgroup(point: &EdwardsPoint) -> bool {
+    !point.is_small_order() && point.is_torsion_free()
 }
 
 #[cfg(test)]
@@ -109,6 +192,103 @@ mod tests {
         assert!(addr.0.chars().all(|c| c.is_ascii_hexdigit()));
     }
 
+    #[test]
+    fn test_verify_batch() {
+        let kps: Vec<_> = (0..8).map(|_| KeyPair::generate()).collect();
+        let pks: Vec<_> = kps.iter().map(KeyPair::public_key).collect();
+        let messages: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i; 40]).collect();
+        let mut sigs: Vec<_> = kps.iter().zip(&messages).map(|(kp, m)| kp.sign(m)).collect();
+        fn items<'a>(
+            pks: &'a [PublicKey],
+            messages: &'a [Vec<u8>],
+            sigs: &'a [Vec<u8>],
+        ) -> Vec<BatchItem<'a>> {
+            (0..pks.len())
+                .map(|i| BatchItem {
+                    pubkey: &pks[i],
+                    message: &messages[i],
+                    signature: &sigs[i],
+                })
+                .collect()
+        }
+        assert!(verify_batch(&items(&pks, &messages, &sigs)).is_ok());
+        assert!(verify_batch(&[]).is_ok());
+
+        // One forged signature and one truncated one are pinpointed.
+        sigs[2] = kps[3].sign(&messages[2]);
+        sigs[6].truncate(10);
+        assert_eq!(
+            verify_batch(&items(&pks, &messages, &sigs)),
+            Err(vec![2, 6])
+        );
+    }
+
+    /// Sign `message` under the key `a * B`, adding the order-8 point `T`
+    /// to `R` (when `torsion_r`) or to the key. The single check always
+    /// sees `T`; the batch equation misses it when the item's weight is a
+    /// multiple of 8.
+    fn torsion_signature(message: &[u8], torsion_r: bool) -> (PublicKey, Vec<u8>) {
+        use curve25519_dalek::constants::{ED25519_BASEPOINT_POINT, EIGHT_TORSION};
+        use curve25519_dalek::scalar::Scalar;
+        use sha2::{Digest, Sha512};
+
+        let a = Scalar::from_bytes_mod_order([7u8; 32]);
+        let r = Scalar::from_bytes_mod_order([9u8; 32]);
+        let t = EIGHT_TORSION[1];
+        let (big_a, big_r) = if torsion_r {
+            (a * ED25519_BASEPOINT_POINT, r * ED25519_BASEPOINT_POINT + t)
+        } else {
+            (a * ED25519_BASEPOINT_POINT + t, r * ED25519_BASEPOINT_POINT)
+        };
+        let (big_a, big_r) = (big_a.compress(), big_r.compress());
+        let h = Scalar::from_hash(
+            Sha512::new()
+                .chain_update(big_r.as_bytes())
+                .chain_update(big_a.as_bytes())
+                .chain_update(message),
+        );
+        let s = r + h * a;
+        let mut sig = big_r.as_bytes().to_vec();
+        sig.extend_from_slice(s.as_bytes());
+        (
+            PublicKey {
+                bytes: big_a.as_bytes().to_vec(),
+            },
+            sig,
+        )
+    }
+
+    #[test]
+    fn test_single_and_batch_agree_on_torsion() {
+        for torsion_r in [true, false] {
+            // The batch weights are derived from the inputs, so a sender
+            // can grind the message until the bare batch equation accepts
+            // (about one try in eight)...
+            let (message, pk, sig) = (0u32..)
+                .map(|i| {
+                    let message = format!("crafted-{i}").into_bytes();
+                    let (pk, sig) = torsion_signature(&message, torsion_r);
+                    (message, pk, sig)
+                })
+                .find(|(message, pk, sig)| {
+                    let key = VerifyingKey::from_bytes(pk.bytes.as_slice().try_into().unwrap())
+                        .unwrap();
+                    let sig = Signature::from_bytes(sig.as_slice().try_into().unwrap());
+                    ed25519_dalek::verify_batch(&[message.as_slice()], &[sig], &[key]).is_ok()
+                })
+                .unwrap();
+
+            // ...but neither of our paths accepts it.
+            assert!(!pk.verify(&message, &sig));
+            let item = BatchItem {
+                pubkey: &pk,
+                message: &message,
+                signature: &sig,
+            };
+            assert_eq!(verify_batch(&[item]), Err(vec![0]));
+        }
+    }
+
     #[test]
     fn test_from_bytes_roundtrip() {
         let kp1 = KeyPair::generate();
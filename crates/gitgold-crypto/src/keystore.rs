@@ -0,0 +1,515 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use gitgold_core::error::KeystoreError;
+use gitgold_core::types::Address;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::hash::sha256_hex;
+use crate::keys::KeyPair;
+
+/// Current keystore file version.
+pub const KEYSTORE_VERSION: u32 = 1;
+
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER_ALGORITHM: &str = "aes-256-gcm";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Argon2id cost parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB.
+    pub memory_kib: u32,
+    /// Number of passes.
+    pub iterations: u32,
+    /// Degree of parallelism (lanes).
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    /// OWASP's minimum recommendation for Argon2id: 19 MiB, 2 passes.
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// Cheapest KDF cost accepted: 8 MiB, one pass. Keeps a keystore from
+/// being written, or edited, down to a trivially brute-forced cost.
+pub const MIN_KDF_PARAMS: KdfParams = KdfParams {
+    memory_kib: 8 * 1024,
+    iterations: 1,
+    parallelism: 1,
+};
+
+/// Costliest KDF cost accepted: 1 GiB, 10 passes, 16 lanes. The cost is
+/// read from the file before the password can be checked, so without a
+/// ceiling a crafted keystore could make decryption exhaust memory or run
+/// for hours.
+pub const MAX_KDF_PARAMS: KdfParams = KdfParams {
+    memory_kib: 1024 * 1024,
+    iterations: 10,
+    parallelism: 16,
+};
+
+impl KdfParams {
+    /// Check each parameter lies between [`MIN_KDF_PARAMS`] and
+    /// [`MAX_KDF_PARAMS`].
+    pub fn check(&self) -> Result<(), KeystoreError> {
+        for (name, value, min, max) in [
+            (
+                "memory_kib",
+                self.memory_kib,
+                MIN_KDF_PARAMS.memory_kib,
+                MAX_KDF_PARAMS.memory_kib,
+            ),
+            (
+                "iterations",
+                self.iterations,
+                MIN_KDF_PARAMS.iterations,
+                MAX_KDF_PARAMS.iterations,
+            ),
+            (
+                "parallelism",
+                self.parallelism,
+                MIN_KDF_PARAMS.parallelism,
+                MAX_KDF_PARAMS.parallelism,
+            ),
+        ] {
+            if !(min..=max).contains(&value) {
+                return Err(KeystoreError::InvalidKdfParams(format!(
+                    "{name} {value} outside {min}..={max}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Key derivation section of a keystore file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfSection {
+    pub algorithm: String,
+    #[serde(flatten)]
+    pub params: KdfParams,
+    /// Hex-encoded random salt.
+    pub salt: String,
+}
+
+/// Encryption section of a keystore file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherSection {
+    pub algorithm: String,
+    /// Hex-encoded AES-GCM nonce.
+    pub nonce: String,
+    /// Hex-encoded encrypted secret key followed by the GCM tag.
+    pub ciphertext: String,
+}
+
+/// Password-protected key file.
+///
+/// The 32-byte Ed25519 secret is encrypted with AES-256-GCM under a key
+/// derived from the password with Argon2id. The address is stored in the
+/// clear so a file can be identified without the password, and is bound
+/// to the ciphertext as associated data. `checksum` covers every other
+/// field, which tells a damaged file apart from a wrong password.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub address: Address,
+    pub kdf: KdfSection,
+    pub cipher: CipherSection,
+    /// Hex SHA-256 over version, address, KDF parameters, salt, nonce and
+    /// ciphertext.
+    pub checksum: String,
+}
+
+impl Keystore {
+    /// Encrypt `key_pair` under `password` with the default KDF cost.
+    pub fn encrypt(key_pair: &KeyPair, password: &str) -> Result<Self, KeystoreError> {
+        Self::encrypt_with(key_pair, password, KdfParams::default())
+    }
+
+    /// Encrypt `key_pair` under `password` with explicit KDF cost, which
+    /// must pass [`KdfParams::check`].
+    pub fn encrypt_with(
+        key_pair: &KeyPair,
+        password: &str,
+        params: KdfParams,
+    ) -> Result<Self, KeystoreError> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let address = key_pair.address();
+        let key = derive_key(password, &salt, params)?;
+        let secret = Zeroizing::new(key_pair.secret_bytes());
+        let ciphertext = cipher(&key)
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: secret.as_slice(),
+                    aad: address.0.as_bytes(),
+                },
+            )
+            .expect("32-byte plaintext within AES-GCM length limit");
+
+        let mut keystore = Self {
+            version: KEYSTORE_VERSION,
+            address,
+            kdf: KdfSection {
+                algorithm: KDF_ALGORITHM.to_string(),
+                params,
+                salt: hex::encode(salt),
+            },
+            cipher: CipherSection {
+                algorithm: CIPHER_ALGORITHM.to_string(),
+                nonce: hex::encode(nonce),
+                ciphertext: hex::encode(ciphertext),
+            },
+            checksum: String::new(),
+        };
+        keystore.checksum = keystore.compute_checksum();
+        Ok(keystore)
+    }
+
+    /// Decrypt the key pair.
+    ///
+    /// Fails with `Corrupted` if the file was altered or damaged, with
+    /// `InvalidKdfParams` if its KDF cost is out of bounds (checked before
+    /// running Argon2), and with `WrongPassword` if it is intact but the
+    /// password does not open it.
+    pub fn decrypt(&self, password: &str) -> Result<KeyPair, KeystoreError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(self.version));
+        }
+        if self.kdf.algorithm != KDF_ALGORITHM {
+            return Err(KeystoreError::UnsupportedAlgorithm(
+                self.kdf.algorithm.clone(),
+            ));
+        }
+        if self.cipher.algorithm != CIPHER_ALGORITHM {
+            return Err(KeystoreError::UnsupportedAlgorithm(
+                self.cipher.algorithm.clone(),
+            ));
+        }
+        if self.checksum != self.compute_checksum() {
+            return Err(KeystoreError::Corrupted);
+        }
+
+        let salt = decode_hex("salt", &self.kdf.salt)?;
+        let nonce = decode_hex("nonce", &self.cipher.nonce)?;
+        let ciphertext = decode_hex("ciphertext", &self.cipher.ciphertext)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(KeystoreError::InvalidFormat(format!(
+                "nonce must be {NONCE_LEN} bytes, got {}",
+                nonce.len()
+            )));
+        }
+
+        let key = derive_key(password, &salt, self.kdf.params)?;
+        let plaintext = Zeroizing::new(
+            cipher(&key)
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &ciphertext,
+                        aad: self.address.0.as_bytes(),
+                    },
+                )
+                .map_err(|_| KeystoreError::WrongPassword)?,
+        );
+        let secret: Zeroizing<[u8; 32]> =
+            Zeroizing::new(plaintext.as_slice().try_into().map_err(|_| {
+                KeystoreError::InvalidFormat(format!(
+                    "secret key must be 32 bytes, got {}",
+                    plaintext.len()
+                ))
+            })?);
+
+        let key_pair = KeyPair::from_bytes(&secret);
+        let actual = key_pair.address();
+        if actual != self.address {
+            return Err(KeystoreError::AddressMismatch {
+                expected: self.address.0.clone(),
+                actual: actual.0,
+            });
+        }
+        Ok(key_pair)
+    }
+
+    /// Serialize to pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("keystore serializes")
+    }
+
+    /// Parse a keystore file. The version is checked before the rest of
+    /// the layout, so files from a newer release report
+    /// `UnsupportedVersion` rather than a format error.
+    pub fn from_json(json: &str) -> Result<Self, KeystoreError> {
+        #[derive(Deserialize)]
+        struct VersionProbe {
+            version: u32,
+        }
+        let probe: VersionProbe =
+            serde_json::from_str(json).map_err(|e| KeystoreError::InvalidFormat(e.to_string()))?;
+        if probe.version != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(probe.version));
+        }
+        serde_json::from_str(json).map_err(|e| KeystoreError::InvalidFormat(e.to_string()))
+    }
+
+    /// Write to `path`, replacing any existing file.
+    ///
+    /// The file is written to `path` with `.tmp` appended and renamed into
+    /// place, so a crash never leaves a half-written keystore. On Unix it
+    /// is created readable by the owner only.
+    pub fn save(&self, path: &Path) -> Result<(), KeystoreError> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp).map_err(io_error)?;
+        file.write_all(self.to_json().as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(io_error)?;
+        fs::rename(&tmp, path).map_err(io_error)
+    }
+
+    /// Read and parse the keystore at `path`.
+    pub fn load(path: &Path) -> Result<Self, KeystoreError> {
+        let json = fs::read_to_string(path).map_err(io_error)?;
+        Self::from_json(&json)
+    }
+
+    fn compute_checksum(&self) -> String {
+        let mut data = Vec::new();
+        data.extend_from_slice(&self.version.to_be_bytes());
+        for field in [
+            self.address.0.as_str(),
+            self.kdf.algorithm.as_str(),
+            self.kdf.salt.as_str(),
+            self.cipher.algorithm.as_str(),
+            self.cipher.nonce.as_str(),
+            self.cipher.ciphertext.as_str(),
+        ] {
+            data.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+        let KdfParams {
+            memory_kib,
+            iterations,
+            parallelism,
+        } = self.kdf.params;
+        for value in [memory_kib, iterations, parallelism] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        sha256_hex(&data)
+    }
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, KeystoreError> {
+    params.check()?;
+    let params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(32),
+    )
+    .map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, key.as_mut())
+        .map_err(|e| KeystoreError::InvalidKdfParams(e.to_string()))?;
+    Ok(key)
+}
+
+fn cipher(key: &[u8; 32]) -> Aes256Gcm {
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, KeystoreError> {
+    hex::decode(value).map_err(|e| KeystoreError::InvalidFormat(format!("{field}: {e}")))
+}
+
+fn io_error(e: std::io::Error) -> KeystoreError {
+    KeystoreError::Io(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheapest accepted parameters, so tests don't spend seconds in Argon2.
+    const FAST: KdfParams = MIN_KDF_PARAMS;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let kp = KeyPair::generate();
+        let keystore = Keystore::encrypt_with(&kp, "correct horse", FAST).unwrap();
+        assert_eq!(keystore.address, kp.address());
+
+        let parsed = Keystore::from_json(&keystore.to_json()).unwrap();
+        let restored = parsed.decrypt("correct horse").unwrap();
+        assert_eq!(restored.secret_bytes(), kp.secret_bytes());
+    }
+
+    #[test]
+    fn test_wrong_password() {
+        let keystore = Keystore::encrypt_with(&KeyPair::generate(), "right", FAST).unwrap();
+        assert!(matches!(
+            keystore.decrypt("wrong"),
+            Err(KeystoreError::WrongPassword)
+        ));
+    }
+
+    #[test]
+    fn test_corruption_detected() {
+        let keystore = Keystore::encrypt_with(&KeyPair::generate(), "pw", FAST).unwrap();
+
+        let mut flipped = keystore.clone();
+        let first = if flipped.cipher.ciphertext.starts_with('0') {
+            "1"
+        } else {
+            "0"
+        };
+        flipped.cipher.ciphertext.replace_range(..1, first);
+        assert!(matches!(
+            flipped.decrypt("pw"),
+            Err(KeystoreError::Corrupted)
+        ));
+
+        let mut swapped = keystore.clone();
+        swapped.address = KeyPair::generate().address();
+        assert!(matches!(
+            swapped.decrypt("pw"),
+            Err(KeystoreError::Corrupted)
+        ));
+
+        let mut raised = keystore.clone();
+        raised.kdf.params.iterations += 1;
+        assert!(matches!(
+            raised.decrypt("pw"),
+            Err(KeystoreError::Corrupted)
+        ));
+    }
+
+    #[test]
+    fn test_kdf_params_bounded() {
+        let keystore = Keystore::encrypt_with(&KeyPair::generate(), "pw", FAST).unwrap();
+
+        // The checksum is unkeyed, so an edited file can carry a matching
+        // one; the bounds must hold regardless, before Argon2 runs.
+        let edits: [fn(&mut KdfParams); 6] = [
+            |p| p.memory_kib = 8,
+            |p| p.iterations = 0,
+            |p| p.parallelism = 0,
+            |p| p.memory_kib = u32::MAX,
+            |p| p.iterations = u32::MAX,
+            |p| p.parallelism = MAX_KDF_PARAMS.parallelism + 1,
+        ];
+        for edit in edits {
+            let mut edited = keystore.clone();
+            edit(&mut edited.kdf.params);
+            edited.checksum = edited.compute_checksum();
+            assert!(matches!(
+                edited.decrypt("pw"),
+                Err(KeystoreError::InvalidKdfParams(_))
+            ));
+            assert!(matches!(
+                Keystore::encrypt_with(&KeyPair::generate(), "pw", edited.kdf.params),
+                Err(KeystoreError::InvalidKdfParams(_))
+            ));
+        }
+
+        assert!(MIN_KDF_PARAMS.check().is_ok());
+        assert!(MAX_KDF_PARAMS.check().is_ok());
+        assert!(KdfParams::default().check().is_ok());
+    }
+
+    #[test]
+    fn test_malformed_and_future_files() {
+        assert!(matches!(
+            Keystore::from_json("not json"),
+            Err(KeystoreError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            Keystore::from_json(r#"{"version": 1, "address": "ab"}"#),
+            Err(KeystoreError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            Keystore::from_json(r#"{"version": 2, "anything": "new"}"#),
+            Err(KeystoreError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn test_save_load_file() {
+        let path =
+            std::env::temp_dir().join(format!("gitgold-keystore-test-{}.json", std::process::id()));
+        let kp = KeyPair::generate();
+        Keystore::encrypt_with(&kp, "pw", FAST)
+            .unwrap()
+            .save(&path)
+            .unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let loaded = Keystore::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.decrypt("pw").unwrap().address(), kp.address());
+        assert!(matches!(Keystore::load(&path), Err(KeystoreError::Io(_))));
+    }
+
+    #[test]
+    fn test_save_leaves_sibling_files_alone() {
+        let dir = std::env::temp_dir().join(format!("gitgold-keystore-tmp-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir(&dir).unwrap();
+        let keystore = Keystore::encrypt_with(&KeyPair::generate(), "pw", FAST).unwrap();
+
+        // `wallet.tmp` is not ours to overwrite.
+        fs::write(dir.join("wallet.tmp"), b"unrelated").unwrap();
+        keystore.save(&dir.join("wallet.json")).unwrap();
+        assert_eq!(fs::read(dir.join("wallet.tmp")).unwrap(), b"unrelated");
+
+        // A target already ending in `.tmp` still goes through a temp file.
+        keystore.save(&dir.join("backup.tmp")).unwrap();
+        assert!(Keystore::load(&dir.join("backup.tmp")).is_ok());
+
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["backup.tmp", "wallet.json", "wallet.tmp"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
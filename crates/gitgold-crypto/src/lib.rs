@@ -1,5 +1,17 @@
+pub mod codec;
+pub mod envelope;
 pub mod field;
+pub mod gf256;
 pub mod hash;
+pub mod hd;
+pub mod hybrid;
 pub mod keys;
+pub mod keystore;
+pub mod multisig;
+pub mod reed_solomon;
+pub mod refresh;
+pub mod reshare;
+pub mod robust;
 pub mod shamir;
+pub mod vss;
 pub mod wallet;
@@ -0,0 +1,218 @@
+use ed25519_dalek::VerifyingKey;
+use gitgold_core::error::MultisigError;
+use gitgold_core::types::Address;
+
+use crate::hash::sha256_hex;
+use crate::keys::PublicKey;
+
+/// Largest number of keys in a policy. Signer indices fit in a `u8`.
+pub const MAX_MULTISIG_KEYS: usize = 16;
+
+/// Domain separator for policy addresses, so they can never equal the
+/// address of a single key.
+const MULTISIG_DOMAIN: &[u8] = b"gitgold/multisig";
+
+/// An m-of-n signing policy: any `threshold` of `pubkeys` may sign for the
+/// policy's address.
+///
+/// Keys are kept sorted, so the same set of keys and threshold always
+/// gives the same address. Signer indices refer to that sorted order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigPolicy {
+    threshold: u8,
+    pubkeys: Vec<Vec<u8>>,
+}
+
+impl MultisigPolicy {
+    /// Build a policy requiring `threshold` of `pubkeys` (1 ≤ threshold ≤
+    /// n ≤ [`MAX_MULTISIG_KEYS`], no duplicates, valid Ed25519 keys).
+    pub fn new(threshold: usize, pubkeys: &[PublicKey]) -> Result<Self, MultisigError> {
+        if pubkeys.len() > MAX_MULTISIG_KEYS {
+            return Err(MultisigError::TooManyKeys {
+                keys: pubkeys.len(),
+                max: MAX_MULTISIG_KEYS,
+            });
+        }
+        if threshold == 0 || threshold > pubkeys.len() {
+            return Err(MultisigError::InvalidThreshold {
+                threshold,
+                keys: pubkeys.len(),
+            });
+        }
+        let mut keys = Vec::with_capacity(pubkeys.len());
+        for pk in pubkeys {
+            let bytes: &[u8; 32] = pk.bytes.as_slice().try_into().map_err(|_| {
+                MultisigError::InvalidPublicKey(format!("{} bytes", pk.bytes.len()))
+            })?;
+            VerifyingKey::from_bytes(bytes)
+                .map_err(|_| MultisigError::InvalidPublicKey(hex::encode(bytes)))?;
+            keys.push(pk.bytes.clone());
+        }
+        keys.sort();
+        if keys.windows(2).any(|w| w[0] == w[1]) {
+            return Err(MultisigError::DuplicateKey);
+        }
+        Ok(Self {
+            threshold: threshold as u8,
+            pubkeys: keys,
+        })
+    }
+
+    /// Number of signatures required.
+    pub fn threshold(&self) -> usize {
+        self.threshold as usize
+    }
+
+    /// Member keys in signer-index order.
+    pub fn pubkeys(&self) -> Vec<PublicKey> {
+        self.pubkeys
+            .iter()
+            .map(|bytes| PublicKey {
+                bytes: bytes.clone(),
+            })
+            .collect()
+    }
+
+    /// Signer index of `pubkey`, if it is a member.
+    pub fn signer_index(&self, pubkey: &PublicKey) -> Option<u8> {
+        self.pubkeys
+            .iter()
+            .position(|bytes| *bytes == pubkey.bytes)
+            .map(|i| i as u8)
+    }
+
+    /// Address controlled by this policy:
+    /// hex(SHA-256("gitgold/multisig" | threshold | n | key_1 | … | key_n)).
+    pub fn address(&self) -> Address {
+        let mut preimage = Vec::with_capacity(MULTISIG_DOMAIN.len() + 2 + 32 * self.pubkeys.len());
+        preimage.extend_from_slice(MULTISIG_DOMAIN);
+        preimage.push(self.threshold);
+        preimage.push(self.pubkeys.len() as u8);
+        for key in &self.pubkeys {
+            preimage.extend_from_slice(key);
+        }
+        Address::new(&sha256_hex(&preimage))
+    }
+
+    /// Check `(signer index, signature)` pairs over `message`: at least
+    /// `threshold` of them, from distinct members, and every one valid.
+    pub fn verify(&self, message: &[u8], signatures: &[(u8, Vec<u8>)]) -> bool {
+        if signatures.len() < self.threshold() {
+            return false;
+        }
+        let mut seen = [false; MAX_MULTISIG_KEYS];
+        for (signer, signature) in signatures {
+            let index = *signer as usize;
+            if index >= self.pubkeys.len() || seen[index] {
+                return false;
+            }
+            seen[index] = true;
+            let pk = PublicKey {
+                bytes: self.pubkeys[index].clone(),
+            };
+            if !pk.verify(message, signature) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::KeyPair;
+
+    fn signers(n: usize) -> Vec<KeyPair> {
+        (0..n).map(|_| KeyPair::generate()).collect()
+    }
+
+    fn sign_as(policy: &MultisigPolicy, kp: &KeyPair, message: &[u8]) -> (u8, Vec<u8>) {
+        (
+            policy.signer_index(&kp.public_key()).unwrap(),
+            kp.sign(message),
+        )
+    }
+
+    #[test]
+    fn test_threshold_met() {
+        let kps = signers(3);
+        let pks: Vec<_> = kps.iter().map(KeyPair::public_key).collect();
+        let policy = MultisigPolicy::new(2, &pks).unwrap();
+        let msg = b"pay the storage bill";
+
+        let one = vec![sign_as(&policy, &kps[0], msg)];
+        assert!(!policy.verify(msg, &one));
+
+        let two = vec![
+            sign_as(&policy, &kps[2], msg),
+            sign_as(&policy, &kps[0], msg),
+        ];
+        assert!(policy.verify(msg, &two));
+        assert!(!policy.verify(b"something else", &two));
+    }
+
+    #[test]
+    fn test_repeated_or_foreign_signer_rejected() {
+        let kps = signers(3);
+        let pks: Vec<_> = kps.iter().map(KeyPair::public_key).collect();
+        let policy = MultisigPolicy::new(2, &pks).unwrap();
+        let msg = b"msg";
+
+        let sig = sign_as(&policy, &kps[1], msg);
+        assert!(!policy.verify(msg, &[sig.clone(), sig.clone()]));
+
+        let outsider = KeyPair::generate();
+        assert!(!policy.verify(msg, &[sig.clone(), (7, outsider.sign(msg))]));
+        // A bad extra signature fails the whole set.
+        let forged = (policy.signer_index(&pks[0]).unwrap(), outsider.sign(msg));
+        let good = sign_as(&policy, &kps[2], msg);
+        assert!(!policy.verify(msg, &[sig, good, forged]));
+    }
+
+    #[test]
+    fn test_address_independent_of_key_order() {
+        let kps = signers(3);
+        let mut pks: Vec<_> = kps.iter().map(KeyPair::public_key).collect();
+        let a = MultisigPolicy::new(2, &pks).unwrap();
+        pks.reverse();
+        let b = MultisigPolicy::new(2, &pks).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.address(), b.address());
+
+        let c = MultisigPolicy::new(3, &pks).unwrap();
+        assert_ne!(a.address(), c.address());
+        // A 1-of-1 policy is not the key's own address.
+        let single = MultisigPolicy::new(1, &pks[..1]).unwrap();
+        assert_ne!(single.address(), kps[2].address());
+    }
+
+    #[test]
+    fn test_invalid_policies() {
+        let pks: Vec<_> = signers(2).iter().map(KeyPair::public_key).collect();
+        assert!(matches!(
+            MultisigPolicy::new(0, &pks),
+            Err(MultisigError::InvalidThreshold { .. })
+        ));
+        assert!(matches!(
+            MultisigPolicy::new(3, &pks),
+            Err(MultisigError::InvalidThreshold { .. })
+        ));
+        assert!(matches!(
+            MultisigPolicy::new(1, &[pks[0].clone(), pks[0].clone()]),
+            Err(MultisigError::DuplicateKey)
+        ));
+        assert!(matches!(
+            MultisigPolicy::new(1, &[PublicKey { bytes: vec![1; 31] }]),
+            Err(MultisigError::InvalidPublicKey(_))
+        ));
+        let many: Vec<_> = signers(MAX_MULTISIG_KEYS + 1)
+            .iter()
+            .map(KeyPair::public_key)
+            .collect();
+        assert!(matches!(
+            MultisigPolicy::new(2, &many),
+            Err(MultisigError::TooManyKeys { .. })
+        ));
+    }
+}
@@ -0,0 +1,285 @@
+use gitgold_core::error::ShamirError;
+
+use crate::codec::ShareCodec;
+use crate::gf256::Gf256;
+use crate::shamir::{truncate_to_secret_len, Share, ShareScheme};
+
+/// Maximum total shards: data and parity rows need distinct nonzero ids.
+const MAX_SHARDS: usize = 255;
+
+/// Systematic Reed-Solomon erasure code over GF(2^8).
+///
+/// Shards 1..=k hold the data itself; shards k+1..=n are parity rows of a
+/// Cauchy matrix, so any k shards recover the data. Each shard is
+/// ceil(len / k) bytes, giving n/k total storage instead of Shamir's n.
+/// Unlike Shamir, fewer than k shards do leak the data.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReedSolomon;
+
+impl ShareCodec for ReedSolomon {
+    fn encode(&self, data: &[u8], k: usize, n: usize) -> Result<Vec<Share>, ShamirError> {
+        encode(data, k, n)
+    }
+
+    fn decode(&self, shares: &[Share], k: usize) -> Result<Vec<u8>, ShamirError> {
+        decode(shares, k)
+    }
+}
+
+/// Split `data` into `k` data shards plus `n - k` parity shards.
+///
+/// The last data shard is zero-padded.
+pub fn encode(data: &[u8], k: usize, n: usize) -> Result<Vec<Share>, ShamirError> {
+    if data.is_empty() {
+        return Err(ShamirError::EmptySecret);
+    }
+    if k < 2 {
+        return Err(ShamirError::ThresholdTooLow { k });
+    }
+    if n < k {
+        return Err(ShamirError::InsufficientShares { k, n });
+    }
+    if n > MAX_SHARDS {
+        return Err(ShamirError::TooManyShares { n, max: MAX_SHARDS });
+    }
+
+    let shard_len = data.len().div_ceil(k);
+    let mut padded = data.to_vec();
+    padded.resize(shard_len * k, 0);
+
+    let mut shares: Vec<Share> = padded
+        .chunks(shard_len)
+        .enumerate()
+        .map(|(i, chunk)| Share {
+            id: i as u32 + 1,
+            data: chunk.to_vec(),
+            scheme: ShareScheme::ReedSolomon,
+            k: k as u32,
+            n: n as u32,
+            secret_len: data.len() as u64,
+            block_payload: 0,
+        })
+        .collect();
+
+    for row in k..n {
+        let coeffs = generator_row(row, k);
+        let mut parity = vec![0u8; shard_len];
+        for (coeff, shard) in coeffs.iter().zip(&shares[..k]) {
+            mul_add(&mut parity, *coeff, &shard.data);
+        }
+        shares.push(Share {
+            id: row as u32 + 1,
+            data: parity,
+            scheme: ShareScheme::ReedSolomon,
+            k: k as u32,
+            n: n as u32,
+            secret_len: data.len() as u64,
+            block_payload: 0,
+        });
+    }
+
+    Ok(shares)
+}
+
+/// Recover the data from any `k` shards.
+///
+/// Output is cut to the shards' recorded `secret_len`; legacy shards
+/// without one return the zero-padded data shards.
+pub fn decode(shares: &[Share], k: usize) -> Result<Vec<u8>, ShamirError> {
+    if shares.len() < k {
+        return Err(ShamirError::NotEnoughShares {
+            have: shares.len(),
+            need: k,
+        });
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for share in shares.iter().take(k) {
+        if !seen.insert(share.id) {
+            return Err(ShamirError::DuplicateShareId(share.id));
+        }
+        if share.id == 0 || share.id as usize > MAX_SHARDS {
+            return Err(ShamirError::InvalidShareId(share.id));
+        }
+    }
+
+    let selected: Vec<&Share> = shares[..k].iter().collect();
+    let ids: Vec<u32> = selected.iter().map(|s| s.id).collect();
+    let inverse = decoding_matrix(&ids);
+    let mut result = decode_with(&selected, inverse.as_deref());
+    truncate_to_secret_len(&mut result, selected[0].secret_len)?;
+    Ok(result)
+}
+
+/// Inverse of the generator rows for `ids`, or `None` when the ids are
+/// exactly the data shards and no arithmetic is needed. Ids must be
+/// distinct and in 1..=255.
+pub(crate) fn decoding_matrix(ids: &[u32]) -> Option<Vec<Vec<Gf256>>> {
+    let k = ids.len();
+    if ids.iter().all(|&id| id as usize <= k) {
+        return None;
+    }
+    let rows: Vec<Vec<Gf256>> = ids
+        .iter()
+        .map(|&id| generator_row(id as usize - 1, k))
+        .collect();
+    Some(invert(rows).expect("any k rows of [I; Cauchy] are invertible"))
+}
+
+/// Recover the zero-padded data shards from `selected`, whose ids
+/// `inverse` was built for (see [`decoding_matrix`]).
+pub(crate) fn decode_with(selected: &[&Share], inverse: Option<&[Vec<Gf256>]>) -> Vec<u8> {
+    let k = selected.len();
+    let shard_len = selected[0].data.len();
+
+    // Fast path: all data shards present.
+    let Some(inverse) = inverse else {
+        let mut by_row: Vec<&[u8]> = vec![&[]; k];
+        for share in selected {
+            by_row[share.id as usize - 1] = &share.data;
+        }
+        return by_row.concat();
+    };
+
+    // Multiply the inverted generator submatrix by the held shards to get
+    // the data shards back.
+    let mut result = Vec::with_capacity(shard_len * k);
+    for inv_row in inverse {
+        let mut shard = vec![0u8; shard_len];
+        for (coeff, share) in inv_row.iter().zip(selected) {
+            mul_add(&mut shard, *coeff, &share.data);
+        }
+        result.extend_from_slice(&shard);
+    }
+    result
+}
+
+/// Row `row` of the systematic generator matrix: identity for data rows,
+/// Cauchy entries 1 / (row ^ col) for parity rows.
+pub(crate) fn generator_row(row: usize, k: usize) -> Vec<Gf256> {
+    (0..k)
+        .map(|col| {
+            if row < k {
+                if row == col {
+                    Gf256::one()
+                } else {
+                    Gf256::zero()
+                }
+            } else {
+                // row >= k > col, so row ^ col is never zero.
+                Gf256((row ^ col) as u8).inv()
+            }
+        })
+        .collect()
+}
+
+/// dst[i] += coeff * src[i] over GF(2^8).
+fn mul_add(dst: &mut [u8], coeff: Gf256, src: &[u8]) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = (Gf256(*d) + coeff * Gf256(*s)).0;
+    }
+}
+
+/// Gauss-Jordan inversion of a square matrix over GF(2^8).
+pub(crate) fn invert(mut m: Vec<Vec<Gf256>>) -> Option<Vec<Vec<Gf256>>> {
+    let size = m.len();
+    let mut inv: Vec<Vec<Gf256>> = (0..size)
+        .map(|i| {
+            (0..size)
+                .map(|j| if i == j { Gf256::one() } else { Gf256::zero() })
+                .collect()
+        })
+        .collect();
+
+    for col in 0..size {
+        let pivot = (col..size).find(|&r| m[r][col] != Gf256::zero())?;
+        m.swap(col, pivot);
+        inv.swap(col, pivot);
+
+        let scale = m[col][col].inv();
+        for j in 0..size {
+            m[col][j] = m[col][j] * scale;
+            inv[col][j] = inv[col][j] * scale;
+        }
+
+        for r in 0..size {
+            if r == col || m[r][col] == Gf256::zero() {
+                continue;
+            }
+            let factor = m[r][col];
+            for j in 0..size {
+                m[r][j] = m[r][j] - factor * m[col][j];
+                inv[r][j] = inv[r][j] - factor * inv[col][j];
+            }
+        }
+    }
+
+    Some(inv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_sizes() {
+        let data = vec![7u8; 1000];
+        let shares = encode(&data, 4, 6).unwrap();
+        assert_eq!(shares.len(), 6);
+        assert!(shares.iter().all(|s| s.data.len() == 250));
+        // Systematic: first k shards are the data itself.
+        assert_eq!(shares[0].data, &data[..250]);
+    }
+
+    #[test]
+    fn test_any_k_subset_recovers() {
+        let data: Vec<u8> = (0..1001).map(|i| (i * 31 % 256) as u8).collect();
+        let (k, n) = (3, 6);
+        let shares = encode(&data, k, n).unwrap();
+
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    let subset = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                    let recovered = decode(&subset, k).unwrap();
+                    assert_eq!(&recovered[..data.len()], &data[..]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parity_only_recovers() {
+        let data = b"recover from parity shards alone".to_vec();
+        let shares = encode(&data, 2, 4).unwrap();
+        let recovered = decode(&shares[2..], 2).unwrap();
+        assert_eq!(&recovered[..data.len()], &data[..]);
+    }
+
+    #[test]
+    fn test_not_enough_shards() {
+        let shares = encode(b"some data", 3, 5).unwrap();
+        assert!(matches!(
+            decode(&shares[..2], 3),
+            Err(ShamirError::NotEnoughShares { have: 2, need: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_too_many_shards() {
+        assert!(matches!(
+            encode(b"x", 3, 256),
+            Err(ShamirError::TooManyShares { n: 256, max: 255 })
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_shard_rejected() {
+        let shares = encode(b"duplicate", 2, 3).unwrap();
+        let dup = [shares[2].clone(), shares[2].clone()];
+        assert!(matches!(
+            decode(&dup, 2),
+            Err(ShamirError::DuplicateShareId(3))
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+use gitgold_core::error::ShamirError;
+use rand::{thread_rng, Rng};
+
+use crate::field::FieldElement;
+use crate::gf256::Gf256;
+use crate::shamir::{Share, ShareScheme, BLOCK_SIZE};
+
+/// Proactive refresh, dealer side: shares of a fresh random polynomial
+/// with zero constant term, one per holder id.
+///
+/// Each holder runs this once per round using its own share as the
+/// `template` (only scheme, threshold and length are read, never the data)
+/// and sends update `j` to holder `j`. Adding every holder's update to a
+/// share leaves the secret unchanged but re-randomizes the polynomial, so
+/// shares from before the round no longer combine with shares after it.
+///
+/// Supported for `Prime256` and `Gf256`. Reed-Solomon shards are not
+/// secret shares, and `Ristretto255` shares would need their commitments
+/// refreshed alongside.
+pub fn zero_shares(template: &Share, ids: &[u32]) -> Result<Vec<Share>, ShamirError> {
+    let k = template.k as usize;
+    if k < 2 {
+        return Err(ShamirError::ThresholdTooLow { k });
+    }
+    let mut rng = thread_rng();
+    let deltas: Vec<Vec<u8>> = match template.scheme {
+        ShareScheme::Prime256 => {
+            let xs: Vec<FieldElement> = ids
+                .iter()
+                .map(|&id| FieldElement::from_u64(id as u64))
+                .collect();
+            let mut deltas = vec![Vec::with_capacity(template.data.len()); ids.len()];
+            for _ in 0..template.data.len() / BLOCK_SIZE {
+                let coeffs: Vec<FieldElement> =
+                    (1..k).map(|_| FieldElement::random(&mut rng)).collect();
+                for (delta, &x) in deltas.iter_mut().zip(&xs) {
+                    // Horner over a_{k-1} .. a_1, then one more multiply
+                    // by x for the zero constant term.
+                    let y = coeffs
+                        .iter()
+                        .rev()
+                        .fold(FieldElement::zero(), |acc, &c| acc * x + c)
+                        * x;
+                    delta.extend_from_slice(&y.to_bytes_be());
+                }
+            }
+            deltas
+        }
+        ShareScheme::Gf256 => {
+            if let Some(&bad) = ids.iter().find(|&&id| id == 0 || id > 255) {
+                return Err(ShamirError::InvalidShareId(bad));
+            }
+            let mut deltas = vec![Vec::with_capacity(template.data.len()); ids.len()];
+            for _ in 0..template.data.len() {
+                let coeffs: Vec<Gf256> = (1..k).map(|_| Gf256(rng.gen())).collect();
+                for (delta, &id) in deltas.iter_mut().zip(ids) {
+                    let x = Gf256(id as u8);
+                    let y = coeffs
+                        .iter()
+                        .rev()
+                        .fold(Gf256::zero(), |acc, &c| acc * x + c)
+                        * x;
+                    delta.push(y.0);
+                }
+            }
+            deltas
+        }
+        other => return Err(ShamirError::UnsupportedScheme(format!("{other:?}"))),
+    };
+
+    Ok(ids
+        .iter()
+        .zip(deltas)
+        .map(|(&id, data)| Share {
+            id,
+            data,
+            ..template.clone()
+        })
+        .collect())
+}
+
+/// Proactive refresh, holder side: add every dealer's update for this
+/// holder to its share. Returns the refreshed share with the same id.
+pub fn apply_updates(share: &Share, updates: &[Share]) -> Result<Share, ShamirError> {
+    let mut refreshed = share.clone();
+    for update in updates {
+        if update.id != share.id {
+            return Err(ShamirError::InvalidShareId(update.id));
+        }
+        if update.scheme != share.scheme {
+            return Err(ShamirError::MixedSchemes);
+        }
+        if (
+            update.k,
+            update.n,
+            update.secret_len,
+            update.block_payload,
+            update.data.len(),
+        ) != (
+            share.k,
+            share.n,
+            share.secret_len,
+            share.block_payload,
+            share.data.len(),
+        ) {
+            return Err(ShamirError::InconsistentShares);
+        }
+        match share.scheme {
+            ShareScheme::Prime256 => {
+                for (block, delta) in refreshed
+                    .data
+                    .chunks_mut(BLOCK_SIZE)
+                    .zip(update.data.chunks(BLOCK_SIZE))
+                {
+                    let sum =
+                        FieldElement::from_bytes_be(block) + FieldElement::from_bytes_be(delta);
+                    block.copy_from_slice(&sum.to_bytes_be());
+                }
+            }
+            ShareScheme::Gf256 => {
+                for (byte, delta) in refreshed.data.iter_mut().zip(&update.data) {
+                    *byte ^= delta;
+                }
+            }
+            other => return Err(ShamirError::UnsupportedScheme(format!("{other:?}"))),
+        }
+    }
+    Ok(refreshed)
+}
+
+/// Run a full refresh round in-process: every holder deals zero shares to
+/// all the others and applies what it receives.
+///
+/// Every share of the secret must take part; a holder left out keeps a
+/// share that no longer matches the rest.
+pub fn refresh(shares: &[Share]) -> Result<Vec<Share>, ShamirError> {
+    let ids: Vec<u32> = shares.iter().map(|s| s.id).collect();
+    let mut seen = std::collections::HashSet::new();
+    if let Some(&dup) = ids.iter().find(|&&id| !seen.insert(id)) {
+        return Err(ShamirError::DuplicateShareId(dup));
+    }
+
+    // dealt[d][j] is dealer d's update for holder j.
+    let dealt = shares
+        .iter()
+        .map(|dealer| zero_shares(dealer, &ids))
+        .collect::<Result<Vec<_>, _>>()?;
+    shares
+        .iter()
+        .enumerate()
+        .map(|(j, share)| {
+            let updates: Vec<Share> = dealt.iter().map(|d| d[j].clone()).collect();
+            apply_updates(share, &updates)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::{reconstruct, split_with};
+
+    #[test]
+    fn test_refresh_preserves_secret() {
+        let secret = b"long-lived archive chunk, refreshed every epoch";
+        for scheme in [ShareScheme::Prime256, ShareScheme::Gf256] {
+            let shares = split_with(secret, 3, 5, scheme).unwrap();
+            let refreshed = refresh(&shares).unwrap();
+
+            for (old, new) in shares.iter().zip(&refreshed) {
+                assert_eq!(old.id, new.id);
+                assert_eq!(old.data.len(), new.data.len());
+                assert_ne!(old.data, new.data);
+            }
+            assert_eq!(reconstruct(&refreshed[2..], 3).unwrap(), secret);
+
+            // Refreshing twice still works.
+            let again = refresh(&refreshed).unwrap();
+            assert_eq!(reconstruct(&again[..3], 3).unwrap(), secret);
+        }
+    }
+
+    #[test]
+    fn test_old_and_new_shares_do_not_mix() {
+        let secret = b"an attacker holding stale shares learns nothing";
+        for scheme in [ShareScheme::Prime256, ShareScheme::Gf256] {
+            let shares = split_with(secret, 3, 5, scheme).unwrap();
+            let refreshed = refresh(&shares).unwrap();
+            let mixed = [shares[0].clone(), shares[1].clone(), refreshed[2].clone()];
+            // Prime256 usually notices the mix (a nonzero high byte);
+            // either way the secret does not come back.
+            assert!(!matches!(reconstruct(&mixed, 3), Ok(s) if s == secret));
+        }
+    }
+
+    #[test]
+    fn test_zero_shares_reconstruct_to_zero() {
+        let shares = split_with(&[7u8; 40], 2, 3, ShareScheme::Prime256).unwrap();
+        let zeros = zero_shares(&shares[0], &[1, 2, 3]).unwrap();
+        assert!(reconstruct(&zeros[1..], 2).unwrap().iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_update_for_other_holder_rejected() {
+        let shares = split_with(b"misrouted", 2, 3, ShareScheme::Gf256).unwrap();
+        let updates = zero_shares(&shares[0], &[1, 2, 3]).unwrap();
+        assert!(matches!(
+            apply_updates(&shares[0], &updates[1..2]),
+            Err(ShamirError::InvalidShareId(2))
+        ));
+    }
+
+    #[test]
+    fn test_reed_solomon_unsupported() {
+        let shards = split_with(b"not secret shares", 2, 3, ShareScheme::ReedSolomon).unwrap();
+        assert!(matches!(
+            refresh(&shards),
+            Err(ShamirError::UnsupportedScheme(_))
+        ));
+    }
+}
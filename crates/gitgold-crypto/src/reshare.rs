@@ -0,0 +1,213 @@
+use gitgold_core::error::ShamirError;
+
+use crate::shamir::{
+    split_field_elements, split_with, Reconstructor, Share, ShareScheme, BLOCK_SIZE,
+};
+
+/// A share of an old share: dealt by old holder `from` to the new holder
+/// `share.id` during redistribution.
+#[derive(Debug, Clone)]
+pub struct SubShare {
+    /// Id of the old share this was dealt from.
+    pub from: u32,
+    /// Threshold of the old share set.
+    pub old_k: u32,
+    /// New-generation share fragment, already carrying the new `k`, `n`
+    /// and the original secret length.
+    pub share: Share,
+}
+
+/// Redistribution, old holder side: re-share this share at `new_k` of
+/// `new_n`, producing one sub-share per new holder id 1..=new_n.
+///
+/// Sharing is linear, so the new holders can combine sub-shares from any
+/// `old_k` old holders into a fresh share of the same secret without
+/// anyone reconstructing it (see [`combine_subshares`]).
+pub fn deal_subshares(
+    share: &Share,
+    new_k: usize,
+    new_n: usize,
+) -> Result<Vec<SubShare>, ShamirError> {
+    check_scheme(share.scheme)?;
+    if share.k < 2 {
+        return Err(ShamirError::ThresholdTooLow {
+            k: share.k as usize,
+        });
+    }
+    // Prime256 blocks are already field elements; re-share each as one.
+    let subs = match share.scheme {
+        ShareScheme::Prime256 => split_field_elements(&share.data, new_k, new_n)?,
+        scheme => split_with(&share.data, new_k, new_n, scheme)?,
+    };
+    Ok(subs
+        .into_iter()
+        .map(|mut sub| {
+            sub.secret_len = share.secret_len;
+            sub.block_payload = share.block_payload;
+            SubShare {
+                from: share.id,
+                old_k: share.k,
+                share: sub,
+            }
+        })
+        .collect())
+}
+
+/// Redistribution, new holder side: combine sub-shares received from at
+/// least `old_k` distinct old holders into this holder's new share.
+///
+/// The new share is the Lagrange combination of the sub-shares over the
+/// old holders' ids, i.e. an evaluation of the new polynomial whose
+/// constant term is the original secret.
+pub fn combine_subshares(subshares: &[SubShare]) -> Result<Share, ShamirError> {
+    let first = subshares
+        .first()
+        .ok_or(ShamirError::NotEnoughShares { have: 0, need: 2 })?;
+    let old_k = first.old_k as usize;
+    if subshares.len() < old_k {
+        return Err(ShamirError::NotEnoughShares {
+            have: subshares.len(),
+            need: old_k,
+        });
+    }
+    let selected = &subshares[..old_k];
+    if let Some(other) = selected.iter().find(|s| s.share.id != first.share.id) {
+        return Err(ShamirError::InvalidShareId(other.share.id));
+    }
+    if selected.iter().any(|s| {
+        (
+            s.old_k,
+            s.share.k,
+            s.share.n,
+            s.share.secret_len,
+            s.share.block_payload,
+        ) != (
+            first.old_k,
+            first.share.k,
+            first.share.n,
+            first.share.secret_len,
+            first.share.block_payload,
+        )
+    }) {
+        return Err(ShamirError::InconsistentShares);
+    }
+
+    // Treat each sub-share as a point (old id, sub-share) of the old
+    // polynomial and interpolate at zero. secret_len 0 and full-width
+    // blocks keep the share's field elements intact.
+    let scheme = first.share.scheme;
+    let points: Vec<Share> = selected
+        .iter()
+        .map(|s| Share {
+            id: s.from,
+            data: s.share.data.clone(),
+            scheme: s.share.scheme,
+            k: s.old_k,
+            n: 0,
+            secret_len: 0,
+            block_payload: BLOCK_SIZE as u8,
+        })
+        .collect();
+    let from: Vec<u32> = points.iter().map(|p| p.id).collect();
+    let data = Reconstructor::new(scheme, &from)?.reconstruct(&points)?;
+
+    Ok(Share {
+        data,
+        ..first.share.clone()
+    })
+}
+
+/// Run a full redistribution in-process: the first `k` old shares each
+/// deal sub-shares, and every new holder combines what it receives.
+///
+/// Returns `new_n` shares with ids 1..=new_n at threshold `new_k`.
+pub fn redistribute(
+    shares: &[Share],
+    new_k: usize,
+    new_n: usize,
+) -> Result<Vec<Share>, ShamirError> {
+    let old_k = shares.first().map_or(0, |s| s.k as usize);
+    if old_k < 2 {
+        return Err(ShamirError::ThresholdTooLow { k: old_k });
+    }
+    if shares.len() < old_k {
+        return Err(ShamirError::NotEnoughShares {
+            have: shares.len(),
+            need: old_k,
+        });
+    }
+    let dealt = shares[..old_k]
+        .iter()
+        .map(|s| deal_subshares(s, new_k, new_n))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    (0..new_n)
+        .map(|j| {
+            let received: Vec<SubShare> = dealt.iter().map(|d| d[j].clone()).collect();
+            combine_subshares(&received)
+        })
+        .collect()
+}
+
+fn check_scheme(scheme: ShareScheme) -> Result<(), ShamirError> {
+    match scheme {
+        ShareScheme::Prime256 | ShareScheme::Gf256 => Ok(()),
+        other => Err(ShamirError::UnsupportedScheme(format!("{other:?}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::reconstruct;
+
+    #[test]
+    fn test_redistribute_changes_threshold() {
+        let secret = b"repository chunk moving from 5-of-9 to other policies";
+        for scheme in [ShareScheme::Prime256, ShareScheme::Gf256] {
+            let shares = split_with(secret, 5, 9, scheme).unwrap();
+            for (new_k, new_n) in [(3, 5), (5, 11), (2, 2)] {
+                let new_shares = redistribute(&shares[3..], new_k, new_n).unwrap();
+                assert_eq!(new_shares.len(), new_n);
+                assert!(new_shares
+                    .iter()
+                    .all(|s| (s.k, s.n) == (new_k as u32, new_n as u32)));
+                let tail = &new_shares[new_n - new_k..];
+                assert_eq!(reconstruct(tail, new_k).unwrap(), secret, "{scheme:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_new_threshold_enforced() {
+        let shares = split_with(b"stricter policy", 2, 3, ShareScheme::Prime256).unwrap();
+        let new_shares = redistribute(&shares, 4, 6).unwrap();
+        assert!(matches!(
+            reconstruct(&new_shares[..3], 3),
+            Err(ShamirError::NotEnoughShares { have: 3, need: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_combine_requires_old_threshold() {
+        let shares = split_with(b"need three", 3, 5, ShareScheme::Gf256).unwrap();
+        let dealt: Vec<Vec<SubShare>> = shares[..2]
+            .iter()
+            .map(|s| deal_subshares(s, 2, 3).unwrap())
+            .collect();
+        let received = [dealt[0][0].clone(), dealt[1][0].clone()];
+        assert!(matches!(
+            combine_subshares(&received),
+            Err(ShamirError::NotEnoughShares { have: 2, need: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_reed_solomon_unsupported() {
+        let shards = split_with(b"erasure coded", 2, 3, ShareScheme::ReedSolomon).unwrap();
+        assert!(matches!(
+            redistribute(&shards, 2, 4),
+            Err(ShamirError::UnsupportedScheme(_))
+        ));
+    }
+}
@@ -0,0 +1,498 @@
+use std::collections::{HashMap, HashSet};
+
+use curve25519_dalek::scalar::Scalar;
+use gitgold_core::error::ShamirError;
+
+use crate::field::FieldElement;
+use crate::gf256::Gf256;
+use crate::hybrid::split_parts;
+use crate::reed_solomon::{generator_row, invert};
+use crate::shamir::{reconstruct, Share, ShareScheme, BLOCK_SIZE};
+use crate::vss::scalar_from_slice;
+
+/// Most k-subsets [`reconstruct_robust`] will search. Any `k + 2f` shares
+/// tolerate `f` faults, so callers holding more can pass fewer.
+pub const MAX_SUBSETS: u64 = 10_000;
+
+/// Outcome of a Byzantine-tolerant reconstruction.
+///
+/// `rejected` lists the shares that disagree with the recovered secret, so
+/// the hosts that served them can be penalised.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RobustReconstruction {
+    pub secret: Vec<u8>,
+    /// Ids of shares consistent with the recovered secret, ascending.
+    pub accepted: Vec<u32>,
+    /// Ids of shares that were malformed or disagree with it, ascending.
+    pub rejected: Vec<u32>,
+}
+
+/// Reconstruct from more than `k` shares while detecting corrupted ones.
+///
+/// Shares whose header (scheme, threshold, share count, secret length, data
+/// length) differs from the majority are rejected outright. The rest are
+/// searched for a k-subset whose polynomial (or, for Reed-Solomon, whose
+/// codeword) every other share agrees with, allowing up to
+/// `(shares.len() - k) / 2` faulty shares in total. Within that bound the
+/// answer is unique: two candidates would have to agree on at least `k`
+/// shares, which makes them identical.
+///
+/// The subset search is exhaustive, so the worst case is C(m, k) subsets
+/// of the `m` well-formed shares. That is cheap for the small `n` used per
+/// chunk and stops at the first all-honest subset; above [`MAX_SUBSETS`]
+/// it fails with `SearchTooLarge` before searching.
+pub fn reconstruct_robust(shares: &[Share], k: usize) -> Result<RobustReconstruction, ShamirError> {
+    let mut seen = HashSet::new();
+    for share in shares {
+        if !seen.insert(share.id) {
+            return Err(ShamirError::DuplicateShareId(share.id));
+        }
+    }
+
+    // Majority header; ties go to the header seen first.
+    let header = |s: &Share| {
+        (
+            s.scheme,
+            s.k,
+            s.n,
+            s.secret_len,
+            s.block_payload,
+            s.data.len(),
+        )
+    };
+    let mut counts: HashMap<_, usize> = HashMap::new();
+    let mut majority = None;
+    for share in shares {
+        let count = counts.entry(header(share)).or_default();
+        *count += 1;
+        if majority.is_none_or(|(_, best)| *count > best) {
+            majority = Some((header(share), *count));
+        }
+    }
+    let (majority, _) = majority.ok_or(ShamirError::NotEnoughShares { have: 0, need: k })?;
+
+    let (scheme, recorded_k, ..) = majority;
+    let k = k.max(recorded_k as usize);
+    if k < 2 {
+        return Err(ShamirError::ThresholdTooLow { k });
+    }
+    let (candidates, mut rejected): (Vec<&Share>, Vec<&Share>) = shares
+        .iter()
+        .partition(|s| header(s) == majority && valid_id(s));
+    if candidates.len() < k {
+        return Err(ShamirError::NotEnoughShares {
+            have: candidates.len(),
+            need: k,
+        });
+    }
+
+    let max_faults = (shares.len() - k) / 2;
+    if rejected.len() > max_faults {
+        return Err(ShamirError::TooManyFaultyShares {
+            shares: shares.len(),
+            k,
+        });
+    }
+    let budget = max_faults - rejected.len();
+    if subset_count(candidates.len(), k).is_none() {
+        return Err(ShamirError::SearchTooLarge {
+            shares: candidates.len(),
+            k,
+            max: MAX_SUBSETS,
+        });
+    }
+
+    let mut subset: Vec<usize> = (0..k).collect();
+    loop {
+        if let Some(disagreeing) = check_subset(scheme, &candidates, &subset, budget) {
+            let basis: Vec<Share> = subset.iter().map(|&i| candidates[i].clone()).collect();
+            let secret = reconstruct(&basis, k)?;
+
+            let mut accepted: Vec<u32> = candidates
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !disagreeing.contains(i))
+                .map(|(_, s)| s.id)
+                .collect();
+            rejected.extend(disagreeing.iter().map(|&i| candidates[i]));
+            let mut rejected: Vec<u32> = rejected.iter().map(|s| s.id).collect();
+            accepted.sort_unstable();
+            rejected.sort_unstable();
+            return Ok(RobustReconstruction {
+                secret,
+                accepted,
+                rejected,
+            });
+        }
+        if !next_combination(&mut subset, candidates.len()) {
+            return Err(ShamirError::TooManyFaultyShares {
+                shares: shares.len(),
+                k,
+            });
+        }
+    }
+}
+
+fn valid_id(share: &Share) -> bool {
+    match share.scheme {
+        ShareScheme::Prime256 | ShareScheme::Ristretto255 => share.id != 0,
+        ShareScheme::Gf256 | ShareScheme::ReedSolomon | ShareScheme::Hybrid => {
+            share.id != 0 && share.id <= 255
+        }
+    }
+}
+
+/// Check every candidate outside `subset` against the subset's polynomial.
+///
+/// Returns the indices of disagreeing candidates, or `None` once more than
+/// `budget` of them disagree.
+fn check_subset(
+    scheme: ShareScheme,
+    candidates: &[&Share],
+    subset: &[usize],
+    budget: usize,
+) -> Option<Vec<usize>> {
+    let basis: Vec<&Share> = subset.iter().map(|&i| candidates[i]).collect();
+    let mut disagreeing = Vec::new();
+    for (idx, target) in candidates.iter().enumerate() {
+        if subset.contains(&idx) {
+            continue;
+        }
+        let agrees = match scheme {
+            ShareScheme::Prime256 => prime256_agrees(&basis, target),
+            ShareScheme::Gf256 => {
+                let xs: Vec<Gf256> = basis.iter().map(|s| Gf256(s.id as u8)).collect();
+                let weights = gf256_lagrange_at(&xs, Gf256(target.id as u8));
+                bytes_agree(&basis, &weights, target)
+            }
+            ShareScheme::ReedSolomon => {
+                bytes_agree(&basis, &reed_solomon_weights(&basis, target), target)
+            }
+            ShareScheme::Ristretto255 => ristretto255_agrees(&basis, target),
+            ShareScheme::Hybrid => hybrid_agrees(&basis, target),
+        };
+        if !agrees {
+            disagreeing.push(idx);
+            if disagreeing.len() > budget {
+                return None;
+            }
+        }
+    }
+    Some(disagreeing)
+}
+
+/// Check the key shares as `Prime256` and the ciphertext shards as
+/// Reed-Solomon; a hybrid share agrees only if both halves do.
+fn hybrid_agrees(basis: &[&Share], target: &Share) -> bool {
+    let Ok(parts) = basis
+        .iter()
+        .map(|s| split_parts(s))
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        return false;
+    };
+    let Ok((target_key, target_shard)) = split_parts(target) else {
+        return false;
+    };
+    let keys: Vec<&Share> = parts.iter().map(|(key, _)| key).collect();
+    let shards: Vec<&Share> = parts.iter().map(|(_, shard)| shard).collect();
+    prime256_agrees(&keys, &target_key)
+        && bytes_agree(
+            &shards,
+            &reed_solomon_weights(&shards, &target_shard),
+            &target_shard,
+        )
+}
+
+/// Does `target` lie on the per-block polynomials through `basis`?
+fn prime256_agrees(basis: &[&Share], target: &Share) -> bool {
+    let x = FieldElement::from_u64(target.id as u64);
+    let xs: Vec<FieldElement> = basis
+        .iter()
+        .map(|s| FieldElement::from_u64(s.id as u64))
+        .collect();
+    let weights: Vec<FieldElement> = xs
+        .iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            let mut num = FieldElement::one();
+            let mut den = FieldElement::one();
+            for (j, &xj) in xs.iter().enumerate() {
+                if i != j {
+                    num = num * (x - xj);
+                    den = den * (xi - xj);
+                }
+            }
+            num / den
+        })
+        .collect();
+
+    target
+        .data
+        .chunks(BLOCK_SIZE)
+        .enumerate()
+        .all(|(block, expected)| {
+            if expected.len() != BLOCK_SIZE {
+                return false;
+            }
+            let range = block * BLOCK_SIZE..(block + 1) * BLOCK_SIZE;
+            let predicted = basis
+                .iter()
+                .zip(&weights)
+                .fold(FieldElement::zero(), |acc, (s, &w)| {
+                    acc + w * FieldElement::from_bytes_be(&s.data[range.clone()])
+                });
+            predicted.to_bytes_be() == expected
+        })
+}
+
+/// Does `target` lie on both the value and blinding polynomials through
+/// `basis`? Every 32-byte scalar in the data is checked the same way.
+fn ristretto255_agrees(basis: &[&Share], target: &Share) -> bool {
+    let x = Scalar::from(target.id);
+    let xs: Vec<Scalar> = basis.iter().map(|s| Scalar::from(s.id)).collect();
+    let weights: Vec<Scalar> = xs
+        .iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            let mut num = Scalar::ONE;
+            let mut den = Scalar::ONE;
+            for (j, &xj) in xs.iter().enumerate() {
+                if i != j {
+                    num *= x - xj;
+                    den *= xi - xj;
+                }
+            }
+            num * den.invert()
+        })
+        .collect();
+
+    target.data.chunks(32).enumerate().all(|(pos, expected)| {
+        if expected.len() != 32 {
+            return false;
+        }
+        let range = pos * 32..(pos + 1) * 32;
+        let predicted = basis
+            .iter()
+            .zip(&weights)
+            .try_fold(Scalar::ZERO, |acc, (s, w)| {
+                Some(acc + w * scalar_from_slice(&s.data[range.clone()])?)
+            });
+        predicted.is_some_and(|p| p.as_bytes() == expected)
+    })
+}
+
+/// Lagrange basis polynomials through `xs`, evaluated at `x`.
+fn gf256_lagrange_at(xs: &[Gf256], x: Gf256) -> Vec<Gf256> {
+    xs.iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            let mut num = Gf256::one();
+            let mut den = Gf256::one();
+            for (j, &xj) in xs.iter().enumerate() {
+                if i != j {
+                    num = num * (x - xj);
+                    den = den * (xi - xj);
+                }
+            }
+            num / den
+        })
+        .collect()
+}
+
+/// Weights expressing shard `target` as a combination of the `basis` shards:
+/// its generator row times the inverse of the basis rows.
+fn reed_solomon_weights(basis: &[&Share], target: &Share) -> Vec<Gf256> {
+    let k = basis.len();
+    let rows: Vec<Vec<Gf256>> = basis
+        .iter()
+        .map(|s| generator_row(s.id as usize - 1, k))
+        .collect();
+    let inverse = invert(rows).expect("any k rows of [I; Cauchy] are invertible");
+    let target_row = generator_row(target.id as usize - 1, k);
+    (0..k)
+        .map(|col| {
+            target_row
+                .iter()
+                .zip(&inverse)
+                .fold(Gf256::zero(), |acc, (&t, inv_row)| acc + t * inv_row[col])
+        })
+        .collect()
+}
+
+fn bytes_agree(basis: &[&Share], weights: &[Gf256], target: &Share) -> bool {
+    target.data.iter().enumerate().all(|(pos, &expected)| {
+        let predicted = basis
+            .iter()
+            .zip(weights)
+            .fold(Gf256::zero(), |acc, (s, &w)| acc + w * Gf256(s.data[pos]));
+        predicted.0 == expected
+    })
+}
+
+/// C(m, k), or `None` if it exceeds [`MAX_SUBSETS`].
+fn subset_count(m: usize, k: usize) -> Option<u64> {
+    let k = k.min(m - k);
+    let mut count: u128 = 1;
+    for i in 0..k {
+        // C(m, i + 1) = C(m, i) * (m - i) / (i + 1), exact at every step.
+        count = count * (m - i) as u128 / (i + 1) as u128;
+        if count > MAX_SUBSETS as u128 {
+            return None;
+        }
+    }
+    Some(count as u64)
+}
+
+/// Advance `idx` to the next k-combination of `0..m` in lexicographic order.
+fn next_combination(idx: &mut [usize], m: usize) -> bool {
+    let k = idx.len();
+    for i in (0..k).rev() {
+        if idx[i] < m - k + i {
+            idx[i] += 1;
+            for j in i + 1..k {
+                idx[j] = idx[j - 1] + 1;
+            }
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::split_with;
+
+    const SCHEMES: [ShareScheme; 5] = [
+        ShareScheme::Prime256,
+        ShareScheme::Gf256,
+        ShareScheme::ReedSolomon,
+        ShareScheme::Ristretto255,
+        ShareScheme::Hybrid,
+    ];
+
+    #[test]
+    fn test_all_honest() {
+        let secret = b"nothing to see here, all shares are honest";
+        for scheme in SCHEMES {
+            let shares = split_with(secret, 3, 6, scheme).unwrap();
+            let result = reconstruct_robust(&shares, 3).unwrap();
+            assert_eq!(result.secret, secret);
+            assert_eq!(result.accepted, vec![1, 2, 3, 4, 5, 6]);
+            assert!(result.rejected.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_corrupted_shares_excluded() {
+        let secret: Vec<u8> = (0..100).map(|i| (i * 7 % 256) as u8).collect();
+        for scheme in SCHEMES {
+            // k=3, n=7 tolerates two faults.
+            let mut shares = split_with(&secret, 3, 7, scheme).unwrap();
+            shares[0].data[5] ^= 0x40;
+            let last = shares[4].data.len() - 1;
+            shares[4].data[last] ^= 0x01;
+
+            let result = reconstruct_robust(&shares, 3).unwrap();
+            assert_eq!(result.secret, secret, "{scheme:?}");
+            assert_eq!(result.accepted, vec![2, 3, 4, 6, 7]);
+            assert_eq!(result.rejected, vec![1, 5]);
+        }
+    }
+
+    #[test]
+    fn test_tampered_header_rejected() {
+        let mut shares = split_with(b"header tamper", 2, 4, ShareScheme::Gf256).unwrap();
+        shares[1].secret_len = 3;
+        let result = reconstruct_robust(&shares, 2).unwrap();
+        assert_eq!(result.secret, b"header tamper");
+        assert_eq!(result.rejected, vec![2]);
+    }
+
+    #[test]
+    fn test_tampered_block_payload_rejected() {
+        let mut shares = split_with(b"payload tamper", 2, 4, ShareScheme::Prime256).unwrap();
+        shares[2].block_payload = 32;
+        let result = reconstruct_robust(&shares, 2).unwrap();
+        assert_eq!(result.secret, b"payload tamper");
+        assert_eq!(result.rejected, vec![3]);
+    }
+
+    #[test]
+    fn test_too_many_faults() {
+        let mut shares = split_with(b"outvoted", 3, 5, ShareScheme::Prime256).unwrap();
+        shares[0].data[0] ^= 1;
+        shares[1].data[0] ^= 1;
+        assert!(matches!(
+            reconstruct_robust(&shares, 3),
+            Err(ShamirError::TooManyFaultyShares { shares: 5, k: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_ids_rejected() {
+        let shares = split_with(b"dup", 2, 3, ShareScheme::Gf256).unwrap();
+        let dup = [shares[0].clone(), shares[1].clone(), shares[1].clone()];
+        assert!(matches!(
+            reconstruct_robust(&dup, 2),
+            Err(ShamirError::DuplicateShareId(2))
+        ));
+    }
+
+    #[test]
+    fn test_odd_length_secret() {
+        let secret: Vec<u8> = (0..45).map(|i| (i * 13 % 256) as u8).collect();
+        for scheme in SCHEMES {
+            let mut shares = split_with(&secret, 3, 7, scheme).unwrap();
+            shares[2].data[0] ^= 0x80;
+            let last = shares[6].data.len() - 1;
+            shares[6].data[last] ^= 0x02;
+
+            let result = reconstruct_robust(&shares, 3).unwrap();
+            assert_eq!(result.secret, secret, "{scheme:?}");
+            assert_eq!(result.rejected, vec![3, 7], "{scheme:?}");
+        }
+
+        // Ragged data that doesn't fill its last block is never accepted,
+        // and never read past its end.
+        for scheme in [ShareScheme::Prime256, ShareScheme::Ristretto255] {
+            let mut shares = split_with(&secret, 3, 7, scheme).unwrap();
+            for share in &mut shares {
+                share.data.pop();
+            }
+            assert!(reconstruct_robust(&shares, 3).is_err(), "{scheme:?}");
+        }
+    }
+
+    #[test]
+    fn test_search_capped() {
+        let shares = split_with(b"many shares", 20, 40, ShareScheme::Gf256).unwrap();
+        assert!(matches!(
+            reconstruct_robust(&shares, 20),
+            Err(ShamirError::SearchTooLarge {
+                shares: 40,
+                k: 20,
+                max: MAX_SUBSETS
+            })
+        ));
+        // Fewer shares stay under the cap.
+        let result = reconstruct_robust(&shares[..23], 20).unwrap();
+        assert_eq!(result.secret, b"many shares");
+
+        assert_eq!(subset_count(9, 5), Some(126));
+        assert_eq!(subset_count(40, 20), None);
+        assert_eq!(subset_count(255, 254), Some(255));
+    }
+
+    #[test]
+    fn test_next_combination_enumerates_all() {
+        let mut idx = vec![0, 1];
+        let mut count = 1;
+        while next_combination(&mut idx, 4) {
+            count += 1;
+        }
+        assert_eq!(count, 6);
+        assert_eq!(idx, vec![2, 3]);
+    }
+}
@@ -1,10 +1,33 @@
 use gitgold_core::error::ShamirError;
-use num_bigint::BigUint;
-use num_bigint::RandBigInt;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 
+use crate::codec::ShareCodec;
 use crate::field::FieldElement;
+use crate::gf256::Gf256;
+use crate::hybrid;
+use crate::reed_solomon;
+use crate::vss;
+
+/// The field a share's polynomial was evaluated over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ShareScheme {
+    /// 32-byte blocks over GF(2^256 - 189), each carrying 31 secret bytes.
+    #[default]
+    Prime256,
+    /// Byte-wise over GF(2^8). Shares are exactly as long as the secret,
+    /// and at most 255 shares can be produced.
+    Gf256,
+    /// Reed-Solomon shards (see `reed_solomon`). Not secret sharing, but
+    /// carried in the same `Share` type so storage handles both alike.
+    ReedSolomon,
+    /// Shamir over the Ristretto255 scalar field with Pedersen commitments
+    /// (see `vss`). 31 secret bytes per block, 64 share bytes per block.
+    Ristretto255,
+    /// Encrypt-then-share (see `hybrid`): a `Prime256` share of the
+    /// AES-256-GCM key followed by a Reed-Solomon shard of the ciphertext.
+    Hybrid,
+}
 
 /// A single share from Shamir secret sharing.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,16 +36,115 @@ pub struct Share {
     pub id: u32,
     /// Share data (32 bytes per block, concatenated for multi-block secrets).
     pub data: Vec<u8>,
+    /// Scheme that produced this share. Missing in older serialized shares,
+    /// which were always `Prime256`.
+    #[serde(default)]
+    pub scheme: ShareScheme,
+    /// Reconstruction threshold the share was produced with (0 if unknown).
+    #[serde(default)]
+    pub k: u32,
+    /// Total number of shares produced (0 if unknown).
+    #[serde(default)]
+    pub n: u32,
+    /// Length of the original secret in bytes. 0 marks a legacy share
+    /// whose reconstruction is returned zero-padded.
+    #[serde(default)]
+    pub secret_len: u64,
+    /// Secret bytes per `Prime256` block: [`PAYLOAD_SIZE`] for current
+    /// shares, a full [`BLOCK_SIZE`] for older ones and for shares of raw
+    /// field elements. 0 for other schemes.
+    #[serde(default = "legacy_block_payload")]
+    pub block_payload: u8,
+}
+
+fn legacy_block_payload() -> u8 {
+    BLOCK_SIZE as u8
 }
 
-/// Block size for chunking secrets into field elements (32 bytes < p).
-const BLOCK_SIZE: usize = 32;
+/// Shamir secret sharing over GF(2^256 - 189) as a `ShareCodec`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Shamir;
+
+impl ShareCodec for Shamir {
+    fn encode(&self, data: &[u8], k: usize, n: usize) -> Result<Vec<Share>, ShamirError> {
+        split(data, k, n)
+    }
+
+    fn decode(&self, shares: &[Share], k: usize) -> Result<Vec<u8>, ShamirError> {
+        reconstruct(shares, k)
+    }
+}
+
+/// Bytes per `Prime256` share block: one field element.
+pub(crate) const BLOCK_SIZE: usize = 32;
+
+/// Secret bytes per `Prime256` block. Any 31 bytes are below p, so every
+/// block round-trips; a full 32-byte block at or above p = 2^256 - 189
+/// would come back reduced.
+pub(crate) const PAYLOAD_SIZE: usize = 31;
+
+/// Maximum number of shares for `ShareScheme::Gf256` (x must be a nonzero byte).
+const GF256_MAX_SHARES: usize = 255;
 
 /// Split a secret into `n` shares where any `k` can reconstruct.
 ///
-/// Secrets larger than 32 bytes are split into 32-byte blocks, each
-/// shared independently. The last block is zero-padded.
+/// Secrets larger than 31 bytes are split into 31-byte blocks, each
+/// shared independently as one field element. The last block is
+/// zero-padded.
 pub fn split(secret: &[u8], k: usize, n: usize) -> Result<Vec<Share>, ShamirError> {
+    split_with(secret, k, n, ShareScheme::Prime256)
+}
+
+/// Split a secret into `n` shares using the given scheme.
+pub fn split_with(
+    secret: &[u8],
+    k: usize,
+    n: usize,
+    scheme: ShareScheme,
+) -> Result<Vec<Share>, ShamirError> {
+    split_impl(secret, k, n, scheme, false)
+}
+
+/// Like [`split_with`], but spreads `Prime256` blocks across threads.
+///
+/// Shares come out identical in layout and order to the serial version.
+/// Other schemes are cheap per byte and run serially.
+#[cfg(feature = "parallel")]
+pub fn split_par(
+    secret: &[u8],
+    k: usize,
+    n: usize,
+    scheme: ShareScheme,
+) -> Result<Vec<Share>, ShamirError> {
+    split_impl(secret, k, n, scheme, true)
+}
+
+fn split_impl(
+    secret: &[u8],
+    k: usize,
+    n: usize,
+    scheme: ShareScheme,
+    parallel: bool,
+) -> Result<Vec<Share>, ShamirError> {
+    check_split(secret, k, n)?;
+    match scheme {
+        ShareScheme::Prime256 => Ok(split_prime256(secret, k, n, PAYLOAD_SIZE, parallel)),
+        ShareScheme::Gf256 => {
+            if n > GF256_MAX_SHARES {
+                return Err(ShamirError::TooManyShares {
+                    n,
+                    max: GF256_MAX_SHARES,
+                });
+            }
+            Ok(split_gf256(secret, k, n))
+        }
+        ShareScheme::ReedSolomon => reed_solomon::encode(secret, k, n),
+        ShareScheme::Ristretto255 => vss::split_verifiable(secret, k, n).map(|(shares, _)| shares),
+        ShareScheme::Hybrid => hybrid::encode(secret, k, n),
+    }
+}
+
+fn check_split(secret: &[u8], k: usize, n: usize) -> Result<(), ShamirError> {
     if secret.is_empty() {
         return Err(ShamirError::EmptySecret);
     }
@@ -32,62 +154,144 @@ pub fn split(secret: &[u8], k: usize, n: usize) -> Result<Vec<Share>, ShamirErro
     if n < k {
         return Err(ShamirError::InsufficientShares { k, n });
     }
+    Ok(())
+}
 
-    // Pad secret to multiple of BLOCK_SIZE
+/// Split data that is already a sequence of 32-byte field elements, one
+/// per block: a share's own blocks when resharing, or a key drawn from
+/// the field. Every element must be below p.
+pub(crate) fn split_field_elements(
+    elements: &[u8],
+    k: usize,
+    n: usize,
+) -> Result<Vec<Share>, ShamirError> {
+    check_split(elements, k, n)?;
+    if !elements.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(ShamirError::InconsistentShares);
+    }
+    Ok(split_prime256(elements, k, n, BLOCK_SIZE, false))
+}
+
+/// Share `secret` in blocks of `payload` bytes, one field element each.
+fn split_prime256(secret: &[u8], k: usize, n: usize, payload: usize, parallel: bool) -> Vec<Share> {
+    // Pad secret to multiple of the payload size
     let mut padded = secret.to_vec();
-    if !padded.len().is_multiple_of(BLOCK_SIZE) {
-        padded.resize(padded.len() + (BLOCK_SIZE - padded.len() % BLOCK_SIZE), 0);
+    if !padded.len().is_multiple_of(payload) {
+        padded.resize(padded.len() + (payload - padded.len() % payload), 0);
     }
-    let num_blocks = padded.len() / BLOCK_SIZE;
+    let num_blocks = padded.len() / payload;
+
+    // Evaluate each block's polynomial at x = 1, 2, ..., n. Blocks are
+    // independent, so this is the part worth spreading across threads.
+    let evaluations: Vec<Vec<FieldElement>> = map_blocks(num_blocks, parallel, |block_idx| {
+        let block_start = block_idx * payload;
+        let block = &padded[block_start..block_start + payload];
+        let secret_elem = FieldElement::from_bytes_be(block);
+
+        // Generate random coefficients a_1 .. a_{k-1}
+        let mut rng = thread_rng();
+        let mut coeffs: Vec<FieldElement> = Vec::with_capacity(k);
+        coeffs.push(secret_elem); // a_0 = secret
+        for _ in 1..k {
+            coeffs.push(FieldElement::random(&mut rng));
+        }
+
+        (1..=n as u64)
+            .map(|x| eval_poly(&coeffs, &FieldElement::from_u64(x)))
+            .collect()
+    });
 
     // Initialize shares
     let mut shares: Vec<Share> = (1..=n as u32)
         .map(|id| Share {
             id,
             data: Vec::with_capacity(num_blocks * BLOCK_SIZE),
+            scheme: ShareScheme::Prime256,
+            k: k as u32,
+            n: n as u32,
+            secret_len: secret.len() as u64,
+            block_payload: payload as u8,
         })
         .collect();
 
-    let mut rng = thread_rng();
+    for ys in &evaluations {
+        for (share, y) in shares.iter_mut().zip(ys) {
+            share.data.extend_from_slice(&y.to_bytes_be());
+        }
+    }
 
-    for block_idx in 0..num_blocks {
-        let block_start = block_idx * BLOCK_SIZE;
-        let block = &padded[block_start..block_start + BLOCK_SIZE];
-        let secret_elem = FieldElement::from_bytes_be(block);
+    shares
+}
 
-        // Generate random coefficients a_1 .. a_{k-1}
-        let p = FieldElement::zero(); // just to get the modulus
-        let modulus = num_bigint::BigUint::from_bytes_be(&{
-            // p = 2^256 - 189
-            let two = BigUint::from(2u32);
-            let p_val = two.pow(256) - BigUint::from(189u32);
-            p_val.to_bytes_be()
-        });
-        let _ = p;
+/// `(0..count).map(f)`, on the rayon pool when `parallel` is set.
+/// Results are always in index order.
+fn map_blocks<T, F>(count: usize, parallel: bool, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    if parallel {
+        use rayon::prelude::*;
+        return (0..count).into_par_iter().map(f).collect();
+    }
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallel;
+    (0..count).map(f).collect()
+}
 
-        let mut coeffs: Vec<FieldElement> = Vec::with_capacity(k);
-        coeffs.push(secret_elem); // a_0 = secret
-        for _ in 1..k {
-            let rand_val = rng.gen_biguint_below(&modulus);
-            coeffs.push(FieldElement::new(rand_val));
-        }
+fn split_gf256(secret: &[u8], k: usize, n: usize) -> Vec<Share> {
+    let mut shares: Vec<Share> = (1..=n as u32)
+        .map(|id| Share {
+            id,
+            data: Vec::with_capacity(secret.len()),
+            scheme: ShareScheme::Gf256,
+            k: k as u32,
+            n: n as u32,
+            secret_len: secret.len() as u64,
+            block_payload: 0,
+        })
+        .collect();
 
-        // Evaluate polynomial at x = 1, 2, ..., n using Horner's method
+    let mut rng = thread_rng();
+    let mut coeffs = vec![Gf256::zero(); k];
+
+    for &byte in secret {
+        coeffs[0] = Gf256(byte);
+        for c in coeffs.iter_mut().skip(1) {
+            *c = Gf256(rng.gen());
+        }
         for share in shares.iter_mut() {
-            let x = FieldElement::from_u64(share.id as u64);
-            let y = eval_poly(&coeffs, &x);
-            share.data.extend_from_slice(&y.to_bytes_be());
+            let x = Gf256(share.id as u8);
+            let mut y = Gf256::zero();
+            for c in coeffs.iter().rev() {
+                y = y * x + *c;
+            }
+            share.data.push(y.0);
         }
     }
 
-    Ok(shares)
+    shares
 }
 
 /// Reconstruct a secret from `k` or more shares.
 ///
-/// The original secret length must be known; excess zero-padding is included
-/// in the output (caller should truncate to original length).
+/// Dispatches on the shares' scheme. Shares carrying a `secret_len` yield
+/// exactly the original bytes; legacy shares without one return the
+/// zero-padded blocks and the caller must truncate. If the shares record a
+/// larger threshold than `k`, that threshold is used instead.
 pub fn reconstruct(shares: &[Share], k: usize) -> Result<Vec<u8>, ShamirError> {
+    reconstruct_impl(shares, k, false)
+}
+
+/// Like [`reconstruct`], but interpolates `Prime256` blocks across threads.
+#[cfg(feature = "parallel")]
+pub fn reconstruct_par(shares: &[Share], k: usize) -> Result<Vec<u8>, ShamirError> {
+    reconstruct_impl(shares, k, true)
+}
+
+fn reconstruct_impl(shares: &[Share], k: usize, parallel: bool) -> Result<Vec<u8>, ShamirError> {
+    let k = k.max(shares.first().map_or(0, |s| s.k as usize));
     if shares.len() < k {
         return Err(ShamirError::NotEnoughShares {
             have: shares.len(),
@@ -95,78 +299,291 @@ pub fn reconstruct(shares: &[Share], k: usize) -> Result<Vec<u8>, ShamirError> {
         });
     }
 
-    // Check for duplicate IDs
-    let mut seen = std::collections::HashSet::new();
-    for share in shares.iter().take(k) {
-        if !seen.insert(share.id) {
-            return Err(ShamirError::DuplicateShareId(share.id));
-        }
+    // Use exactly k shares
+    let selected: Vec<&Share> = shares[..k].iter().collect();
+    check_headers(&selected)?;
+    let ids: Vec<u32> = selected.iter().map(|s| s.id).collect();
+    Reconstructor::new(selected[0].scheme, &ids)?.apply(&selected, parallel)
+}
+
+/// Shares combined in one reconstruction must agree on scheme, header and
+/// data length. Shares come from untrusted hosts, and interpolation indexes
+/// every share by the first one's length.
+fn check_headers(selected: &[&Share]) -> Result<(), ShamirError> {
+    let first = selected[0];
+    if selected.iter().any(|s| s.scheme != first.scheme) {
+        return Err(ShamirError::MixedSchemes);
+    }
+    if selected.iter().any(|s| {
+        (s.k, s.n, s.secret_len, s.block_payload, s.data.len())
+            != (
+                first.k,
+                first.n,
+                first.secret_len,
+                first.block_payload,
+                first.data.len(),
+            )
+    }) {
+        return Err(ShamirError::InconsistentShares);
+    }
+    if first.scheme == ShareScheme::Prime256 && !first.data.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(ShamirError::InconsistentShares);
     }
+    Ok(())
+}
 
-    // Use exactly k shares
-    let selected = &shares[..k];
-    let num_blocks = selected[0].data.len() / BLOCK_SIZE;
+/// Reconstruction weights for a fixed set of share ids.
+///
+/// The Lagrange weights (or, for Reed-Solomon, the inverted generator
+/// rows) depend only on which share ids are combined, not on the data.
+/// Build one per id set and reuse it for every block of every chunk
+/// pulled from the same hosts, so reconstruction costs a weighted sum per
+/// block instead of k inversions.
+#[derive(Debug, Clone)]
+pub struct Reconstructor {
+    scheme: ShareScheme,
+    ids: Vec<u32>,
+    weights: Weights,
+}
+
+#[derive(Debug, Clone)]
+enum Weights {
+    Prime256(Vec<FieldElement>),
+    Gf256(Vec<Gf256>),
+    /// `None` when the ids are exactly the data shards.
+    ReedSolomon(Option<Vec<Vec<Gf256>>>),
+    Ristretto255(Vec<curve25519_dalek::scalar::Scalar>),
+    Hybrid {
+        key: Vec<FieldElement>,
+        shards: Option<Vec<Vec<Gf256>>>,
+    },
+}
 
-    let mut result = Vec::with_capacity(num_blocks * BLOCK_SIZE);
+impl Reconstructor {
+    /// Precompute weights for shares with these ids; their count is the
+    /// threshold.
+    pub fn new(scheme: ShareScheme, ids: &[u32]) -> Result<Self, ShamirError> {
+        if ids.len() < 2 {
+            return Err(ShamirError::ThresholdTooLow { k: ids.len() });
+        }
+        let mut seen = std::collections::HashSet::new();
+        for &id in ids {
+            if !seen.insert(id) {
+                return Err(ShamirError::DuplicateShareId(id));
+            }
+        }
+        if matches!(
+            scheme,
+            ShareScheme::Gf256 | ShareScheme::ReedSolomon | ShareScheme::Hybrid
+        ) {
+            if let Some(&bad) = ids
+                .iter()
+                .find(|&&id| id == 0 || id as usize > GF256_MAX_SHARES)
+            {
+                return Err(ShamirError::InvalidShareId(bad));
+            }
+        }
 
-    for block_idx in 0..num_blocks {
-        let offset = block_idx * BLOCK_SIZE;
+        let weights = match scheme {
+            ShareScheme::Prime256 => Weights::Prime256(prime256_weights(ids)),
+            ShareScheme::Gf256 => Weights::Gf256(gf256_weights(ids)),
+            ShareScheme::ReedSolomon => Weights::ReedSolomon(reed_solomon::decoding_matrix(ids)),
+            ShareScheme::Ristretto255 => Weights::Ristretto255(vss::lagrange_weights(ids)),
+            ShareScheme::Hybrid => Weights::Hybrid {
+                key: prime256_weights(ids),
+                shards: reed_solomon::decoding_matrix(ids),
+            },
+        };
+        Ok(Self {
+            scheme,
+            ids: ids.to_vec(),
+            weights,
+        })
+    }
+
+    /// Scheme the weights were computed for.
+    pub fn scheme(&self) -> ShareScheme {
+        self.scheme
+    }
+
+    /// Share ids this reconstructor combines.
+    pub fn ids(&self) -> &[u32] {
+        &self.ids
+    }
 
-        // Collect (x_i, y_i) points for this block
-        let points: Vec<(FieldElement, FieldElement)> = selected
+    /// Reconstruct from `shares`, which must include every id in
+    /// [`Reconstructor::ids`]; other shares are ignored.
+    pub fn reconstruct(&self, shares: &[Share]) -> Result<Vec<u8>, ShamirError> {
+        let found: Vec<&Share> = self
+            .ids
             .iter()
-            .map(|s| {
-                let x = FieldElement::from_u64(s.id as u64);
-                let y = FieldElement::from_bytes_be(&s.data[offset..offset + BLOCK_SIZE]);
-                (x, y)
-            })
+            .filter_map(|id| shares.iter().find(|s| s.id == *id))
             .collect();
+        if found.len() < self.ids.len() {
+            return Err(ShamirError::NotEnoughShares {
+                have: found.len(),
+                need: self.ids.len(),
+            });
+        }
+        check_headers(&found)?;
+        if found[0].scheme != self.scheme {
+            return Err(ShamirError::MixedSchemes);
+        }
+        let recorded_k = found[0].k as usize;
+        if recorded_k > self.ids.len() {
+            return Err(ShamirError::NotEnoughShares {
+                have: self.ids.len(),
+                need: recorded_k,
+            });
+        }
+        self.apply(&found, false)
+    }
 
-        // Lagrange interpolation at x = 0
-        let secret_elem = lagrange_interpolate_at_zero(&points);
-        result.extend_from_slice(&secret_elem.to_bytes_be());
+    /// Combine `selected`, ordered as `self.ids`, and cut to the secret length.
+    fn apply(&self, selected: &[&Share], parallel: bool) -> Result<Vec<u8>, ShamirError> {
+        let mut result = match &self.weights {
+            Weights::Prime256(w) => unpack_payload(
+                interpolate_prime256(selected, w, parallel),
+                selected[0].block_payload,
+            )?,
+            Weights::Gf256(w) => interpolate_gf256(selected, w),
+            Weights::ReedSolomon(inverse) => {
+                reed_solomon::decode_with(selected, inverse.as_deref())
+            }
+            Weights::Ristretto255(w) => vss::interpolate_ristretto255(selected, w)?,
+            Weights::Hybrid { key, shards } => {
+                hybrid::decode_with(selected, key, shards.as_deref())?
+            }
+        };
+        truncate_to_secret_len(&mut result, selected[0].secret_len)?;
+        Ok(result)
     }
+}
 
-    Ok(result)
+/// Strip the zero high byte that keeps each 31-byte `Prime256` payload
+/// below p. Full 32-byte blocks are returned as interpolated.
+fn unpack_payload(blocks: Vec<u8>, payload: u8) -> Result<Vec<u8>, ShamirError> {
+    match payload as usize {
+        BLOCK_SIZE => Ok(blocks),
+        PAYLOAD_SIZE => {
+            let mut result = Vec::with_capacity(blocks.len() / BLOCK_SIZE * PAYLOAD_SIZE);
+            for block in blocks.chunks(BLOCK_SIZE) {
+                // Shares of a 31-byte payload interpolate below 2^248.
+                if block[0] != 0 {
+                    return Err(ShamirError::InconsistentShares);
+                }
+                result.extend_from_slice(&block[1..]);
+            }
+            Ok(result)
+        }
+        other => Err(ShamirError::InvalidEncoding(format!(
+            "unsupported Prime256 block payload of {other} bytes"
+        ))),
+    }
 }
 
-/// Evaluate polynomial with coefficients `coeffs` at point `x` using Horner's method.
-/// coeffs[0] is the constant term, coeffs[k-1] is the highest degree.
-fn eval_poly(coeffs: &[FieldElement], x: &FieldElement) -> FieldElement {
-    let mut result = FieldElement::zero();
-    for coeff in coeffs.iter().rev() {
-        result = &(&result * x) + coeff;
+/// Cut reconstructed output back to the recorded secret length, if any.
+pub(crate) fn truncate_to_secret_len(
+    result: &mut Vec<u8>,
+    secret_len: u64,
+) -> Result<(), ShamirError> {
+    if secret_len == 0 {
+        return Ok(());
     }
-    result
+    if (result.len() as u64) < secret_len {
+        return Err(ShamirError::InvalidEncoding(format!(
+            "share data covers {} bytes, header says {secret_len}",
+            result.len()
+        )));
+    }
+    result.truncate(secret_len as usize);
+    Ok(())
 }
 
-/// Lagrange interpolation at x=0 to recover the secret.
-fn lagrange_interpolate_at_zero(points: &[(FieldElement, FieldElement)]) -> FieldElement {
-    let k = points.len();
-    let mut secret = FieldElement::zero();
+/// Lagrange weights at x = 0: L_i(0) = prod_{j!=i} (0 - x_j) / (x_i - x_j).
+/// The signs cancel, leaving prod_{j!=i} x_j / (x_j - x_i).
+fn prime256_weights(ids: &[u32]) -> Vec<FieldElement> {
+    let xs: Vec<FieldElement> = ids
+        .iter()
+        .map(|&id| FieldElement::from_u64(id as u64))
+        .collect();
+    xs.iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            let mut numerator = FieldElement::one();
+            let mut denominator = FieldElement::one();
+            for (j, &xj) in xs.iter().enumerate() {
+                if i != j {
+                    numerator = numerator * xj;
+                    denominator = denominator * (xj - xi);
+                }
+            }
+            numerator / denominator
+        })
+        .collect()
+}
+
+pub(crate) fn interpolate_prime256(
+    selected: &[&Share],
+    weights: &[FieldElement],
+    parallel: bool,
+) -> Vec<u8> {
+    let num_blocks = selected[0].data.len() / BLOCK_SIZE;
+
+    let blocks = map_blocks(num_blocks, parallel, |block_idx| {
+        let offset = block_idx * BLOCK_SIZE;
+        selected
+            .iter()
+            .zip(weights)
+            .fold(FieldElement::zero(), |acc, (s, &w)| {
+                acc + w * FieldElement::from_bytes_be(&s.data[offset..offset + BLOCK_SIZE])
+            })
+            .to_bytes_be()
+    });
 
-    for i in 0..k {
-        let (ref xi, ref yi) = points[i];
-        let mut numerator = FieldElement::one();
-        let mut denominator = FieldElement::one();
+    blocks.concat()
+}
 
-        for (j, (ref xj, _)) in points.iter().enumerate() {
-            if i == j {
-                continue;
+/// Lagrange weights at x = 0 over GF(2^8), where x_j - x_i is x_j ^ x_i.
+fn gf256_weights(ids: &[u32]) -> Vec<Gf256> {
+    let xs: Vec<Gf256> = ids.iter().map(|&id| Gf256(id as u8)).collect();
+    xs.iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            let mut num = Gf256::one();
+            let mut den = Gf256::one();
+            for (j, &xj) in xs.iter().enumerate() {
+                if i != j {
+                    num = num * xj;
+                    den = den * (xj - xi);
+                }
             }
-            // numerator *= x_j (evaluating at x=0, so (0 - x_j) = -x_j, but
-            // the sign cancels in num/denom, so we use x_j directly with the
-            // standard formula: L_i(0) = prod_{j!=i} (0 - x_j) / (x_i - x_j)
-            //                           = prod_{j!=i} x_j / (x_j - x_i)
-            numerator = &numerator * xj;
-            denominator = &denominator * &(xj - xi);
-        }
+            num / den
+        })
+        .collect()
+}
 
-        let lagrange_coeff = &(yi * &numerator) / &denominator;
-        secret = &secret + &lagrange_coeff;
-    }
+fn interpolate_gf256(selected: &[&Share], weights: &[Gf256]) -> Vec<u8> {
+    let len = selected[0].data.len();
+    (0..len)
+        .map(|pos| {
+            selected
+                .iter()
+                .zip(weights)
+                .fold(Gf256::zero(), |acc, (s, &w)| acc + w * Gf256(s.data[pos]))
+                .0
+        })
+        .collect()
+}
 
-    secret
+/// Evaluate polynomial with coefficients `coeffs` at point `x` using Horner's method.
+/// coeffs[0] is the constant term, coeffs[k-1] is the highest degree.
+fn eval_poly(coeffs: &[FieldElement], x: &FieldElement) -> FieldElement {
+    let mut result = FieldElement::zero();
+    for coeff in coeffs.iter().rev() {
+        result = result * *x + *coeff;
+    }
+    result
 }
 
 #[cfg(test)]
@@ -268,6 +685,188 @@ mod tests {
         assert_eq!(&r1[..secret.len()], secret);
     }
 
+    #[test]
+    fn test_gf256_exact_length_roundtrip() {
+        let secret = b"odd-length secret, no padding\0\0";
+        let shares = split_with(secret, 3, 5, ShareScheme::Gf256).unwrap();
+        for share in &shares {
+            assert_eq!(share.data.len(), secret.len());
+            assert_eq!(share.scheme, ShareScheme::Gf256);
+        }
+        for combo in combinations(&shares, 3) {
+            assert_eq!(reconstruct(&combo, 3).unwrap(), secret);
+        }
+    }
+
+    #[test]
+    fn test_gf256_too_many_shares() {
+        assert!(matches!(
+            split_with(b"x", 3, 256, ShareScheme::Gf256),
+            Err(ShamirError::TooManyShares { n: 256, max: 255 })
+        ));
+    }
+
+    #[test]
+    fn test_gf256_invalid_share_id() {
+        let mut shares = split_with(b"secret", 2, 3, ShareScheme::Gf256).unwrap();
+        shares[0].id = 300;
+        assert!(matches!(
+            reconstruct(&shares, 2),
+            Err(ShamirError::InvalidShareId(300))
+        ));
+    }
+
+    #[test]
+    fn test_mixed_schemes_rejected() {
+        let prime = split(b"secret", 2, 3).unwrap();
+        let bytes = split_with(b"secret", 2, 3, ShareScheme::Gf256).unwrap();
+        let mixed = vec![prime[0].clone(), bytes[1].clone()];
+        assert!(matches!(
+            reconstruct(&mixed, 2),
+            Err(ShamirError::MixedSchemes)
+        ));
+    }
+
+    #[test]
+    fn test_exact_length_with_trailing_zeros() {
+        let secret = [0xAB, 0x00, 0x00, 0x00];
+        let shares = split(&secret, 2, 3).unwrap();
+        assert_eq!(shares[0].data.len(), 32);
+        assert_eq!(reconstruct(&shares[1..], 2).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_recorded_threshold_enforced() {
+        let shares = split(b"needs three", 3, 5).unwrap();
+        assert!(matches!(
+            reconstruct(&shares[..2], 2),
+            Err(ShamirError::NotEnoughShares { have: 2, need: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_inconsistent_headers_rejected() {
+        let mut shares = split(b"header mismatch", 2, 3).unwrap();
+        shares[1].secret_len += 1;
+        assert!(matches!(
+            reconstruct(&shares, 2),
+            Err(ShamirError::InconsistentShares)
+        ));
+    }
+
+    #[test]
+    fn test_short_share_rejected() {
+        for scheme in [ShareScheme::Prime256, ShareScheme::Gf256] {
+            let mut shares = split_with(&[0x5A; 40], 2, 3, scheme).unwrap();
+            shares[1].data.truncate(8);
+            assert!(matches!(
+                reconstruct(&shares, 2),
+                Err(ShamirError::InconsistentShares)
+            ));
+            let reconstructor = Reconstructor::new(scheme, &[1, 2]).unwrap();
+            assert!(matches!(
+                reconstructor.reconstruct(&shares),
+                Err(ShamirError::InconsistentShares)
+            ));
+        }
+
+        // Equal lengths, but not whole Prime256 blocks.
+        let mut shares = split(b"ragged", 2, 3).unwrap();
+        for share in &mut shares {
+            share.data.pop();
+        }
+        assert!(matches!(
+            reconstruct(&shares, 2),
+            Err(ShamirError::InconsistentShares)
+        ));
+    }
+
+    #[test]
+    fn test_legacy_share_json_defaults_to_prime256() {
+        let share: Share = serde_json::from_str(r#"{"id":1,"data":[1,2,3]}"#).unwrap();
+        assert_eq!(share.scheme, ShareScheme::Prime256);
+        assert_eq!(share.secret_len, 0);
+        assert_eq!(share.block_payload as usize, BLOCK_SIZE);
+    }
+
+    #[test]
+    fn test_blocks_above_modulus_roundtrip() {
+        // 0xff..ff is above p = 2^256 - 189 and would reduce as a whole
+        // 32-byte block.
+        let mut secret = [0xff; 3 * BLOCK_SIZE];
+        secret[40] = 0x00;
+        for len in [BLOCK_SIZE, PAYLOAD_SIZE, 2 * BLOCK_SIZE + 1, secret.len()] {
+            let shares = split(&secret[..len], 3, 5).unwrap();
+            assert_eq!(shares[0].block_payload as usize, PAYLOAD_SIZE);
+            assert_eq!(reconstruct(&shares[2..], 3).unwrap(), &secret[..len]);
+        }
+
+        // Legacy full-width blocks still reconstruct as stored.
+        let legacy = split_field_elements(&[0x11; BLOCK_SIZE], 2, 3).unwrap();
+        assert_eq!(legacy[0].block_payload as usize, BLOCK_SIZE);
+        assert_eq!(reconstruct(&legacy[1..], 2).unwrap(), [0x11; BLOCK_SIZE]);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_serial() {
+        let secret: Vec<u8> = (0..10_000).map(|i| (i % 253) as u8).collect();
+        let shares = split_par(&secret, 3, 5, ShareScheme::Prime256).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|s| s.data.len() == 323 * BLOCK_SIZE));
+        assert_eq!(reconstruct(&shares[..3], 3).unwrap(), secret);
+        assert_eq!(reconstruct_par(&shares[2..], 3).unwrap(), secret);
+
+        let gf = split_par(&secret, 3, 5, ShareScheme::Gf256).unwrap();
+        assert_eq!(reconstruct_par(&gf[1..4], 3).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_reconstructor_reused_across_chunks() {
+        for scheme in [
+            ShareScheme::Prime256,
+            ShareScheme::Gf256,
+            ShareScheme::ReedSolomon,
+            ShareScheme::Ristretto255,
+            ShareScheme::Hybrid,
+        ] {
+            let reconstructor = Reconstructor::new(scheme, &[5, 2, 4]).unwrap();
+            for chunk in [&b"first chunk"[..], &[0xEE; 100], b"third"] {
+                let shares = split_with(chunk, 3, 5, scheme).unwrap();
+                // Extra and out-of-order shares are fine as long as ids 2, 4, 5 are present.
+                assert_eq!(
+                    reconstructor.reconstruct(&shares).unwrap(),
+                    chunk,
+                    "{scheme:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_reconstructor_missing_id() {
+        let reconstructor = Reconstructor::new(ShareScheme::Prime256, &[1, 2, 3]).unwrap();
+        let shares = split(b"missing", 3, 5).unwrap();
+        assert!(matches!(
+            reconstructor.reconstruct(&shares[1..]),
+            Err(ShamirError::NotEnoughShares { have: 2, need: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_reconstructor_scheme_mismatch() {
+        let reconstructor = Reconstructor::new(ShareScheme::Gf256, &[1, 2]).unwrap();
+        let shares = split(b"prime shares", 2, 3).unwrap();
+        assert!(matches!(
+            reconstructor.reconstruct(&shares),
+            Err(ShamirError::MixedSchemes)
+        ));
+        assert!(matches!(
+            Reconstructor::new(ShareScheme::Gf256, &[1, 1]),
+            Err(ShamirError::DuplicateShareId(1))
+        ));
+    }
+
     /// Generate all k-element combinations from a slice.
     fn combinations<T: Clone>(items: &[T], k: usize) -> Vec<Vec<T>> {
         if k == 0 {
@@ -0,0 +1,321 @@
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_TABLE;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::{IsIdentity, VartimeMultiscalarMul};
+use gitgold_core::error::ShamirError;
+use rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::hash::sha256;
+use crate::shamir::{Share, ShareScheme};
+
+/// Secret bytes per block: 31 bytes always fit below the group order.
+pub(crate) const SECRET_BLOCK_SIZE: usize = 31;
+
+/// Share bytes per block: the value f(x) followed by the blinding r(x),
+/// both as canonical little-endian scalars.
+pub(crate) const SHARE_BLOCK_SIZE: usize = 64;
+
+/// Domain tag for deriving the second Pedersen generator.
+const H_DOMAIN: &[u8] = b"gitgold/vss/pedersen-h";
+
+/// Public Pedersen commitments to a dealer's polynomials.
+///
+/// For every block and every coefficient index j this holds
+/// `C_j = a_j * G + b_j * H`, where `a` is the secret polynomial and `b` a
+/// random blinding polynomial. Commitments hide the secret, so they can be
+/// published next to the shares.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commitments {
+    /// Threshold the polynomials were generated with (their degree + 1).
+    pub k: u32,
+    /// Length of the shared secret in bytes.
+    pub secret_len: u64,
+    /// Compressed points, block-major: `points[block * k + j]` is `C_j`.
+    pub points: Vec<[u8; 32]>,
+}
+
+impl Commitments {
+    /// Number of blocks covered.
+    pub fn block_count(&self) -> usize {
+        if self.k == 0 {
+            0
+        } else {
+            self.points.len() / self.k as usize
+        }
+    }
+}
+
+/// Split `secret` into `n` verifiable shares where any `k` reconstruct it.
+///
+/// Shares use `ShareScheme::Ristretto255` and reconstruct through
+/// `shamir::reconstruct` like any other scheme. The returned commitments
+/// let each holder check its share with [`verify_share`]. They cost `32 * k`
+/// bytes per 31-byte block, so publish them per chunk rather than per repo.
+pub fn split_verifiable(
+    secret: &[u8],
+    k: usize,
+    n: usize,
+) -> Result<(Vec<Share>, Commitments), ShamirError> {
+    if secret.is_empty() {
+        return Err(ShamirError::EmptySecret);
+    }
+    if k < 2 {
+        return Err(ShamirError::ThresholdTooLow { k });
+    }
+    if n < k {
+        return Err(ShamirError::InsufficientShares { k, n });
+    }
+
+    let h = pedersen_h();
+    let num_blocks = secret.len().div_ceil(SECRET_BLOCK_SIZE);
+    let mut shares: Vec<Share> = (1..=n as u32)
+        .map(|id| Share {
+            id,
+            data: Vec::with_capacity(num_blocks * SHARE_BLOCK_SIZE),
+            scheme: ShareScheme::Ristretto255,
+            k: k as u32,
+            n: n as u32,
+            secret_len: secret.len() as u64,
+            block_payload: 0,
+        })
+        .collect();
+    let mut points = Vec::with_capacity(num_blocks * k);
+
+    let mut rng = thread_rng();
+    let mut random_scalar = || {
+        let mut wide = [0u8; 64];
+        rng.fill_bytes(&mut wide);
+        Scalar::from_bytes_mod_order_wide(&wide)
+    };
+
+    for block in secret.chunks(SECRET_BLOCK_SIZE) {
+        let mut bytes = [0u8; 32];
+        bytes[..block.len()].copy_from_slice(block);
+
+        let mut coeffs = Vec::with_capacity(k);
+        coeffs.push(Scalar::from_bytes_mod_order(bytes));
+        coeffs.extend((1..k).map(|_| random_scalar()));
+        let blinding: Vec<Scalar> = (0..k).map(|_| random_scalar()).collect();
+
+        for (a, b) in coeffs.iter().zip(&blinding) {
+            let c = a * RISTRETTO_BASEPOINT_TABLE + h * b;
+            points.push(c.compress().to_bytes());
+        }
+
+        for share in shares.iter_mut() {
+            let x = Scalar::from(share.id);
+            share
+                .data
+                .extend_from_slice(eval_poly(&coeffs, &x).as_bytes());
+            share
+                .data
+                .extend_from_slice(eval_poly(&blinding, &x).as_bytes());
+        }
+    }
+
+    let commitments = Commitments {
+        k: k as u32,
+        secret_len: secret.len() as u64,
+        points,
+    };
+    Ok((shares, commitments))
+}
+
+/// Check a share against the dealer's published commitments.
+///
+/// Succeeds iff, for every block, `f(x) * G + r(x) * H` equals
+/// `sum_j C_j * x^j` at the share's id `x`.
+pub fn verify_share(share: &Share, commitments: &Commitments) -> Result<(), ShamirError> {
+    if share.scheme != ShareScheme::Ristretto255 {
+        return Err(ShamirError::MixedSchemes);
+    }
+    if share.id == 0 {
+        return Err(ShamirError::InvalidShareId(0));
+    }
+    let k = commitments.k as usize;
+    if k < 2 || !commitments.points.len().is_multiple_of(k) {
+        return Err(ShamirError::InvalidCommitments(format!(
+            "{} points for threshold {k}",
+            commitments.points.len()
+        )));
+    }
+    if (share.k, share.secret_len) != (commitments.k, commitments.secret_len)
+        || share.data.len() != commitments.block_count() * SHARE_BLOCK_SIZE
+    {
+        return Err(ShamirError::InconsistentShares);
+    }
+
+    let points = commitments
+        .points
+        .iter()
+        .map(|bytes| {
+            CompressedRistretto(*bytes)
+                .decompress()
+                .ok_or_else(|| ShamirError::InvalidCommitments("invalid point".to_string()))
+        })
+        .collect::<Result<Vec<RistrettoPoint>, _>>()?;
+
+    // Negated powers of x, so each block is a single multiscalar
+    // multiplication that must land on the identity.
+    let x = Scalar::from(share.id);
+    let mut neg_powers = Vec::with_capacity(k);
+    let mut power = Scalar::ONE;
+    for _ in 0..k {
+        neg_powers.push(-power);
+        power *= x;
+    }
+
+    let h = pedersen_h();
+    for (block, (data, block_points)) in share
+        .data
+        .chunks(SHARE_BLOCK_SIZE)
+        .zip(points.chunks(k))
+        .enumerate()
+    {
+        let failed = || ShamirError::ShareVerificationFailed {
+            id: share.id,
+            block,
+        };
+        let value = scalar_from_slice(&data[..32]).ok_or_else(failed)?;
+        let blind = scalar_from_slice(&data[32..]).ok_or_else(failed)?;
+
+        let scalars = [value, blind].into_iter().chain(neg_powers.iter().copied());
+        let bases = [RISTRETTO_BASEPOINT_TABLE.basepoint(), h]
+            .into_iter()
+            .chain(block_points.iter().copied());
+        if !RistrettoPoint::vartime_multiscalar_mul(scalars, bases).is_identity() {
+            return Err(failed());
+        }
+    }
+    Ok(())
+}
+
+/// Lagrange weights at zero for the given share ids.
+pub(crate) fn lagrange_weights(ids: &[u32]) -> Vec<Scalar> {
+    let xs: Vec<Scalar> = ids.iter().map(|&id| Scalar::from(id)).collect();
+    xs.iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            let mut num = Scalar::ONE;
+            let mut den = Scalar::ONE;
+            for (j, &xj) in xs.iter().enumerate() {
+                if i != j {
+                    num *= xj;
+                    den *= xj - xi;
+                }
+            }
+            num * den.invert()
+        })
+        .collect()
+}
+
+/// Interpolate the value half of each block at zero, with `weights` from
+/// [`lagrange_weights`] over the ids of `selected`, in the same order.
+pub(crate) fn interpolate_ristretto255(
+    selected: &[&Share],
+    weights: &[Scalar],
+) -> Result<Vec<u8>, ShamirError> {
+    let num_blocks = selected[0].data.len() / SHARE_BLOCK_SIZE;
+    let mut result = Vec::with_capacity(num_blocks * SECRET_BLOCK_SIZE);
+    for block in 0..num_blocks {
+        let offset = block * SHARE_BLOCK_SIZE;
+        let mut secret = Scalar::ZERO;
+        for (share, w) in selected.iter().zip(weights) {
+            let y = scalar_from_slice(&share.data[offset..offset + 32]).ok_or_else(|| {
+                ShamirError::InvalidEncoding(format!(
+                    "share {} has a non-canonical scalar",
+                    share.id
+                ))
+            })?;
+            secret += w * y;
+        }
+        result.extend_from_slice(&secret.as_bytes()[..SECRET_BLOCK_SIZE]);
+    }
+    Ok(result)
+}
+
+/// Parse a canonical little-endian scalar.
+pub(crate) fn scalar_from_slice(bytes: &[u8]) -> Option<Scalar> {
+    let bytes: [u8; 32] = bytes.try_into().ok()?;
+    Scalar::from_canonical_bytes(bytes).into()
+}
+
+/// Second generator with no known discrete log relative to G, derived by
+/// hashing a fixed domain tag to the group.
+fn pedersen_h() -> RistrettoPoint {
+    let mut wide = [0u8; 64];
+    wide[..32].copy_from_slice(&sha256(&[H_DOMAIN, b"/0"].concat()));
+    wide[32..].copy_from_slice(&sha256(&[H_DOMAIN, b"/1"].concat()));
+    RistrettoPoint::from_uniform_bytes(&wide)
+}
+
+fn eval_poly(coeffs: &[Scalar], x: &Scalar) -> Scalar {
+    coeffs
+        .iter()
+        .rev()
+        .fold(Scalar::ZERO, |acc, coeff| acc * x + coeff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::reconstruct;
+
+    #[test]
+    fn test_honest_shares_verify() {
+        let secret = b"verifiable secret sharing spans more than one block";
+        let (shares, commitments) = split_verifiable(secret, 3, 5).unwrap();
+        assert_eq!(commitments.block_count(), 2);
+        for share in &shares {
+            verify_share(share, &commitments).unwrap();
+        }
+        assert_eq!(reconstruct(&shares[1..4], 3).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_tampered_share_detected() {
+        let (mut shares, commitments) = split_verifiable(&[9u8; 70], 2, 4).unwrap();
+        // Second block's value scalar; keep it canonical by touching byte 0.
+        shares[2].data[SHARE_BLOCK_SIZE] ^= 0x01;
+        assert!(matches!(
+            verify_share(&shares[2], &commitments),
+            Err(ShamirError::ShareVerificationFailed { id: 3, block: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_share_from_other_dealing_rejected() {
+        let (shares, _) = split_verifiable(b"first dealing", 2, 3).unwrap();
+        let (_, other) = split_verifiable(b"other dealing", 2, 3).unwrap();
+        assert!(matches!(
+            verify_share(&shares[0], &other),
+            Err(ShamirError::ShareVerificationFailed { id: 1, block: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_wrong_id_rejected() {
+        let (mut shares, commitments) = split_verifiable(b"relabelled", 2, 3).unwrap();
+        shares[0].id = 2;
+        assert!(verify_share(&shares[0], &commitments).is_err());
+    }
+
+    #[test]
+    fn test_malformed_commitments() {
+        let (shares, mut commitments) = split_verifiable(b"short", 2, 3).unwrap();
+        commitments.points.pop();
+        assert!(matches!(
+            verify_share(&shares[0], &commitments),
+            Err(ShamirError::InvalidCommitments(_))
+        ));
+    }
+
+    #[test]
+    fn test_exact_length_roundtrip() {
+        let secret = [0u8; 62];
+        let (shares, _) = split_verifiable(&secret, 2, 2).unwrap();
+        assert_eq!(shares[0].data.len(), 2 * SHARE_BLOCK_SIZE);
+        assert_eq!(reconstruct(&shares, 2).unwrap(), secret);
+    }
+}
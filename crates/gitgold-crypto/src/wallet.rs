@@ -1,10 +1,21 @@
+use std::path::Path;
+
+use gitgold_core::error::{KeyDerivationError, KeystoreError};
 use gitgold_core::types::Address;
+use zeroize::ZeroizeOnDrop;
 
+use crate::hd::{self, ExtendedKey};
 use crate::keys::{KeyPair, PublicKey};
+use crate::keystore::{KdfParams, Keystore};
 
-/// Minimal wallet holding a key pair.
+/// Minimal wallet holding a key pair. The secret key is zeroized on drop.
+///
+/// Wallets restored from a mnemonic are hierarchical: they keep the
+/// account node `m/44'/18247'/0'` and can derive any number of child
+/// keys, with child 0 as the wallet's own key pair.
 pub struct Wallet {
     key_pair: KeyPair,
+    account: Option<ExtendedKey>,
 }
 
 impl Wallet {
@@ -12,6 +23,7 @@ impl Wallet {
     pub fn new() -> Self {
         Self {
             key_pair: KeyPair::generate(),
+            account: None,
         }
     }
 
@@ -19,7 +31,51 @@ impl Wallet {
     pub fn from_secret(bytes: &[u8; 32]) -> Self {
         Self {
             key_pair: KeyPair::from_bytes(bytes),
+            account: None,
+        }
+    }
+
+    /// Create a hierarchical wallet with a fresh mnemonic of `word_count`
+    /// words. Returns the wallet and the phrase to back up.
+    pub fn generate_hd(word_count: usize) -> Result<(Self, String), KeyDerivationError> {
+        let phrase = hd::generate_mnemonic(word_count)?;
+        let wallet = Self::from_mnemonic(&phrase, "")?;
+        Ok((wallet, phrase))
+    }
+
+    /// Restore a hierarchical wallet from a BIP39 phrase and optional
+    /// passphrase ("" for none). The same inputs always give the same keys.
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self, KeyDerivationError> {
+        let seed = hd::mnemonic_to_seed(phrase, passphrase)?;
+        let account = ExtendedKey::master(seed.as_slice()).derive_path(&hd::account_path(0))?;
+        Ok(Self {
+            key_pair: account.derive_child(0).key_pair(),
+            account: Some(account),
+        })
+    }
+
+    /// Whether this wallet can derive child keys.
+    pub fn is_hierarchical(&self) -> bool {
+        self.account.is_some()
+    }
+
+    /// Child key pair `index` (hardened) under the wallet's account.
+    pub fn derive_key_pair(&self, index: u32) -> Result<KeyPair, KeyDerivationError> {
+        let account = self
+            .account
+            .as_ref()
+            .ok_or(KeyDerivationError::NotHierarchical)?;
+        if index >= hd::HARDENED {
+            return Err(KeyDerivationError::InvalidPath(format!(
+                "child index {index} out of range"
+            )));
         }
+        Ok(account.derive_child(index).key_pair())
+    }
+
+    /// Address of child key `index`.
+    pub fn derive_address(&self, index: u32) -> Result<Address, KeyDerivationError> {
+        Ok(self.derive_key_pair(index)?.address())
     }
 
     /// Get the wallet's address.
@@ -41,8 +97,35 @@ impl Wallet {
     pub fn secret_bytes(&self) -> [u8; 32] {
         self.key_pair.secret_bytes()
     }
+
+    /// Encrypt the wallet under `password` and write it to `path` as a
+    /// keystore file (see [`Keystore`]). Only the wallet's own key pair is
+    /// stored; child keys of a hierarchical wallet come from its mnemonic.
+    pub fn save(&self, path: &Path, password: &str) -> Result<(), KeystoreError> {
+        self.save_with(path, password, KdfParams::default())
+    }
+
+    /// [`Wallet::save`] with explicit key derivation cost.
+    pub fn save_with(
+        &self,
+        path: &Path,
+        password: &str,
+        params: KdfParams,
+    ) -> Result<(), KeystoreError> {
+        Keystore::encrypt_with(&self.key_pair, password, params)?.save(path)
+    }
+
+    /// Load and decrypt a wallet written by [`Wallet::save`].
+    pub fn load(path: &Path, password: &str) -> Result<Self, KeystoreError> {
+        Ok(Self {
+            key_pair: Keystore::load(path)?.decrypt(password)?,
+            account: None,
+        })
+    }
 }
 
+impl ZeroizeOnDrop for Wallet {}
+
 impl Default for Wallet {
     fn default() -> Self {
         Self::new()
@@ -61,6 +144,60 @@ mod tests {
         assert!(wallet.public_key().verify(msg, &sig));
     }
 
+    #[test]
+    fn test_wallet_save_load() {
+        let path =
+            std::env::temp_dir().join(format!("gitgold-wallet-test-{}.json", std::process::id()));
+        let wallet = Wallet::new();
+        wallet.save(&path, "hunter2").unwrap();
+
+        let loaded = Wallet::load(&path, "hunter2").unwrap();
+        assert_eq!(loaded.address(), wallet.address());
+        assert!(matches!(
+            Wallet::load(&path, "hunter3"),
+            Err(KeystoreError::WrongPassword)
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_hd_restore_is_deterministic() {
+        let (wallet, phrase) = Wallet::generate_hd(24).unwrap();
+        let restored = Wallet::from_mnemonic(&phrase, "").unwrap();
+        assert_eq!(wallet.address(), restored.address());
+        assert_eq!(wallet.address(), wallet.derive_address(0).unwrap());
+
+        let children: Vec<Address> = (0..5).map(|i| wallet.derive_address(i).unwrap()).collect();
+        for (i, address) in children.iter().enumerate() {
+            assert_eq!(restored.derive_address(i as u32).unwrap(), *address);
+        }
+        let unique: std::collections::HashSet<_> = children.iter().collect();
+        assert_eq!(unique.len(), 5);
+
+        // A passphrase yields an unrelated wallet from the same phrase.
+        let other = Wallet::from_mnemonic(&phrase, "extra").unwrap();
+        assert_ne!(other.address(), wallet.address());
+    }
+
+    #[test]
+    fn test_fixed_mnemonic_addresses() {
+        let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let a = Wallet::from_mnemonic(phrase, "").unwrap();
+        let b = Wallet::from_mnemonic(phrase, "").unwrap();
+        assert_eq!(a.derive_address(7).unwrap(), b.derive_address(7).unwrap());
+        let sig = a.derive_key_pair(3).unwrap().sign(b"repo push");
+        assert!(b.derive_key_pair(3).unwrap().public_key().verify(b"repo push", &sig));
+    }
+
+    #[test]
+    fn test_plain_wallet_cannot_derive() {
+        assert!(!Wallet::new().is_hierarchical());
+        assert!(matches!(
+            Wallet::new().derive_address(0),
+            Err(KeyDerivationError::NotHierarchical)
+        ));
+    }
+
     #[test]
     fn test_wallet_from_secret_roundtrip() {
         let w1 = Wallet::new();
@@ -0,0 +1,253 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gitgold_core::types::{Address, TransactionType};
+use gitgold_crypto::wallet::Wallet;
+use gitgold_ledger::balance::BalanceTracker;
+use gitgold_ledger::block::{self, Block, BlockHeader};
+use gitgold_ledger::store::Ledger;
+use gitgold_ledger::transaction::{Transaction, TX_VERSION};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts live heap bytes and their peak, so the bench can report what a
+/// ledger holds, and needs along the way, as its history grows.
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Heap use from a starting point on.
+struct Heap {
+    start: usize,
+}
+
+impl Heap {
+    fn start() -> Self {
+        let start = LIVE.load(Ordering::Relaxed);
+        PEAK.store(start, Ordering::Relaxed);
+        Self { start }
+    }
+
+    /// Bytes allocated since `start` and still live.
+    fn held(&self) -> usize {
+        LIVE.load(Ordering::Relaxed).saturating_sub(self.start)
+    }
+
+    /// Most bytes live at once since `start`, beyond what was then.
+    fn peak(&self) -> usize {
+        PEAK.load(Ordering::Relaxed).saturating_sub(self.start)
+    }
+}
+
+/// Criterion only measures time, so each bench also prints what the
+/// ledger held to stderr once it finishes, one line per history size:
+///
+/// ```text
+/// heap <case>/<history>: held <KiB> KiB[, peak <KiB> KiB]
+/// ```
+///
+/// `held` is the heap the ledger keeps once the step returns; `peak`,
+/// where shown, the most it needed during the step. Neither should grow
+/// with history.
+#[derive(Default)]
+struct HeapSummary(Vec<String>);
+
+impl HeapSummary {
+    fn held(&mut self, case: &str, history: usize, heap: &Heap) {
+        self.0.push(format!(
+            "heap {case}/{history}: held {:.1} KiB",
+            heap.held() as f64 / 1024.0
+        ));
+    }
+
+    fn held_and_peak(&mut self, case: &str, history: usize, heap: &Heap) {
+        self.0.push(format!(
+            "heap {case}/{history}: held {:.1} KiB, peak {:.1} KiB",
+            heap.held() as f64 / 1024.0,
+            heap.peak() as f64 / 1024.0
+        ));
+    }
+
+    fn print(&self) {
+        for line in &self.0 {
+            eprintln!("{line}");
+        }
+    }
+}
+
+/// History sizes the ledger is grown to before measuring.
+const HISTORY: [usize; 3] = [100_000, 1_000_000, 2_000_000];
+/// Transactions per imported block while growing the history.
+const BLOCK_SIZE: usize = 50_000;
+/// Distinct recipients, so balances stay bounded while history grows.
+const ACCOUNTS: usize = 1_000;
+
+fn mint(id: String, i: usize) -> Transaction {
+    Transaction {
+        tx_id: id,
+        tx_type: TransactionType::Mint,
+        from: Address::system(),
+        to: Address::new(&format!("acct-{}", i % ACCOUNTS)),
+        amount: 1,
+        metadata: serde_json::json!({}),
+        timestamp: 1700000000,
+        nonce: 0,
+        signature: String::new(),
+        pubkey: String::new(),
+        version: TX_VERSION,
+        multisig: None,
+    }
+}
+
+/// Grow `ledger` to `target` transactions with imported blocks, tracking
+/// the expected balances, supply and log to sign each header's state root.
+/// Returns the id of a transaction from the middle of the added history.
+fn grow(ledger: &mut Ledger, producer: &Wallet, target: usize) -> String {
+    if ledger.pending_count() > 0 {
+        ledger.seal_block(producer).unwrap();
+    }
+    let middle = format!("history-{}", (ledger.tx_count() + target) / 2);
+    let mut balances: BalanceTracker = ledger.balances().clone();
+    let mut log = ledger.log().clone();
+    let mut minted = ledger.supply().total_minted();
+    while ledger.tx_count() < target {
+        let first = ledger.tx_count();
+        let txs: Vec<Transaction> = (first..target.min(first + BLOCK_SIZE))
+            .map(|i| mint(format!("history-{i}"), i))
+            .collect();
+        for tx in &txs {
+            balances.credit(&tx.to, tx.amount);
+            minted += tx.amount;
+            log.push(tx.hash());
+        }
+        balances.set_supply(minted, ledger.supply().total_burned());
+        balances.set_log(log.len(), log.root());
+        let head = ledger.head();
+        let header = BlockHeader::sign(
+            head.map_or(0, |h| h.height + 1),
+            head.map_or(block::GENESIS_PREV_HASH, BlockHeader::hash),
+            block::tx_root(&txs),
+            balances.state_root(),
+            head.map_or(1700000000, |h| h.timestamp),
+            producer,
+        );
+        ledger.import_block(Block { header, txs }).unwrap();
+    }
+    middle
+}
+
+/// Append and duplicate-check cost against a ledger holding millions of
+/// transactions in sealed blocks. Duplicate detection is a primary-key
+/// lookup, so neither should grow much with history.
+///
+/// Heap summary (see [`HeapSummary`]): `sealed` is the ledger appended to,
+/// `open` a fresh `Ledger::open` of the same history, replaying all of it
+/// (no snapshots), with the peak reached during replay.
+fn append_history(c: &mut Criterion) {
+    let path = std::env::temp_dir().join(format!("gitgold-append-bench-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let path = path.to_str().unwrap().to_string();
+    let producer = Wallet::new();
+    let producers = [producer.public_key()];
+    let mut summary = HeapSummary::default();
+
+    let heap = Heap::start();
+    let mut ledger = Ledger::open_with_producers(&path, &producers).unwrap();
+    ledger.set_snapshot_interval(0);
+
+    let mut group = c.benchmark_group("append_history");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements(1));
+    let mut next = 0usize;
+    for history in HISTORY {
+        let stored = grow(&mut ledger, &producer, history);
+        summary.held("sealed", history, &heap);
+
+        let open = Heap::start();
+        let reopened = Ledger::open_with_producers(&path, &producers).unwrap();
+        summary.held_and_peak("open", history, &open);
+        drop(reopened);
+
+        group.bench_function(BenchmarkId::new("append", history), |b| {
+            b.iter(|| {
+                next += 1;
+                ledger
+                    .append(black_box(mint(format!("new-{next}"), next)))
+                    .unwrap()
+            })
+        });
+        group.bench_function(BenchmarkId::new("validate_new", history), |b| {
+            let tx = mint("never-stored".to_string(), 0);
+            b.iter(|| ledger.validate(black_box(&tx)).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("reject_duplicate", history), |b| {
+            let tx = mint(stored.clone(), 0);
+            b.iter(|| ledger.validate(black_box(&tx)).unwrap_err())
+        });
+    }
+    group.finish();
+    summary.print();
+
+    drop(ledger);
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Append cost against a ledger whose whole history is unsealed, which
+/// must not make it hold more: pending transactions are kept as a running
+/// Merkle root, not a list.
+///
+/// Heap summary (see [`HeapSummary`]): `unsealed` is the ledger appended to.
+fn unsealed_history(c: &mut Criterion) {
+    let mut summary = HeapSummary::default();
+    let heap = Heap::start();
+    let mut ledger = Ledger::in_memory().unwrap();
+    ledger.set_snapshot_interval(0);
+
+    let mut group = c.benchmark_group("append_unsealed");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements(1));
+    let mut next = 0usize;
+    for history in HISTORY {
+        while ledger.tx_count() < history {
+            let first = ledger.tx_count();
+            let txs: Vec<Transaction> = (first..history.min(first + BLOCK_SIZE))
+                .map(|i| mint(format!("unsealed-{i}"), i))
+                .collect();
+            ledger.append_batch(txs).unwrap();
+        }
+        summary.held("unsealed", history, &heap);
+
+        group.bench_function(BenchmarkId::new("append", history), |b| {
+            b.iter(|| {
+                next += 1;
+                ledger
+                    .append(black_box(mint(format!("new-{next}"), next)))
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+    assert_eq!(ledger.pending_count(), ledger.tx_count());
+    summary.print();
+}
+
+criterion_group!(benches, append_history, unsealed_history);
+criterion_main!(benches);
@@ -1,6 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use gitgold_crypto::hash::sha256;
 use gitgold_ledger::merkle::MerkleTree;
+use gitgold_ledger::smt::SparseMerkleTree;
 
 fn merkle_build(c: &mut Criterion) {
     let leaf_counts = [10, 100, 1_000, 10_000, 100_000];
@@ -86,5 +87,36 @@ fn merkle_from_data(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, merkle_build, merkle_proof, merkle_verify, merkle_from_data,);
+fn smt_update(c: &mut Criterion) {
+    let key_counts = [1_000, 100_000];
+
+    let mut group = c.benchmark_group("smt_update");
+    for &count in &key_counts {
+        let mut tree = SparseMerkleTree::new();
+        for i in 0..count as u64 {
+            tree.update(sha256(&i.to_le_bytes()), Some(sha256(&i.to_be_bytes())));
+        }
+
+        // Overwrite an existing key, as a balance change does
+        let key = sha256(&(count as u64 / 2).to_le_bytes());
+        group.bench_with_input(BenchmarkId::from_parameter(count), &tree, |bench, tree| {
+            let mut tree = tree.clone();
+            let mut n = 0u64;
+            bench.iter(|| {
+                n += 1;
+                tree.update(black_box(key), Some(sha256(&n.to_be_bytes())))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    merkle_build,
+    merkle_proof,
+    merkle_verify,
+    merkle_from_data,
+    smt_update,
+);
 criterion_main!(benches);
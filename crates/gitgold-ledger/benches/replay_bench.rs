@@ -0,0 +1,136 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gitgold_core::types::{Address, TransactionType};
+use gitgold_crypto::keys::{self, BatchItem, KeyPair, PublicKey};
+use gitgold_crypto::wallet::Wallet;
+use gitgold_ledger::store::Ledger;
+use gitgold_ledger::transaction::{Transaction, TX_VERSION};
+
+const TX_COUNTS: [usize; 2] = [1_000, 10_000];
+const SENDERS: usize = 16;
+
+/// Signed transfers from a few funded senders, preceded by their mints.
+fn signed_txs(count: usize) -> Vec<Transaction> {
+    let senders: Vec<KeyPair> = (0..SENDERS).map(|_| KeyPair::generate()).collect();
+    let mut txs: Vec<Transaction> = senders
+        .iter()
+        .map(|kp| Transaction {
+            tx_id: format!("mint-{}", kp.address()),
+            tx_type: TransactionType::Mint,
+            from: Address::system(),
+            to: kp.address(),
+            amount: count as u64,
+            metadata: serde_json::json!({}),
+            timestamp: 1700000000,
+            nonce: 0,
+            signature: String::new(),
+            pubkey: String::new(),
+            version: TX_VERSION,
+            multisig: None,
+        })
+        .collect();
+    for i in 0..count {
+        let kp = &senders[i % SENDERS];
+        let mut tx = Transaction {
+            tx_id: format!("tx-{i}"),
+            tx_type: TransactionType::Transfer,
+            from: kp.address(),
+            to: Address::new("bob"),
+            amount: 1,
+            metadata: serde_json::json!({}),
+            timestamp: 1700000000 + i as i64,
+            nonce: (i / SENDERS) as u64,
+            signature: String::new(),
+            pubkey: hex::encode(kp.public_key().bytes),
+            version: TX_VERSION,
+            multisig: None,
+        };
+        tx.signature = hex::encode(kp.sign(&tx.signable_bytes()));
+        txs.push(tx);
+    }
+    txs
+}
+
+/// Decoded (pubkey, message, signature) triples of the signed transactions.
+fn signatures(txs: &[Transaction]) -> Vec<(PublicKey, Vec<u8>, Vec<u8>)> {
+    txs.iter()
+        .filter(|tx| tx.from != Address::system())
+        .map(|tx| {
+            (
+                PublicKey {
+                    bytes: hex::decode(&tx.pubkey).unwrap(),
+                },
+                tx.signable_bytes(),
+                hex::decode(&tx.signature).unwrap(),
+            )
+        })
+        .collect()
+}
+
+/// Signature checking alone: one at a time (how replay used to verify)
+/// versus a single batch (how it verifies now).
+fn replay_signatures(c: &mut Criterion) {
+    let mut group = c.benchmark_group("replay_signatures");
+    group.sample_size(10);
+    for count in TX_COUNTS {
+        let sigs = signatures(&signed_txs(count));
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(BenchmarkId::new("sequential", count), &sigs, |b, sigs| {
+            b.iter(|| {
+                for (pk, message, signature) in sigs {
+                    assert!(pk.verify(black_box(message), black_box(signature)));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", count), &sigs, |b, sigs| {
+            b.iter(|| {
+                let items: Vec<BatchItem> = sigs
+                    .iter()
+                    .map(|(pubkey, message, signature)| BatchItem {
+                        pubkey,
+                        message,
+                        signature,
+                    })
+                    .collect();
+                keys::verify_batch(black_box(&items)).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// `Ledger::open` replaying every stored transaction, then resuming from
+/// a snapshot at the block sealing all of them (nothing before it is read).
+fn ledger_open(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ledger_open");
+    group.sample_size(10);
+    let producer = Wallet::new();
+    let producers = [producer.public_key()];
+    for count in TX_COUNTS {
+        let path = std::env::temp_dir().join(format!(
+            "gitgold-replay-bench-{count}-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_str().unwrap().to_string();
+        let mut ledger = Ledger::open_with_producers(&path, &producers).unwrap();
+        ledger.set_snapshot_interval(0);
+        ledger.append_batch(signed_txs(count)).unwrap();
+        ledger.seal_block(&producer).unwrap();
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::new("full", count), &path, |b, path| {
+            b.iter(|| Ledger::open_with_producers(black_box(path), &producers).unwrap())
+        });
+        ledger.snapshot().unwrap();
+        drop(ledger);
+        group.bench_with_input(BenchmarkId::new("snapshot", count), &path, |b, path| {
+            b.iter(|| Ledger::open_with_producers(black_box(path), &producers).unwrap())
+        });
+        std::fs::remove_file(&path).unwrap();
+    }
+    group.finish();
+}
+
+criterion_group!(benches, replay_signatures, ledger_open);
+criterion_main!(benches);
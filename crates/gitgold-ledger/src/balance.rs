@@ -1,18 +1,74 @@
 use gitgold_core::error::LedgerError;
-use gitgold_core::types::{Address, MicroGitGold};
+use gitgold_core::types::{Address, Hash256, MicroGitGold};
+use gitgold_crypto::hash::sha256;
 use std::collections::HashMap;
 
-/// Tracks balances for all addresses.
+use crate::smt::{SparseMerkleProof, SparseMerkleTree};
+
+/// Domain separators for leaves (and non-balance keys) in the state tree.
+const BALANCE_DOMAIN: &[u8] = b"gitgold/balance";
+const NONCE_DOMAIN: &[u8] = b"gitgold/nonce";
+const SUPPLY_DOMAIN: &[u8] = b"gitgold/supply";
+const LOG_DOMAIN: &[u8] = b"gitgold/log";
+
+/// Tracks balances and the next expected nonce for all addresses.
+///
+/// Both are also kept in a [`SparseMerkleTree`], updated as they change,
+/// whose root commits to every non-zero balance and nonce. The ledger adds
+/// its supply totals and transaction log to the same tree (see
+/// [`BalanceTracker::set_supply`] and [`BalanceTracker::set_log`]), so one
+/// root covers the whole ledger state.
 #[derive(Debug, Clone)]
 pub struct BalanceTracker {
     balances: HashMap<Address, MicroGitGold>,
+    nonces: HashMap<Address, u64>,
+    state: SparseMerkleTree,
 }
 
 impl BalanceTracker {
     pub fn new() -> Self {
         Self {
             balances: HashMap::new(),
+            nonces: HashMap::new(),
+            state: SparseMerkleTree::new(),
+        }
+    }
+
+    /// Rebuild a tracker from saved balances and next nonces.
+    pub fn from_parts(
+        balances: impl IntoIterator<Item = (Address, MicroGitGold)>,
+        nonces: impl IntoIterator<Item = (Address, u64)>,
+    ) -> Self {
+        let mut tracker = Self::new();
+        for (addr, amount) in balances {
+            tracker.set_balance(&addr, amount);
         }
+        for (addr, nonce) in nonces {
+            tracker.set_nonce(&addr, nonce);
+        }
+        tracker
+    }
+
+    fn set_balance(&mut self, addr: &Address, amount: MicroGitGold) {
+        self.balances.insert(addr.clone(), amount);
+        self.state.update(account_key(addr), balance_leaf(amount));
+    }
+
+    fn set_nonce(&mut self, addr: &Address, nonce: u64) {
+        self.nonces.insert(addr.clone(), nonce);
+        self.state.update(nonce_key(addr), nonce_leaf(nonce));
+    }
+
+    /// Record the ledger's supply totals in the state tree.
+    pub fn set_supply(&mut self, total_minted: MicroGitGold, total_burned: MicroGitGold) {
+        self.state
+            .update(supply_key(), Some(supply_leaf(total_minted, total_burned)));
+    }
+
+    /// Record the ledger's transaction count and log root in the state tree.
+    pub fn set_log(&mut self, tx_count: usize, log_root: Hash256) {
+        self.state
+            .update(log_key(), Some(log_leaf(tx_count, log_root)));
     }
 
     /// Get balance for an address (0 if unknown).
@@ -22,8 +78,8 @@ impl BalanceTracker {
 
     /// Credit (add) amount to an address.
     pub fn credit(&mut self, addr: &Address, amount: MicroGitGold) {
-        let entry = self.balances.entry(addr.clone()).or_insert(0);
-        *entry = entry.saturating_add(amount);
+        let current = self.balance(addr);
+        self.set_balance(addr, current.saturating_add(amount));
     }
 
     /// Debit (subtract) amount from an address. Fails if insufficient balance.
@@ -35,7 +91,7 @@ impl BalanceTracker {
                 need: amount,
             });
         }
-        self.balances.insert(addr.clone(), current - amount);
+        self.set_balance(addr, current - amount);
         Ok(())
     }
 
@@ -51,10 +107,263 @@ impl BalanceTracker {
         Ok(())
     }
 
+    /// Nonce the next transaction from `addr` must carry (0 if it has never
+    /// sent one).
+    pub fn next_nonce(&self, addr: &Address) -> u64 {
+        self.nonces.get(addr).copied().unwrap_or(0)
+    }
+
+    /// Check that `nonce` is the next expected one for `addr`. Reused and
+    /// skipped-ahead nonces both fail.
+    pub fn check_nonce(&self, addr: &Address, nonce: u64) -> Result<(), LedgerError> {
+        let expected = self.next_nonce(addr);
+        if nonce != expected {
+            return Err(LedgerError::InvalidNonce {
+                address: addr.0.clone(),
+                expected,
+                actual: nonce,
+            });
+        }
+        Ok(())
+    }
+
+    /// Consume `nonce` for `addr`, advancing its next expected nonce. Fails
+    /// without changes if it is not the next expected one.
+    pub fn use_nonce(&mut self, addr: &Address, nonce: u64) -> Result<(), LedgerError> {
+        self.check_nonce(addr, nonce)?;
+        self.set_nonce(addr, nonce + 1);
+        Ok(())
+    }
+
     /// Get all addresses with non-zero balances.
     pub fn all_balances(&self) -> &HashMap<Address, MicroGitGold> {
         &self.balances
     }
+
+    /// Next nonces of every address that has sent a transaction.
+    pub fn all_nonces(&self) -> &HashMap<Address, u64> {
+        &self.nonces
+    }
+
+    /// Root of the state tree. Zero balances and nonces are left out, so it
+    /// depends only on the current state, not on how it was reached.
+    pub fn state_root(&self) -> Hash256 {
+        self.state.root()
+    }
+
+    /// Proof of `addr`'s balance against [`BalanceTracker::state_root`];
+    /// check it with [`verify_balance`]. A zero balance gets a
+    /// non-inclusion proof.
+    pub fn balance_proof(&self, addr: &Address) -> SparseMerkleProof {
+        self.state.proof(&account_key(addr))
+    }
+
+    /// Start staging changes on top of this tracker without touching it.
+    pub fn stage(&self) -> StagedBalances<'_> {
+        StagedBalances {
+            base: self,
+            changes: BalanceChanges::default(),
+        }
+    }
+
+    /// Apply changes taken from [`StagedBalances::into_changes`]. Cannot
+    /// fail: every check already ran while staging.
+    pub fn commit(&mut self, changes: BalanceChanges) {
+        for (addr, amount) in &changes.balances {
+            self.set_balance(addr, *amount);
+        }
+        for (addr, nonce) in &changes.nonces {
+            self.set_nonce(addr, *nonce);
+        }
+        if let Some((minted, burned)) = changes.supply {
+            self.set_supply(minted, burned);
+        }
+        if let Some((tx_count, log_root)) = changes.log {
+            self.set_log(tx_count, log_root);
+        }
+    }
+}
+
+/// Balance and nonce updates staged on top of a [`BalanceTracker`].
+///
+/// Reads see the staged values and writes run the same checks as the
+/// tracker, but nothing reaches it until [`BalanceTracker::commit`].
+/// Dropping the stage discards the changes.
+#[derive(Debug)]
+pub struct StagedBalances<'a> {
+    base: &'a BalanceTracker,
+    changes: BalanceChanges,
+}
+
+/// New balances, next nonces and ledger totals produced by a
+/// [`StagedBalances`].
+#[derive(Debug, Default)]
+pub struct BalanceChanges {
+    balances: HashMap<Address, MicroGitGold>,
+    nonces: HashMap<Address, u64>,
+    supply: Option<(MicroGitGold, MicroGitGold)>,
+    log: Option<(usize, Hash256)>,
+}
+
+impl StagedBalances<'_> {
+    /// Staged balance for an address.
+    pub fn balance(&self, addr: &Address) -> MicroGitGold {
+        match self.changes.balances.get(addr) {
+            Some(&amount) => amount,
+            None => self.base.balance(addr),
+        }
+    }
+
+    /// Stage a credit.
+    pub fn credit(&mut self, addr: &Address, amount: MicroGitGold) {
+        let current = self.balance(addr);
+        self.changes
+            .balances
+            .insert(addr.clone(), current.saturating_add(amount));
+    }
+
+    /// Stage a debit. Fails if the staged balance is insufficient.
+    pub fn debit(&mut self, addr: &Address, amount: MicroGitGold) -> Result<(), LedgerError> {
+        let current = self.balance(addr);
+        if current < amount {
+            return Err(LedgerError::InsufficientBalance {
+                have: current,
+                need: amount,
+            });
+        }
+        self.changes.balances.insert(addr.clone(), current - amount);
+        Ok(())
+    }
+
+    /// Stage a transfer.
+    pub fn transfer(
+        &mut self,
+        from: &Address,
+        to: &Address,
+        amount: MicroGitGold,
+    ) -> Result<(), LedgerError> {
+        self.debit(from, amount)?;
+        self.credit(to, amount);
+        Ok(())
+    }
+
+    /// Staged next nonce for an address.
+    pub fn next_nonce(&self, addr: &Address) -> u64 {
+        match self.changes.nonces.get(addr) {
+            Some(&nonce) => nonce,
+            None => self.base.next_nonce(addr),
+        }
+    }
+
+    /// Stage consuming `nonce` for `addr`; see [`BalanceTracker::use_nonce`].
+    pub fn use_nonce(&mut self, addr: &Address, nonce: u64) -> Result<(), LedgerError> {
+        let expected = self.next_nonce(addr);
+        if nonce != expected {
+            return Err(LedgerError::InvalidNonce {
+                address: addr.0.clone(),
+                expected,
+                actual: nonce,
+            });
+        }
+        self.changes.nonces.insert(addr.clone(), nonce + 1);
+        Ok(())
+    }
+
+    /// Stage [`BalanceTracker::set_supply`].
+    pub fn set_supply(&mut self, total_minted: MicroGitGold, total_burned: MicroGitGold) {
+        self.changes.supply = Some((total_minted, total_burned));
+    }
+
+    /// Stage [`BalanceTracker::set_log`].
+    pub fn set_log(&mut self, tx_count: usize, log_root: Hash256) {
+        self.changes.log = Some((tx_count, log_root));
+    }
+
+    /// [`BalanceTracker::state_root`] as it would be after committing.
+    pub fn state_root(&self) -> Hash256 {
+        let mut state = self.base.state.clone();
+        for (addr, &amount) in &self.changes.balances {
+            state.update(account_key(addr), balance_leaf(amount));
+        }
+        for (addr, &nonce) in &self.changes.nonces {
+            state.update(nonce_key(addr), nonce_leaf(nonce));
+        }
+        if let Some((minted, burned)) = self.changes.supply {
+            state.update(supply_key(), Some(supply_leaf(minted, burned)));
+        }
+        if let Some((tx_count, log_root)) = self.changes.log {
+            state.update(log_key(), Some(log_leaf(tx_count, log_root)));
+        }
+        state.root()
+    }
+
+    /// Finish staging, keeping the changes for [`BalanceTracker::commit`].
+    pub fn into_changes(self) -> BalanceChanges {
+        self.changes
+    }
+}
+
+/// Key of `addr` in the state tree: SHA-256 of the address string.
+pub fn account_key(addr: &Address) -> Hash256 {
+    sha256(addr.0.as_bytes())
+}
+
+/// Leaf value for a balance: `SHA-256("gitgold/balance" | amount u64)`
+/// (big-endian), or `None` for zero, which is stored as absent.
+pub fn balance_leaf(amount: MicroGitGold) -> Option<Hash256> {
+    (amount > 0).then(|| sha256(&[BALANCE_DOMAIN, &amount.to_be_bytes()].concat()))
+}
+
+/// Key for anything but a balance: `SHA-256(0xff | domain | data)`. No
+/// address string is valid UTF-8 starting with 0xff, so these never
+/// collide with an [`account_key`].
+fn tagged_key(domain: &[u8], data: &[u8]) -> Hash256 {
+    sha256(&[&[0xff], domain, data].concat())
+}
+
+fn nonce_key(addr: &Address) -> Hash256 {
+    tagged_key(NONCE_DOMAIN, addr.0.as_bytes())
+}
+
+/// `SHA-256("gitgold/nonce" | next nonce u64)`, or `None` for 0.
+fn nonce_leaf(nonce: u64) -> Option<Hash256> {
+    (nonce > 0).then(|| sha256(&[NONCE_DOMAIN, &nonce.to_be_bytes()].concat()))
+}
+
+fn supply_key() -> Hash256 {
+    tagged_key(SUPPLY_DOMAIN, &[])
+}
+
+/// `SHA-256("gitgold/supply" | total_minted u64 | total_burned u64)`.
+fn supply_leaf(total_minted: MicroGitGold, total_burned: MicroGitGold) -> Hash256 {
+    sha256(
+        &[
+            SUPPLY_DOMAIN,
+            &total_minted.to_be_bytes(),
+            &total_burned.to_be_bytes(),
+        ]
+        .concat(),
+    )
+}
+
+fn log_key() -> Hash256 {
+    tagged_key(LOG_DOMAIN, &[])
+}
+
+/// `SHA-256("gitgold/log" | tx_count u64 | log_root)`.
+fn log_leaf(tx_count: usize, log_root: Hash256) -> Hash256 {
+    sha256(&[LOG_DOMAIN, &(tx_count as u64).to_be_bytes(), &log_root].concat())
+}
+
+/// Check that `addr` holds exactly `amount` under `state_root`, e.g. a
+/// block header's, without access to the ledger.
+pub fn verify_balance(
+    state_root: Hash256,
+    addr: &Address,
+    amount: MicroGitGold,
+    proof: &SparseMerkleProof,
+) -> bool {
+    proof.verify(state_root, &account_key(addr), balance_leaf(amount))
 }
 
 impl Default for BalanceTracker {
@@ -122,6 +431,136 @@ mod tests {
         assert_eq!(tracker.balance(&bob), 0);
     }
 
+    #[test]
+    fn test_nonces_sequential() {
+        let mut tracker = BalanceTracker::new();
+        let alice = Address::new("alice");
+        assert_eq!(tracker.next_nonce(&alice), 0);
+        tracker.use_nonce(&alice, 0).unwrap();
+        tracker.use_nonce(&alice, 1).unwrap();
+        assert_eq!(tracker.next_nonce(&alice), 2);
+        assert_eq!(tracker.next_nonce(&Address::new("bob")), 0);
+
+        // Reused and skipped nonces are rejected and leave the counter alone.
+        for bad in [1, 3] {
+            assert!(matches!(
+                tracker.use_nonce(&alice, bad),
+                Err(LedgerError::InvalidNonce { expected: 2, actual, .. }) if actual == bad
+            ));
+        }
+        assert_eq!(tracker.next_nonce(&alice), 2);
+    }
+
+    #[test]
+    fn test_staged_changes_apply_only_on_commit() {
+        let mut tracker = BalanceTracker::new();
+        let alice = Address::new("alice");
+        let bob = Address::new("bob");
+        tracker.credit(&alice, 1000);
+
+        let mut stage = tracker.stage();
+        stage.transfer(&alice, &bob, 300).unwrap();
+        stage.use_nonce(&alice, 0).unwrap();
+        // Reads see staged values, so a second overspend is caught.
+        assert_eq!(stage.balance(&alice), 700);
+        assert!(stage.debit(&alice, 701).is_err());
+        assert!(stage.use_nonce(&alice, 0).is_err());
+        let changes = stage.into_changes();
+        assert_eq!(tracker.balance(&alice), 1000);
+        assert_eq!(tracker.next_nonce(&alice), 0);
+
+        tracker.commit(changes);
+        assert_eq!(tracker.balance(&alice), 700);
+        assert_eq!(tracker.balance(&bob), 300);
+        assert_eq!(tracker.next_nonce(&alice), 1);
+
+        // A discarded stage leaves no trace.
+        let mut stage = tracker.stage();
+        stage.credit(&bob, 5);
+        drop(stage);
+        assert_eq!(tracker.balance(&bob), 300);
+    }
+
+    #[test]
+    fn test_state_root_covers_nonces_and_totals() {
+        let alice = Address::new("alice");
+        let mut tracker = BalanceTracker::new();
+        tracker.credit(&alice, 500);
+        let mut roots = vec![tracker.state_root()];
+
+        tracker.use_nonce(&alice, 0).unwrap();
+        roots.push(tracker.state_root());
+        tracker.set_supply(500, 0);
+        roots.push(tracker.state_root());
+        tracker.set_supply(500, 1);
+        roots.push(tracker.state_root());
+        tracker.set_log(1, [1u8; 32]);
+        roots.push(tracker.state_root());
+        tracker.set_log(2, [1u8; 32]);
+        roots.push(tracker.state_root());
+        for (i, root) in roots.iter().enumerate() {
+            assert!(!roots[..i].contains(root), "change {i} left the root alone");
+        }
+
+        // Staged totals reach the root exactly as committed ones do.
+        let mut stage = tracker.stage();
+        stage.use_nonce(&alice, 1).unwrap();
+        stage.set_supply(600, 1);
+        stage.set_log(3, [2u8; 32]);
+        let staged_root = stage.state_root();
+        tracker.commit(stage.into_changes());
+        assert_eq!(tracker.state_root(), staged_root);
+
+        let mut rebuilt = BalanceTracker::from_parts([(alice.clone(), 500)], [(alice, 2)]);
+        rebuilt.set_supply(600, 1);
+        rebuilt.set_log(3, [2u8; 32]);
+        assert_eq!(rebuilt.state_root(), staged_root);
+    }
+
+    #[test]
+    fn test_state_root_tracks_holdings_only() {
+        let alice = Address::new("alice");
+        let bob = Address::new("bob");
+        let mut a = BalanceTracker::new();
+        a.credit(&alice, 500);
+        a.credit(&bob, 0);
+
+        // Same holdings reached another way give the same root.
+        let mut b = BalanceTracker::new();
+        b.credit(&alice, 700);
+        b.transfer(&alice, &bob, 200).unwrap();
+        b.debit(&bob, 200).unwrap();
+        assert_eq!(a.state_root(), b.state_root());
+
+        let mut stage = b.stage();
+        stage.credit(&bob, 1);
+        let staged_root = stage.state_root();
+        assert_ne!(staged_root, a.state_root());
+        b.commit(stage.into_changes());
+        assert_eq!(b.state_root(), staged_root);
+    }
+
+    #[test]
+    fn test_balance_proofs() {
+        let mut tracker = BalanceTracker::new();
+        let addrs: Vec<Address> = (0..20).map(|i| Address::new(&format!("addr{i}"))).collect();
+        for (i, addr) in addrs.iter().enumerate() {
+            tracker.credit(addr, 100 * i as u64);
+        }
+        tracker.debit(&addrs[5], 500).unwrap();
+        let root = tracker.state_root();
+
+        for (i, addr) in addrs.iter().enumerate() {
+            let held = if i == 5 { 0 } else { 100 * i as u64 };
+            let proof = tracker.balance_proof(addr);
+            assert!(verify_balance(root, addr, held, &proof));
+            assert!(!verify_balance(root, addr, held + 1, &proof));
+        }
+        let stranger = Address::new("stranger");
+        assert!(verify_balance(root, &stranger, 0, &tracker.balance_proof(&stranger)));
+        assert!(!verify_balance(root, &stranger, 1, &tracker.balance_proof(&stranger)));
+    }
+
     #[test]
     fn test_unknown_address_zero() {
         let tracker = BalanceTracker::new();
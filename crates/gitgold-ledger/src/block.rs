@@ -0,0 +1,163 @@
+use gitgold_core::types::{Address, Hash256, MicroGitGold};
+use gitgold_crypto::hash::sha256;
+use gitgold_crypto::keys::PublicKey;
+use gitgold_crypto::wallet::Wallet;
+use serde::{Deserialize, Serialize};
+
+use crate::balance;
+use crate::merkle::{MerkleTree, ProofStep};
+use crate::smt::SparseMerkleProof;
+use crate::transaction::Transaction;
+
+/// Domain separator for header signatures and hashes.
+const BLOCK_DOMAIN: &[u8] = b"gitgold/block";
+
+/// `prev_hash` of the genesis block.
+pub const GENESIS_PREV_HASH: Hash256 = [0u8; 32];
+
+/// Header committing to a block's transactions and the state after them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHeader {
+    /// Position in the chain; the genesis block is 0.
+    pub height: u64,
+    /// Hash of the previous header ([`GENESIS_PREV_HASH`] for genesis).
+    pub prev_hash: Hash256,
+    /// Merkle root over the block's transaction hashes, in order.
+    pub tx_root: Hash256,
+    /// Sparse Merkle root over all balances, nonces, supply totals and the
+    /// transaction log after the block's transactions.
+    pub state_root: Hash256,
+    /// Unix timestamp in seconds.
+    pub timestamp: i64,
+    /// Ed25519 public key of the producer (hex-encoded).
+    pub producer: String,
+    /// Producer's signature over signable_bytes (hex-encoded).
+    pub signature: String,
+}
+
+impl BlockHeader {
+    /// Build and sign a header as `producer`.
+    pub fn sign(
+        height: u64,
+        prev_hash: Hash256,
+        tx_root: Hash256,
+        state_root: Hash256,
+        timestamp: i64,
+        producer: &Wallet,
+    ) -> Self {
+        let mut header = Self {
+            height,
+            prev_hash,
+            tx_root,
+            state_root,
+            timestamp,
+            producer: hex::encode(producer.public_key().bytes),
+            signature: String::new(),
+        };
+        header.signature = hex::encode(producer.sign(&header.signable_bytes()));
+        header
+    }
+
+    /// Bytes the producer signs: every field except the signature.
+    ///
+    /// ```text
+    /// "gitgold/block" | height u64 | prev_hash | tx_root | state_root
+    /// | timestamp i64 | producer (u32 length-prefixed)
+    /// ```
+    pub fn signable_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(BLOCK_DOMAIN.len() + 8 * 2 + 32 * 3 + 4 + 64);
+        out.extend_from_slice(BLOCK_DOMAIN);
+        out.extend_from_slice(&self.height.to_be_bytes());
+        out.extend_from_slice(&self.prev_hash);
+        out.extend_from_slice(&self.tx_root);
+        out.extend_from_slice(&self.state_root);
+        out.extend_from_slice(&self.timestamp.to_be_bytes());
+        out.extend_from_slice(&(self.producer.len() as u32).to_be_bytes());
+        out.extend_from_slice(self.producer.as_bytes());
+        out
+    }
+
+    /// Header hash, which the next block links to.
+    pub fn hash(&self) -> Hash256 {
+        sha256(&self.signable_bytes())
+    }
+
+    /// Check the producer's signature.
+    pub fn verify_signature(&self) -> bool {
+        let (Ok(producer), Ok(signature)) = (hex::decode(&self.producer), hex::decode(&self.signature))
+        else {
+            return false;
+        };
+        PublicKey { bytes: producer }.verify(&self.signable_bytes(), &signature)
+    }
+
+    /// Light-client check that `tx` is in this block, given the proof
+    /// from `Ledger::inclusion_proof`. Only the header is needed.
+    pub fn verify_inclusion(&self, tx: &Transaction, proof: &[ProofStep]) -> bool {
+        MerkleTree::verify_proof(tx.hash(), proof, self.tx_root)
+    }
+
+    /// Light-client check that `addr` held `amount` after this block, given
+    /// the proof from `BalanceTracker::balance_proof` at that point.
+    pub fn verify_balance(
+        &self,
+        addr: &Address,
+        amount: MicroGitGold,
+        proof: &SparseMerkleProof,
+    ) -> bool {
+        balance::verify_balance(self.state_root, addr, amount, proof)
+    }
+}
+
+/// A header and the transactions it commits to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Block {
+    pub header: BlockHeader,
+    pub txs: Vec<Transaction>,
+}
+
+/// Merkle root over transaction hashes, as stored in [`BlockHeader::tx_root`].
+pub fn tx_root(txs: &[Transaction]) -> Hash256 {
+    MerkleTree::build(txs.iter().map(Transaction::hash).collect()).root()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(producer: &Wallet) -> BlockHeader {
+        BlockHeader::sign(3, [1u8; 32], [2u8; 32], [3u8; 32], 1700000000, producer)
+    }
+
+    #[test]
+    fn test_signature_covers_every_field() {
+        let producer = Wallet::new();
+        let good = header(&producer);
+        assert!(good.verify_signature());
+
+        let tampered: [fn(&mut BlockHeader); 6] = [
+            |h| h.height += 1,
+            |h| h.prev_hash[0] ^= 1,
+            |h| h.tx_root[0] ^= 1,
+            |h| h.state_root[0] ^= 1,
+            |h| h.timestamp += 1,
+            |h| h.producer = hex::encode(Wallet::new().public_key().bytes),
+        ];
+        for tamper in tampered {
+            let mut h = good.clone();
+            tamper(&mut h);
+            assert!(!h.verify_signature());
+            assert_ne!(h.hash(), good.hash());
+        }
+    }
+
+    #[test]
+    fn test_hash_excludes_signature() {
+        let producer = Wallet::new();
+        let mut h = header(&producer);
+        let hash = h.hash();
+        h.signature = String::new();
+        assert_eq!(h.hash(), hash);
+        assert!(!h.verify_signature());
+    }
+}
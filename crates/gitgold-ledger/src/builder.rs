@@ -0,0 +1,304 @@
+use gitgold_core::error::LedgerError;
+use gitgold_core::types::{Address, MicroGitGold, TransactionType};
+use gitgold_crypto::multisig::MultisigPolicy;
+use gitgold_crypto::wallet::Wallet;
+
+use crate::store::Ledger;
+use crate::transaction::{MultisigAuth, Transaction, TX_VERSION};
+
+/// Builds and signs a [`Transaction`] from a wallet.
+///
+/// `tx_id` defaults to a random UUID and `timestamp` to the current time.
+/// `nonce` defaults to 0, or to the sender's next nonce when signed with
+/// [`TransactionBuilder::sign_checked`]. Burns go to the system address
+/// unless a recipient is set; every other type needs one.
+#[derive(Debug, Clone)]
+pub struct TransactionBuilder {
+    tx_type: TransactionType,
+    to: Option<Address>,
+    amount: MicroGitGold,
+    metadata: serde_json::Value,
+    timestamp: Option<i64>,
+    tx_id: Option<String>,
+    nonce: Option<u64>,
+}
+
+impl TransactionBuilder {
+    /// Start a transaction of the given type.
+    pub fn new(tx_type: TransactionType) -> Self {
+        Self {
+            tx_type,
+            to: None,
+            amount: 0,
+            metadata: serde_json::json!({}),
+            timestamp: None,
+            tx_id: None,
+            nonce: None,
+        }
+    }
+
+    /// Shorthand for a `Transfer` of `amount` to `to`.
+    pub fn transfer(to: Address, amount: MicroGitGold) -> Self {
+        Self::new(TransactionType::Transfer).to(to).amount(amount)
+    }
+
+    /// Shorthand for a `Burn` of `amount`.
+    pub fn burn(amount: MicroGitGold) -> Self {
+        Self::new(TransactionType::Burn).amount(amount)
+    }
+
+    /// Set the recipient.
+    pub fn to(mut self, to: Address) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// Set the amount in micro-GitGold.
+    pub fn amount(mut self, amount: MicroGitGold) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    /// Set the metadata (repo_hash, fragment_ids, etc.).
+    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Set the Unix timestamp in seconds.
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Set an explicit transaction id.
+    pub fn tx_id(mut self, tx_id: impl Into<String>) -> Self {
+        self.tx_id = Some(tx_id.into());
+        self
+    }
+
+    /// Set the sender's nonce.
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Fill in the sender and public key from `wallet` and sign.
+    ///
+    /// Mints are rejected: they come from the system address, which has
+    /// no key.
+    pub fn sign(self, wallet: &Wallet) -> Result<Transaction, LedgerError> {
+        let mut tx = self.unsigned(wallet.address(), hex::encode(wallet.public_key().bytes))?;
+        tx.signature = hex::encode(wallet.sign(&tx.signable_bytes()));
+        Ok(tx)
+    }
+
+    /// Build an unsigned transaction from `policy`'s address. Members add
+    /// their signatures with [`Transaction::cosign`] until the threshold
+    /// is met.
+    pub fn build_multisig(self, policy: &MultisigPolicy) -> Result<Transaction, LedgerError> {
+        let mut tx = self.unsigned(policy.address(), String::new())?;
+        tx.multisig = Some(MultisigAuth::new(policy));
+        Ok(tx)
+    }
+
+    fn unsigned(self, from: Address, pubkey: String) -> Result<Transaction, LedgerError> {
+        if self.tx_type == TransactionType::Mint {
+            return Err(LedgerError::InvalidTransaction(
+                "mint transactions are issued by the system address".to_string(),
+            ));
+        }
+        let to = match (self.to, &self.tx_type) {
+            (Some(to), _) => to,
+            (None, TransactionType::Burn) => Address::system(),
+            (None, _) => {
+                return Err(LedgerError::InvalidTransaction(
+                    "missing recipient".to_string(),
+                ))
+            }
+        };
+
+        Ok(Transaction {
+            tx_id: self
+                .tx_id
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            tx_type: self.tx_type,
+            from,
+            to,
+            amount: self.amount,
+            metadata: self.metadata,
+            timestamp: self
+                .timestamp
+                .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+            nonce: self.nonce.unwrap_or(0),
+            signature: String::new(),
+            pubkey,
+            version: TX_VERSION,
+            multisig: None,
+        })
+    }
+
+    /// [`TransactionBuilder::sign`], then check the result against
+    /// `ledger` (see [`Ledger::validate`]) so an unaffordable, duplicate or
+    /// out-of-sequence transaction is caught before submission. An unset
+    /// nonce is taken from the ledger.
+    pub fn sign_checked(
+        self,
+        wallet: &Wallet,
+        ledger: &Ledger,
+    ) -> Result<Transaction, LedgerError> {
+        let nonce = self
+            .nonce
+            .unwrap_or_else(|| ledger.next_nonce(&wallet.address()));
+        let tx = self.nonce(nonce).sign(wallet)?;
+        ledger.validate(&tx)?;
+        Ok(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn funded_ledger(wallet: &Wallet, amount: MicroGitGold) -> Ledger {
+        funded_address(wallet.address(), amount)
+    }
+
+    fn funded_address(to: Address, amount: MicroGitGold) -> Ledger {
+        let mut ledger = Ledger::in_memory().unwrap();
+        ledger
+            .append(Transaction {
+                tx_id: uuid::Uuid::new_v4().to_string(),
+                tx_type: TransactionType::Mint,
+                from: Address::system(),
+                to,
+                amount,
+                metadata: serde_json::json!({}),
+                timestamp: 1700000000,
+                nonce: 0,
+                signature: String::new(),
+                pubkey: String::new(),
+                version: TX_VERSION,
+                multisig: None,
+            })
+            .unwrap();
+        ledger
+    }
+
+    #[test]
+    fn test_signed_transfer_accepted() {
+        let wallet = Wallet::new();
+        let mut ledger = funded_ledger(&wallet, 1_000_000);
+
+        let tx = TransactionBuilder::transfer(Address::new("bob"), 250_000)
+            .metadata(serde_json::json!({"repo_hash": "abc123"}))
+            .sign_checked(&wallet, &ledger)
+            .unwrap();
+        assert_eq!(tx.from, wallet.address());
+        assert!(tx.timestamp > 0);
+        assert_eq!(tx.nonce, 0);
+        ledger.append(tx).unwrap();
+
+        let burn = TransactionBuilder::burn(50_000)
+            .sign_checked(&wallet, &ledger)
+            .unwrap();
+        assert_eq!(burn.nonce, 1);
+        ledger.append(burn).unwrap();
+        assert_eq!(ledger.balance(&wallet.address()), 700_000);
+        assert_eq!(ledger.balance(&Address::new("bob")), 250_000);
+        assert_eq!(ledger.supply().total_burned(), 50_000);
+    }
+
+    #[test]
+    fn test_preflight_catches_overspend() {
+        let wallet = Wallet::new();
+        let ledger = funded_ledger(&wallet, 100);
+        let result =
+            TransactionBuilder::transfer(Address::new("bob"), 101).sign_checked(&wallet, &ledger);
+        assert!(matches!(
+            result,
+            Err(LedgerError::InsufficientBalance {
+                have: 100,
+                need: 101
+            })
+        ));
+        // Nothing was applied.
+        assert_eq!(ledger.balance(&wallet.address()), 100);
+        assert_eq!(ledger.tx_count(), 1);
+    }
+
+    #[test]
+    fn test_preflight_catches_duplicate() {
+        let wallet = Wallet::new();
+        let mut ledger = funded_ledger(&wallet, 1_000);
+        let build = || {
+            TransactionBuilder::transfer(Address::new("bob"), 10)
+                .tx_id("fixed-id")
+                .timestamp(1700000001)
+                .nonce(0)
+        };
+        ledger.append(build().sign(&wallet).unwrap()).unwrap();
+        assert!(matches!(
+            build().sign_checked(&wallet, &ledger),
+            Err(LedgerError::DuplicateTransaction(_))
+        ));
+    }
+
+    #[test]
+    fn test_tampered_metadata_rejected() {
+        let wallet = Wallet::new();
+        let mut ledger = funded_ledger(&wallet, 1_000);
+        let mut tx = TransactionBuilder::transfer(Address::new("bob"), 10)
+            .metadata(serde_json::json!({"fragment_ids": [1, 2]}))
+            .sign(&wallet)
+            .unwrap();
+        tx.metadata = serde_json::json!({"fragment_ids": [1, 2, 3]});
+        assert!(matches!(
+            ledger.validate(&tx),
+            Err(LedgerError::InvalidSignature)
+        ));
+        assert!(matches!(
+            ledger.append(tx),
+            Err(LedgerError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_builds() {
+        let wallet = Wallet::new();
+        assert!(matches!(
+            TransactionBuilder::new(TransactionType::Transfer)
+                .amount(5)
+                .sign(&wallet),
+            Err(LedgerError::InvalidTransaction(_))
+        ));
+        assert!(matches!(
+            TransactionBuilder::new(TransactionType::Mint)
+                .to(wallet.address())
+                .amount(5)
+                .sign(&wallet),
+            Err(LedgerError::InvalidTransaction(_))
+        ));
+    }
+
+    #[test]
+    fn test_multisig_transfer() {
+        let wallets: Vec<Wallet> = (0..3).map(|_| Wallet::new()).collect();
+        let pks: Vec<_> = wallets.iter().map(Wallet::public_key).collect();
+        let policy = MultisigPolicy::new(2, &pks).unwrap();
+        let mut ledger = funded_address(policy.address(), 1_000);
+
+        let mut tx = TransactionBuilder::transfer(Address::new("bob"), 400)
+            .build_multisig(&policy)
+            .unwrap();
+        tx.cosign(&wallets[1]).unwrap();
+        assert!(matches!(
+            ledger.validate(&tx),
+            Err(LedgerError::InvalidSignature)
+        ));
+        tx.cosign(&wallets[2]).unwrap();
+        ledger.append(tx).unwrap();
+        assert_eq!(ledger.balance(&policy.address()), 600);
+        assert_eq!(ledger.next_nonce(&policy.address()), 1);
+    }
+}
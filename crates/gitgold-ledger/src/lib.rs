@@ -1,5 +1,9 @@
 pub mod balance;
+pub mod block;
+pub mod builder;
 pub mod merkle;
+pub mod smt;
+pub mod snapshot;
 pub mod store;
 pub mod supply;
 pub mod transaction;
@@ -119,6 +119,95 @@ impl MerkleTree {
     }
 }
 
+/// Running root over appended leaves, equal to
+/// `MerkleTree::build(leaves).root()` but keeping only O(log n) hashes.
+#[derive(Debug, Clone, Default)]
+pub struct MerkleAccumulator {
+    /// `frontier[level]` is the last complete subtree at `level` still
+    /// waiting for its right sibling (set iff bit `level` of `count` is).
+    frontier: Vec<Option<Hash256>>,
+    count: usize,
+}
+
+impl MerkleAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resume from a saved [`frontier`](Self::frontier) over `count`
+    /// leaves, or `None` if the frontier doesn't fit `count`.
+    pub fn from_frontier(count: usize, frontier: Vec<Option<Hash256>>) -> Option<Self> {
+        let fits = count.checked_shr(frontier.len() as u32).unwrap_or(0) == 0
+            && frontier
+                .iter()
+                .enumerate()
+                .all(|(level, node)| node.is_some() == (count >> level & 1 == 1));
+        fits.then_some(Self { frontier, count })
+    }
+
+    /// Pending subtree roots, one slot per level; together with
+    /// [`len`](Self::len) enough to resume with [`from_frontier`](Self::from_frontier).
+    pub fn frontier(&self) -> &[Option<Hash256>] {
+        &self.frontier
+    }
+
+    /// Append a leaf hash.
+    pub fn push(&mut self, leaf: Hash256) {
+        let mut node = leaf;
+        let mut level = 0;
+        loop {
+            if level == self.frontier.len() {
+                self.frontier.push(None);
+            }
+            match self.frontier[level].take() {
+                Some(left) => {
+                    node = sha256_pair(&left, &node);
+                    level += 1;
+                }
+                None => {
+                    self.frontier[level] = Some(node);
+                    break;
+                }
+            }
+        }
+        self.count += 1;
+    }
+
+    /// Root over every leaf pushed so far.
+    pub fn root(&self) -> Hash256 {
+        if self.count == 0 {
+            return [0u8; 32];
+        }
+        // Fold the frontier bottom-up; `carry` is the trailing node built
+        // from an odd leftover, paired with itself like in `build`.
+        let mut carry: Option<Hash256> = None;
+        let mut width = self.count;
+        let mut level = 0;
+        while width > 1 {
+            let full = self.frontier[level];
+            carry = match (full, carry) {
+                (Some(left), Some(right)) => Some(sha256_pair(&left, &right)),
+                (Some(node), None) | (None, Some(node)) => Some(sha256_pair(&node, &node)),
+                (None, None) => None,
+            };
+            width = width.div_ceil(2);
+            level += 1;
+        }
+        carry
+            .or_else(|| self.frontier.get(level).copied().flatten())
+            .expect("non-empty accumulator has a root")
+    }
+
+    /// Number of leaves pushed.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,4 +291,40 @@ mod tests {
             assert!(MerkleTree::verify_proof(sha256(d), &proof, root));
         }
     }
+
+    #[test]
+    fn test_accumulator_matches_build() {
+        let mut acc = MerkleAccumulator::new();
+        let mut leaves = Vec::new();
+        assert_eq!(acc.root(), MerkleTree::build(vec![]).root());
+        for i in 0..70u32 {
+            let leaf = sha256(&i.to_be_bytes());
+            acc.push(leaf);
+            leaves.push(leaf);
+            assert_eq!(acc.root(), MerkleTree::build(leaves.clone()).root(), "{} leaves", i + 1);
+        }
+        assert_eq!(acc.len(), 70);
+    }
+
+    #[test]
+    fn test_accumulator_resumes_from_frontier() {
+        let mut acc = MerkleAccumulator::new();
+        let mut leaves = Vec::new();
+        for i in 0..11u32 {
+            let leaf = sha256(&i.to_be_bytes());
+            acc.push(leaf);
+            leaves.push(leaf);
+        }
+        let mut resumed = MerkleAccumulator::from_frontier(11, acc.frontier().to_vec()).unwrap();
+        assert_eq!(resumed.root(), acc.root());
+        let leaf = sha256(b"next");
+        resumed.push(leaf);
+        leaves.push(leaf);
+        assert_eq!(resumed.root(), MerkleTree::build(leaves).root());
+
+        // The frontier must match the bits of the count.
+        assert!(MerkleAccumulator::from_frontier(10, acc.frontier().to_vec()).is_none());
+        assert!(MerkleAccumulator::from_frontier(27, acc.frontier().to_vec()).is_none());
+        assert!(MerkleAccumulator::from_frontier(0, vec![]).is_some());
+    }
 }
@@ -0,0 +1,328 @@
+use gitgold_core::types::Hash256;
+use gitgold_crypto::hash::sha256;
+use std::sync::Arc;
+
+/// Hash of an empty subtree.
+pub const EMPTY_ROOT: Hash256 = [0u8; 32];
+
+/// Key length in bits; the tree is 256 levels deep.
+const KEY_BITS: usize = 256;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Sparse Merkle tree over 256-bit keys.
+///
+/// Logically every key has a slot at depth 256, but a subtree holding a
+/// single leaf is stored (and hashed) as just that leaf, so an update
+/// touches O(log n) nodes rather than 256. Empty subtrees hash to
+/// [`EMPTY_ROOT`]. The root depends only on the key/value set, not on the
+/// order of updates.
+///
+/// Nodes are shared between clones, so cloning is O(1) and an update
+/// copies only the path it changes.
+#[derive(Debug, Clone)]
+pub struct SparseMerkleTree {
+    root: Arc<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Empty,
+    Leaf {
+        key: Hash256,
+        value: Hash256,
+    },
+    Internal {
+        left: Arc<Node>,
+        right: Arc<Node>,
+        hash: Hash256,
+    },
+}
+
+/// Proof that a key holds a value, or holds nothing.
+///
+/// `siblings` run from the root down to where the key's path ends: at its
+/// own leaf, at an empty subtree, or at another key's leaf sharing the
+/// path so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMerkleProof {
+    /// The leaf (key, value) found at the end of the path, if any.
+    pub leaf: Option<(Hash256, Hash256)>,
+    /// Sibling hashes, root first.
+    pub siblings: Vec<Hash256>,
+}
+
+impl Node {
+    fn hash(&self) -> Hash256 {
+        match self {
+            Node::Empty => EMPTY_ROOT,
+            Node::Leaf { key, value } => leaf_hash(key, value),
+            Node::Internal { hash, .. } => *hash,
+        }
+    }
+
+    fn internal(left: Arc<Node>, right: Arc<Node>) -> Arc<Node> {
+        let hash = node_hash(&left.hash(), &right.hash());
+        Arc::new(Node::Internal { left, right, hash })
+    }
+}
+
+fn leaf_hash(key: &Hash256, value: &Hash256) -> Hash256 {
+    let mut buf = [0u8; 65];
+    buf[0] = LEAF_PREFIX;
+    buf[1..33].copy_from_slice(key);
+    buf[33..].copy_from_slice(value);
+    sha256(&buf)
+}
+
+fn node_hash(left: &Hash256, right: &Hash256) -> Hash256 {
+    let mut buf = [0u8; 65];
+    buf[0] = NODE_PREFIX;
+    buf[1..33].copy_from_slice(left);
+    buf[33..].copy_from_slice(right);
+    sha256(&buf)
+}
+
+/// Bit `depth` of `key`, most significant first. `true` means right.
+fn bit(key: &Hash256, depth: usize) -> bool {
+    key[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+fn common_prefix_bits(a: &Hash256, b: &Hash256) -> usize {
+    (0..KEY_BITS)
+        .find(|&d| bit(a, d) != bit(b, d))
+        .unwrap_or(KEY_BITS)
+}
+
+impl SparseMerkleTree {
+    /// An empty tree.
+    pub fn new() -> Self {
+        Self {
+            root: Arc::new(Node::Empty),
+        }
+    }
+
+    /// Root hash.
+    pub fn root(&self) -> Hash256 {
+        self.root.hash()
+    }
+
+    /// Set `key` to `value`, or remove it with `None`.
+    pub fn update(&mut self, key: Hash256, value: Option<Hash256>) {
+        self.root = match value {
+            Some(value) => insert(&self.root, key, value, 0),
+            None => remove(&self.root, &key, 0),
+        };
+    }
+
+    /// Value stored at `key`.
+    pub fn get(&self, key: &Hash256) -> Option<Hash256> {
+        self.proof(key)
+            .leaf
+            .filter(|(found, _)| found == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Inclusion proof for a present key, or non-inclusion proof for an
+    /// absent one.
+    pub fn proof(&self, key: &Hash256) -> SparseMerkleProof {
+        let mut siblings = Vec::new();
+        let mut node = &self.root;
+        for depth in 0.. {
+            match node.as_ref() {
+                Node::Empty => break,
+                Node::Leaf { key, value } => {
+                    return SparseMerkleProof {
+                        leaf: Some((*key, *value)),
+                        siblings,
+                    }
+                }
+                Node::Internal { left, right, .. } => {
+                    let (next, sibling) = if bit(key, depth) {
+                        (right, left)
+                    } else {
+                        (left, right)
+                    };
+                    siblings.push(sibling.hash());
+                    node = next;
+                }
+            }
+        }
+        SparseMerkleProof {
+            leaf: None,
+            siblings,
+        }
+    }
+}
+
+impl Default for SparseMerkleTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn insert(node: &Arc<Node>, key: Hash256, value: Hash256, depth: usize) -> Arc<Node> {
+    match node.as_ref() {
+        Node::Empty => Arc::new(Node::Leaf { key, value }),
+        Node::Leaf { key: existing, .. } if *existing == key => Arc::new(Node::Leaf { key, value }),
+        Node::Leaf { key: existing, .. } => {
+            // Push the existing leaf down one level and insert beside it.
+            let empty = Arc::new(Node::Empty);
+            let split = if bit(existing, depth) {
+                Node::internal(empty, node.clone())
+            } else {
+                Node::internal(node.clone(), empty)
+            };
+            insert(&split, key, value, depth)
+        }
+        Node::Internal { left, right, .. } => {
+            if bit(&key, depth) {
+                Node::internal(left.clone(), insert(right, key, value, depth + 1))
+            } else {
+                Node::internal(insert(left, key, value, depth + 1), right.clone())
+            }
+        }
+    }
+}
+
+fn remove(node: &Arc<Node>, key: &Hash256, depth: usize) -> Arc<Node> {
+    match node.as_ref() {
+        Node::Empty => node.clone(),
+        Node::Leaf { key: existing, .. } if existing == key => Arc::new(Node::Empty),
+        Node::Leaf { .. } => node.clone(),
+        Node::Internal { left, right, .. } => {
+            let (left, right) = if bit(key, depth) {
+                (left.clone(), remove(right, key, depth + 1))
+            } else {
+                (remove(left, key, depth + 1), right.clone())
+            };
+            // A subtree left holding one leaf collapses into that leaf.
+            match (left.as_ref(), right.as_ref()) {
+                (Node::Empty, Node::Empty) => Arc::new(Node::Empty),
+                (Node::Leaf { .. }, Node::Empty) => left,
+                (Node::Empty, Node::Leaf { .. }) => right,
+                _ => Node::internal(left, right),
+            }
+        }
+    }
+}
+
+impl SparseMerkleProof {
+    /// Check that `key` holds `value` (or nothing, for `None`) under `root`.
+    pub fn verify(&self, root: Hash256, key: &Hash256, value: Option<Hash256>) -> bool {
+        if self.siblings.len() > KEY_BITS {
+            return false;
+        }
+        let bottom = match (value, self.leaf) {
+            (Some(value), Some(leaf)) if leaf == (*key, value) => leaf_hash(key, &value),
+            (Some(_), _) => return false,
+            (None, None) => EMPTY_ROOT,
+            // Another key's leaf proves absence only if it sits on our path.
+            (None, Some((other, other_value))) => {
+                if other == *key || common_prefix_bits(&other, key) < self.siblings.len() {
+                    return false;
+                }
+                leaf_hash(&other, &other_value)
+            }
+        };
+
+        let current =
+            self.siblings
+                .iter()
+                .enumerate()
+                .rev()
+                .fold(bottom, |current, (depth, sibling)| {
+                    if bit(key, depth) {
+                        node_hash(sibling, &current)
+                    } else {
+                        node_hash(&current, sibling)
+                    }
+                });
+        current == root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u32) -> Hash256 {
+        sha256(&n.to_be_bytes())
+    }
+
+    fn value(n: u32) -> Hash256 {
+        sha256(&[n.to_be_bytes(), n.to_be_bytes()].concat())
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let tree = SparseMerkleTree::new();
+        assert_eq!(tree.root(), EMPTY_ROOT);
+        let proof = tree.proof(&key(1));
+        assert!(proof.siblings.is_empty());
+        assert!(proof.verify(tree.root(), &key(1), None));
+        assert!(!proof.verify(tree.root(), &key(1), Some(value(1))));
+    }
+
+    #[test]
+    fn test_inclusion_and_non_inclusion_proofs() {
+        let mut tree = SparseMerkleTree::new();
+        for n in 0..50 {
+            tree.update(key(n), Some(value(n)));
+        }
+        let root = tree.root();
+        for n in 0..50 {
+            let proof = tree.proof(&key(n));
+            assert!(proof.verify(root, &key(n), Some(value(n))));
+            assert!(!proof.verify(root, &key(n), Some(value(n + 1))));
+            assert!(!proof.verify(root, &key(n), None));
+            assert_eq!(tree.get(&key(n)), Some(value(n)));
+        }
+        for n in 50..100 {
+            let proof = tree.proof(&key(n));
+            assert!(proof.verify(root, &key(n), None));
+            assert!(!proof.verify(root, &key(n), Some(value(n))));
+            assert_eq!(tree.get(&key(n)), None);
+        }
+
+        // Another key's proof does not prove absence.
+        assert!(!tree.proof(&key(0)).verify(root, &key(100), None));
+    }
+
+    #[test]
+    fn test_root_is_order_independent() {
+        let mut a = SparseMerkleTree::new();
+        let mut b = SparseMerkleTree::new();
+        for n in 0..40 {
+            a.update(key(n), Some(value(n)));
+            b.update(key(39 - n), Some(value(39 - n)));
+        }
+        // Extra keys inserted then removed leave no trace.
+        for n in 40..60 {
+            b.update(key(n), Some(value(n)));
+        }
+        for n in 40..60 {
+            b.update(key(n), None);
+        }
+        assert_eq!(a.root(), b.root());
+
+        for n in 0..40 {
+            a.update(key(n), None);
+        }
+        assert_eq!(a.root(), EMPTY_ROOT);
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let mut tree = SparseMerkleTree::new();
+        tree.update(key(1), Some(value(1)));
+        let before = tree.root();
+        let mut copy = tree.clone();
+        copy.update(key(2), Some(value(2)));
+        copy.update(key(1), Some(value(3)));
+        assert_eq!(tree.root(), before);
+        assert_eq!(tree.get(&key(1)), Some(value(1)));
+        assert_ne!(copy.root(), before);
+    }
+}
@@ -0,0 +1,164 @@
+use gitgold_core::error::LedgerError;
+use gitgold_core::types::{Address, Hash256, MicroGitGold};
+use gitgold_crypto::hash::sha256_hex;
+use serde::{Deserialize, Serialize};
+
+use crate::balance::BalanceTracker;
+use crate::merkle::MerkleAccumulator;
+use crate::supply::SupplyTracker;
+
+/// Ledger state at the end of block `height`, so `Ledger::open` can
+/// restore it and replay only the transactions after it.
+///
+/// A snapshot is only trusted once it checks out against that block's
+/// signed header: its balances, nonces, supply totals and log frontier
+/// must together hash to `state_root`, and `state_root` must be the one
+/// the header commits to. The log frontier pins every transaction before
+/// it, so nothing earlier needs to be read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Height of the block the snapshot was taken at.
+    pub height: u64,
+    /// Number of transactions covered: every one in blocks up to `height`.
+    pub tx_count: u64,
+    /// [`MerkleAccumulator::frontier`] of the log over those transactions.
+    pub log_frontier: Vec<Option<Hash256>>,
+    /// [`BalanceTracker::state_root`] of the rest; equal to the state
+    /// root of block `height`.
+    pub state_root: Hash256,
+    /// Non-zero balances, sorted by address.
+    pub balances: Vec<(Address, MicroGitGold)>,
+    /// Next nonces, sorted by address.
+    pub nonces: Vec<(Address, u64)>,
+    pub total_minted: MicroGitGold,
+    pub total_burned: MicroGitGold,
+}
+
+impl Snapshot {
+    /// Capture the current state, which must be that of block `height`.
+    pub fn capture(
+        height: u64,
+        log: &MerkleAccumulator,
+        balances: &BalanceTracker,
+        supply: &SupplyTracker,
+    ) -> Self {
+        let sorted = |map: &std::collections::HashMap<Address, u64>| {
+            let mut entries: Vec<_> = map
+                .iter()
+                .filter(|(_, &value)| value > 0)
+                .map(|(addr, &value)| (addr.clone(), value))
+                .collect();
+            entries.sort_unstable_by(|a, b| a.0 .0.cmp(&b.0 .0));
+            entries
+        };
+        Self {
+            height,
+            tx_count: log.len() as u64,
+            log_frontier: log.frontier().to_vec(),
+            state_root: balances.state_root(),
+            balances: sorted(balances.all_balances()),
+            nonces: sorted(balances.all_nonces()),
+            total_minted: supply.total_minted(),
+            total_burned: supply.total_burned(),
+        }
+    }
+
+    /// Stored form and its checksum (hex SHA-256 of the bytes).
+    pub fn encode(&self) -> Result<(Vec<u8>, String), LedgerError> {
+        let data = serde_json::to_vec(self)
+            .map_err(|e| LedgerError::Database(format!("encoding snapshot: {e}")))?;
+        let checksum = sha256_hex(&data);
+        Ok((data, checksum))
+    }
+
+    /// Decode a stored snapshot; `None` if the checksum doesn't match or
+    /// the bytes don't parse.
+    pub fn decode(data: &[u8], checksum: &str) -> Option<Self> {
+        if sha256_hex(data) != checksum {
+            return None;
+        }
+        serde_json::from_slice(data).ok()
+    }
+
+    /// Rebuild the log accumulator, or `None` if the frontier doesn't fit
+    /// `tx_count`.
+    pub fn log(&self) -> Option<MerkleAccumulator> {
+        let count = usize::try_from(self.tx_count).ok()?;
+        MerkleAccumulator::from_frontier(count, self.log_frontier.clone())
+    }
+
+    /// Rebuild the balance tracker, or `None` if the balances, nonces,
+    /// supply totals and log don't hash to `state_root`.
+    pub fn balance_tracker(&self) -> Option<BalanceTracker> {
+        let log = self.log()?;
+        let mut tracker = BalanceTracker::from_parts(self.balances.clone(), self.nonces.clone());
+        tracker.set_supply(self.total_minted, self.total_burned);
+        tracker.set_log(log.len(), log.root());
+        (tracker.state_root() == self.state_root).then_some(tracker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        let mut balances = BalanceTracker::new();
+        balances.credit(&Address::new("alice"), 700);
+        balances.credit(&Address::new("bob"), 300);
+        balances.use_nonce(&Address::new("alice"), 0).unwrap();
+        let mut supply = SupplyTracker::default_config();
+        supply.mint(1_000).unwrap();
+        let mut log = MerkleAccumulator::new();
+        log.push([8u8; 32]);
+        log.push([9u8; 32]);
+        balances.set_supply(supply.total_minted(), supply.total_burned());
+        balances.set_log(log.len(), log.root());
+        Snapshot::capture(1, &log, &balances, &supply)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let snap = snapshot();
+        let (data, checksum) = snap.encode().unwrap();
+        let decoded = Snapshot::decode(&data, &checksum).unwrap();
+        assert_eq!(decoded, snap);
+
+        let tracker = decoded.balance_tracker().unwrap();
+        assert_eq!(tracker.balance(&Address::new("alice")), 700);
+        assert_eq!(tracker.next_nonce(&Address::new("alice")), 1);
+        assert_eq!(tracker.state_root(), snap.state_root);
+        assert_eq!(decoded.log().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_corruption_detected() {
+        let snap = snapshot();
+        let (mut data, checksum) = snap.encode().unwrap();
+        let last = data.len() - 2;
+        data[last] ^= 1;
+        assert!(Snapshot::decode(&data, &checksum).is_none());
+
+        // Consistent bytes but state that doesn't match the state root:
+        // balances, nonces, supply totals and the log are all covered.
+        let forgeries: [fn(&mut Snapshot); 5] = [
+            |s| s.balances[0].1 += 1,
+            |s| s.nonces[0].1 += 1,
+            |s| s.total_minted += 1,
+            |s| s.total_burned += 1,
+            |s| s.log_frontier[1] = Some([7u8; 32]),
+        ];
+        for forge in forgeries {
+            let mut forged = snap.clone();
+            forge(&mut forged);
+            let (data, checksum) = forged.encode().unwrap();
+            let decoded = Snapshot::decode(&data, &checksum).unwrap();
+            assert!(decoded.balance_tracker().is_none());
+        }
+
+        // A frontier that doesn't fit the count.
+        let mut forged = snap.clone();
+        forged.tx_count = 3;
+        assert!(forged.log().is_none());
+    }
+}
@@ -1,41 +1,118 @@
 use gitgold_core::error::LedgerError;
-use gitgold_core::types::{Address, MicroGitGold, TransactionType};
+use gitgold_core::types::{Address, Hash256, MicroGitGold, TransactionType};
 use gitgold_crypto::hash::sha256_hex;
-use gitgold_crypto::keys::PublicKey;
-use rusqlite::Connection;
+use gitgold_crypto::keys::{self, BatchItem, PublicKey};
+use gitgold_crypto::wallet::Wallet;
+use rusqlite::{Connection, OptionalExtension};
 use std::collections::HashSet;
 
-use crate::balance::BalanceTracker;
-use crate::merkle::MerkleTree;
+use crate::balance::{BalanceChanges, BalanceTracker, StagedBalances};
+use crate::block::{Block, BlockHeader, GENESIS_PREV_HASH};
+use crate::merkle::{MerkleAccumulator, MerkleTree, ProofStep};
+use crate::snapshot::Snapshot;
 use crate::supply::SupplyTracker;
-use crate::transaction::Transaction;
+use crate::transaction::{MultisigAuth, Transaction, TX_VERSION};
+
+/// Default number of transactions between automatic snapshots.
+pub const SNAPSHOT_INTERVAL: usize = 10_000;
+
+/// Snapshots kept on disk; older ones are pruned.
+const SNAPSHOTS_KEPT: i64 = 3;
+
+/// Transactions read and batch-verified at a time on replay, so memory
+/// stays bounded however long the log is. Small in tests, so they cross
+/// chunk boundaries cheaply.
+const REPLAY_CHUNK: usize = if cfg!(test) { 64 } else { 4096 };
 
 /// Append-only ledger backed by SQLite.
 ///
-/// On open, replays all transactions to rebuild balances.
-/// Merkle trees are built over transaction batches.
+/// On open, replays all transactions to rebuild balances, re-verifying
+/// every signature (legacy ones against their original encoding). If a
+/// [`Snapshot`] that checks out against the header of the block it was
+/// taken at is stored, its state is restored and only the transactions
+/// after that block are read and replayed.
+///
+/// Appended transactions are pending until [`Ledger::seal_block`] commits
+/// them to a signed [`BlockHeader`] chained to the previous one. Headers
+/// carry a Merkle root over the block's transactions and a state root over
+/// balances, nonces, supply totals and the transaction log, both
+/// re-checked on replay. Only the producers the ledger was
+/// opened with may sign blocks, whether sealed here, imported or replayed;
+/// a ledger opened without any holds no blocks.
+///
+/// Appends are atomic: a transaction's effects are staged, its row is
+/// committed in a SQLite transaction, and only then does the in-memory
+/// state move. On any error the ledger is left exactly as it was.
 pub struct Ledger {
     conn: Connection,
     balances: BalanceTracker,
     supply: SupplyTracker,
-    tx_ids: HashSet<String>,
+    /// Latest sealed header, if any.
+    head: Option<BlockHeader>,
+    /// Running Merkle root over appended transactions not yet in a block,
+    /// which becomes the next block's `tx_root`. Only their count and
+    /// O(log n) hashes are kept; the rows are found again by rowid.
+    pending: MerkleAccumulator,
+    /// Rowid of the last transaction in a sealed block (0 if none); every
+    /// row after it is pending.
+    sealed_rowid: i64,
+    /// Running Merkle root over every transaction hash, also committed to
+    /// by the state root.
+    log: MerkleAccumulator,
+    snapshot_interval: usize,
+    /// `tx_count` of the latest snapshot (0 if none).
+    last_snapshot: usize,
+    /// Hex-encoded keys allowed to sign blocks.
+    producers: HashSet<String>,
+}
+
+/// Where a replayed transaction is stored: its rowid, and its block
+/// height and position if sealed.
+struct StoredAt {
+    rowid: i64,
+    block: Option<(u64, u64)>,
+}
+
+/// A transaction's effects, computed by [`Ledger::stage_tx`] but not yet
+/// published.
+struct StagedTx {
+    balances: BalanceChanges,
+    supply: SupplyTracker,
 }
 
 impl Ledger {
-    /// Open (or create) a ledger at the given path.
+    /// Open (or create) a ledger at the given path, with no block producers.
+    ///
+    /// With no producers every block is refused, including stored ones, so
+    /// this only opens ledgers that have never sealed a block. A ledger with
+    /// sealed blocks fails with `InvalidBlock`; open it with
+    /// [`Ledger::open_with_producers`].
     pub fn open(path: &str) -> Result<Self, LedgerError> {
+        Self::open_with_producers(path, &[])
+    }
+
+    /// Open (or create) a ledger at the given path whose blocks must be
+    /// signed by one of `producers`.
+    pub fn open_with_producers(path: &str, producers: &[PublicKey]) -> Result<Self, LedgerError> {
         let conn = Connection::open(path).map_err(|e| LedgerError::Database(e.to_string()))?;
-        Self::init(conn)
+        Self::init(conn, producers)
     }
 
-    /// Create an in-memory ledger (for tests).
+    /// Create an in-memory ledger with no block producers (for tests). It
+    /// refuses every block; see [`Ledger::open`].
     pub fn in_memory() -> Result<Self, LedgerError> {
+        Self::in_memory_with_producers(&[])
+    }
+
+    /// Create an in-memory ledger whose blocks must be signed by one of
+    /// `producers` (for tests).
+    pub fn in_memory_with_producers(producers: &[PublicKey]) -> Result<Self, LedgerError> {
         let conn =
             Connection::open_in_memory().map_err(|e| LedgerError::Database(e.to_string()))?;
-        Self::init(conn)
+        Self::init(conn, producers)
     }
 
-    fn init(conn: Connection) -> Result<Self, LedgerError> {
+    fn init(conn: Connection, producers: &[PublicKey]) -> Result<Self, LedgerError> {
         conn.execute_batch(
             "
             CREATE TABLE IF NOT EXISTS transactions (
@@ -47,64 +124,316 @@ impl Ledger {
                 metadata    TEXT NOT NULL,
                 timestamp   INTEGER NOT NULL,
                 signature   TEXT NOT NULL,
-                pubkey      TEXT NOT NULL
+                pubkey      TEXT NOT NULL,
+                version     INTEGER NOT NULL DEFAULT 0,
+                nonce       INTEGER NOT NULL DEFAULT 0,
+                multisig    TEXT
             );
 
             CREATE INDEX IF NOT EXISTS idx_tx_from ON transactions (from_addr);
             CREATE INDEX IF NOT EXISTS idx_tx_to   ON transactions (to_addr);
             CREATE INDEX IF NOT EXISTS idx_tx_time ON transactions (timestamp);
+
+            CREATE TABLE IF NOT EXISTS blocks (
+                height      INTEGER PRIMARY KEY,
+                hash        TEXT NOT NULL UNIQUE,
+                prev_hash   TEXT NOT NULL,
+                tx_root     TEXT NOT NULL,
+                state_root  TEXT NOT NULL,
+                timestamp   INTEGER NOT NULL,
+                producer    TEXT NOT NULL,
+                signature   TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS block_txs (
+                height      INTEGER NOT NULL,
+                position    INTEGER NOT NULL,
+                tx_id       TEXT NOT NULL UNIQUE,
+                PRIMARY KEY (height, position)
+            );
+
+            CREATE TABLE IF NOT EXISTS snapshots (
+                tx_count    INTEGER PRIMARY KEY,
+                data        BLOB NOT NULL,
+                checksum    TEXT NOT NULL
+            );
             ",
         )
         .map_err(|e| LedgerError::Database(e.to_string()))?;
 
+        // Older ledgers lack the later columns; their rows default to
+        // legacy, nonce-less, single-key transactions.
+        for (column, definition) in [
+            ("version", "INTEGER NOT NULL DEFAULT 0"),
+            ("nonce", "INTEGER NOT NULL DEFAULT 0"),
+            ("multisig", "TEXT"),
+        ] {
+            let exists = conn
+                .prepare("SELECT 1 FROM pragma_table_info('transactions') WHERE name = ?1")
+                .and_then(|mut stmt| stmt.exists([column]))
+                .map_err(|e| LedgerError::Database(e.to_string()))?;
+            if !exists {
+                conn.execute_batch(&format!(
+                    "ALTER TABLE transactions ADD COLUMN {column} {definition};"
+                ))
+                .map_err(|e| LedgerError::Database(e.to_string()))?;
+            }
+        }
+
+        let supply = SupplyTracker::default_config();
+        let mut balances = BalanceTracker::new();
+        balances.set_supply(supply.total_minted(), supply.total_burned());
+        balances.set_log(0, MerkleAccumulator::new().root());
         let mut ledger = Self {
             conn,
-            balances: BalanceTracker::new(),
-            supply: SupplyTracker::default_config(),
-            tx_ids: HashSet::new(),
+            balances,
+            supply,
+            head: None,
+            pending: MerkleAccumulator::new(),
+            sealed_rowid: 0,
+            log: MerkleAccumulator::new(),
+            snapshot_interval: SNAPSHOT_INTERVAL,
+            last_snapshot: 0,
+            producers: producers.iter().map(|pk| hex::encode(&pk.bytes)).collect(),
         };
 
         ledger.replay()?;
         Ok(ledger)
     }
 
-    /// Replay all transactions from the database to rebuild balances.
+    /// Replay all transactions from the database to rebuild balances and
+    /// nonces, under the same signature and nonce rules as `append`.
+    ///
+    /// Transactions are read [`REPLAY_CHUNK`] at a time, each chunk's
+    /// signatures batch-verified up front; a bad one is reported when
+    /// replay reaches it, so errors surface in ledger order.
+    ///
+    /// Sealed blocks must cover a prefix of the transactions in order.
+    /// Each header is checked as [`Ledger::import_block`] would, with its
+    /// state root compared once replay reaches the end of the block.
+    ///
+    /// With a restored snapshot, neither the transactions nor the blocks
+    /// it covers are read again: its block's signed state root already
+    /// commits to them through the log.
     fn replay(&mut self) -> Result<(), LedgerError> {
-        let txs = Self::load_all_txs(&self.conn)?;
+        self.sealed_rowid = self.restore_snapshot()?;
+        let mut next_block = self.stored_block(self.next_height())?;
+        let mut last_rowid = self.sealed_rowid;
+        loop {
+            let (stored_at, txs): (Vec<StoredAt>, Vec<Transaction>) =
+                Self::load_chunk(&self.conn, last_rowid)?
+                    .into_iter()
+                    .unzip();
+            let mut bad_signature = Self::verify_signatures(&txs).err();
+            for (index, (at, tx)) in stored_at.iter().zip(&txs).enumerate() {
+                // Close every block ending here (empty blocks end anywhere).
+                self.close_blocks(&mut next_block, last_rowid)?;
+                let expected = next_block
+                    .as_ref()
+                    .map(|(header, _)| (header.height, self.pending.len() as u64));
+                if at.block != expected {
+                    return Err(LedgerError::InvalidBlock(match (expected, at.block) {
+                        (None, Some((height, _))) => {
+                            format!("transaction {} belongs to unknown block {height}", tx.tx_id)
+                        }
+                        _ => format!(
+                            "block {} does not match the stored transaction order",
+                            self.next_height()
+                        ),
+                    }));
+                }
+                if let Some((_, err)) = bad_signature.take_if(|(bad, _)| *bad == index) {
+                    return Err(err);
+                }
+                let staged = self.stage_tx(tx)?;
+                self.publish(tx, staged);
+                last_rowid = at.rowid;
+            }
+            if txs.len() < REPLAY_CHUNK {
+                break;
+            }
+        }
+        self.close_blocks(&mut next_block, last_rowid)?;
 
-        for tx in txs {
-            self.apply_tx(&tx)?;
-            self.tx_ids.insert(tx.tx_id);
+        let unreached = self
+            .conn
+            .prepare("SELECT 1 FROM blocks WHERE height >= ?1")
+            .and_then(|mut stmt| stmt.exists([self.next_height() as i64]))
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        if unreached {
+            return Err(LedgerError::InvalidBlock(format!(
+                "block {} does not match the stored transaction order",
+                self.next_height()
+            )));
         }
+        Ok(())
+    }
 
+    /// Close `next` and each following block whose transactions are exactly
+    /// the pending ones (counts match, as positions were checked on the way),
+    /// checking it as [`Ledger::import_block`] would. `last_rowid` is the row
+    /// of the last transaction replayed.
+    fn close_blocks(
+        &mut self,
+        next: &mut Option<(BlockHeader, usize)>,
+        last_rowid: i64,
+    ) -> Result<(), LedgerError> {
+        while let Some((header, _)) = next.take_if(|(_, count)| *count == self.pending.len()) {
+            self.check_header(&header, self.pending.root())?;
+            Self::check_state_root(&header, self.balances.state_root())?;
+            self.pending = MerkleAccumulator::new();
+            self.sealed_rowid = last_rowid;
+            self.head = Some(header);
+            *next = self.stored_block(self.next_height())?;
+        }
         Ok(())
     }
 
-    fn load_all_txs(conn: &Connection) -> Result<Vec<Transaction>, LedgerError> {
-        let mut stmt = conn
-            .prepare(
-                "SELECT tx_id, tx_type, from_addr, to_addr, amount, metadata, timestamp, signature, pubkey
-                 FROM transactions ORDER BY rowid",
+    /// Restore the newest stored snapshot that checks out against the
+    /// header of its block, returning the rowid of the last transaction it
+    /// covers (0 if none does). Snapshots that are corrupt, or whose state
+    /// doesn't hash to the state root signed by one of our producers, are
+    /// skipped.
+    fn restore_snapshot(&mut self) -> Result<i64, LedgerError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tx_count, data, checksum FROM snapshots ORDER BY tx_count DESC")
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+
+        for row in rows {
+            let (count, data, checksum) = row.map_err(|e| LedgerError::Database(e.to_string()))?;
+            let Some(snapshot) = Snapshot::decode(&data, &checksum) else {
+                continue;
+            };
+            if snapshot.tx_count != count as u64 {
+                continue;
+            }
+            let header = match self.block_header(snapshot.height) {
+                Ok(header) => header,
+                Err(LedgerError::Database(e)) => return Err(LedgerError::Database(e)),
+                Err(_) => continue,
+            };
+            if self.check_producer(&header).is_err()
+                || !header.verify_signature()
+                || header.state_root != snapshot.state_root
+            {
+                continue;
+            }
+            let (Some(balances), Some(log)) = (snapshot.balance_tracker(), snapshot.log()) else {
+                continue;
+            };
+            let Some(after) = self.last_sealed_rowid(header.height)? else {
+                continue;
+            };
+            self.balances = balances;
+            self.log = log;
+            self.supply
+                .restore_totals(snapshot.total_minted, snapshot.total_burned);
+            self.head = Some(header);
+            self.last_snapshot = count;
+            return Ok(after);
+        }
+        Ok(0)
+    }
+
+    /// Rowid of the last transaction in blocks up to `height` (0 if they
+    /// are all empty), or `None` if that transaction is missing.
+    fn last_sealed_rowid(&self, height: u64) -> Result<Option<i64>, LedgerError> {
+        let last = self
+            .conn
+            .query_row(
+                "SELECT t.rowid FROM block_txs b LEFT JOIN transactions t ON t.tx_id = b.tx_id
+                 WHERE b.height <= ?1 ORDER BY b.height DESC, b.position DESC LIMIT 1",
+                [height as i64],
+                |row| row.get::<_, Option<i64>>(0),
             )
+            .optional()
             .map_err(|e| LedgerError::Database(e.to_string()))?;
+        Ok(last.unwrap_or(Some(0)))
+    }
 
-        let rows = stmt.query_map([], |row| {
-            let tx_type_str: String = row.get(1)?;
-            let metadata_str: String = row.get(5)?;
-            Ok(Transaction {
-                tx_id: row.get(0)?,
-                tx_type: serde_json::from_str(&format!("\"{}\"", tx_type_str))
-                    .unwrap_or(TransactionType::Transfer),
-                from: Address(row.get(2)?),
-                to: Address(row.get(3)?),
-                amount: row.get::<_, i64>(4)? as u64,
-                metadata: serde_json::from_str(&metadata_str).unwrap_or(serde_json::json!({})),
-                timestamp: row.get(6)?,
-                signature: row.get(7)?,
-                pubkey: row.get(8)?,
-            })
-        })
+    /// Snapshot of the current state, which must be that at the end of
+    /// block `height`.
+    fn capture_snapshot(&self, height: u64) -> Snapshot {
+        Snapshot::capture(height, &self.log, &self.balances, &self.supply)
+    }
+
+    /// Write `snapshot`, keeping only the newest few.
+    fn insert_snapshot(conn: &Connection, snapshot: &Snapshot) -> Result<(), LedgerError> {
+        let (data, checksum) = snapshot.encode()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO snapshots (tx_count, data, checksum) VALUES (?1, ?2, ?3)",
+            rusqlite::params![snapshot.tx_count as i64, data, checksum],
+        )
         .map_err(|e| LedgerError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM snapshots WHERE tx_count NOT IN
+                 (SELECT tx_count FROM snapshots ORDER BY tx_count DESC LIMIT ?1)",
+            [SNAPSHOTS_KEPT],
+        )
+        .map_err(|e| LedgerError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Snapshot the current state so the next open can skip replaying
+    /// everything up to here. Only possible at a block boundary: at least
+    /// one block must be sealed and no transactions pending.
+    pub fn snapshot(&mut self) -> Result<(), LedgerError> {
+        let Some(head) = &self.head else {
+            return Err(LedgerError::InvalidBlock(
+                "cannot snapshot before the first block".to_string(),
+            ));
+        };
+        if !self.pending.is_empty() {
+            return Err(LedgerError::InvalidBlock(format!(
+                "cannot snapshot with {} unsealed transactions",
+                self.pending.len()
+            )));
+        }
+        let snapshot = self.capture_snapshot(head.height);
+        let db_tx = self
+            .conn
+            .transaction()
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        Self::insert_snapshot(&db_tx, &snapshot)?;
+        db_tx
+            .commit()
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        self.last_snapshot = self.tx_count();
+        Ok(())
+    }
+
+    /// Take a snapshot automatically when sealing a block once `interval`
+    /// transactions have been added since the last one (default
+    /// [`SNAPSHOT_INTERVAL`]; 0 turns automatic snapshots off).
+    pub fn set_snapshot_interval(&mut self, interval: usize) {
+        self.snapshot_interval = interval;
+    }
+
+    const TX_COLUMNS: &str =
+        "t.tx_id, t.tx_type, t.from_addr, t.to_addr, t.amount, t.metadata, t.timestamp,
+         t.signature, t.pubkey, t.version, t.nonce, t.multisig";
+
+    fn load_all_txs(conn: &Connection) -> Result<Vec<Transaction>, LedgerError> {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM transactions t ORDER BY t.rowid",
+                Self::TX_COLUMNS
+            ))
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], Self::tx_from_row)
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
 
         let result = rows
             .collect::<Result<Vec<_>, _>>()
@@ -113,16 +442,142 @@ impl Ledger {
         Ok(result)
     }
 
-    /// Apply a transaction's effects to balances and supply.
-    fn apply_tx(&mut self, tx: &Transaction) -> Result<(), LedgerError> {
+    /// Load up to [`REPLAY_CHUNK`] transactions stored after rowid `after`,
+    /// in log order, with where each is stored.
+    fn load_chunk(
+        conn: &Connection,
+        after: i64,
+    ) -> Result<Vec<(StoredAt, Transaction)>, LedgerError> {
+        let mut stmt = conn
+            .prepare_cached(&format!(
+                "SELECT {}, t.rowid, b.height, b.position
+                 FROM transactions t LEFT JOIN block_txs b ON b.tx_id = t.tx_id
+                 WHERE t.rowid > ?1 ORDER BY t.rowid LIMIT ?2",
+                Self::TX_COLUMNS
+            ))
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(rusqlite::params![after, REPLAY_CHUNK as i64], |row| {
+                let block = match (
+                    row.get::<_, Option<i64>>(13)?,
+                    row.get::<_, Option<i64>>(14)?,
+                ) {
+                    (Some(height), Some(position)) => Some((height as u64, position as u64)),
+                    _ => None,
+                };
+                let at = StoredAt {
+                    rowid: row.get(12)?,
+                    block,
+                };
+                Ok((at, Self::tx_from_row(row)?))
+            })
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| LedgerError::Database(e.to_string()))
+    }
+
+    /// Map a row selected with [`Ledger::TX_COLUMNS`]. A JSON column that
+    /// fails to parse is an error, never a default: silently dropping a
+    /// multisig authorization would change who the transaction claims
+    /// signed it.
+    fn tx_from_row(row: &rusqlite::Row) -> rusqlite::Result<Transaction> {
+        let tx_type_str: String = row.get(1)?;
+        let metadata_str: String = row.get(5)?;
+        let multisig_str: Option<String> = row.get(11)?;
+        Ok(Transaction {
+            tx_id: row.get(0)?,
+            tx_type: json_column(1, &format!("\"{}\"", tx_type_str))?,
+            from: Address(row.get(2)?),
+            to: Address(row.get(3)?),
+            amount: row.get::<_, i64>(4)? as u64,
+            metadata: json_column(5, &metadata_str)?,
+            timestamp: row.get(6)?,
+            signature: row.get(7)?,
+            pubkey: row.get(8)?,
+            version: row.get(9)?,
+            nonce: row.get::<_, i64>(10)? as u64,
+            multisig: multisig_str.map(|s| json_column(11, &s)).transpose()?,
+        })
+    }
+
+    /// Header at `height` with how many transactions the block holds, if
+    /// stored.
+    fn stored_block(&self, height: u64) -> Result<Option<(BlockHeader, usize)>, LedgerError> {
+        let block = self
+            .conn
+            .query_row(
+                "SELECT height, prev_hash, tx_root, state_root, timestamp, producer, signature,
+                        (SELECT COUNT(*) FROM block_txs b WHERE b.height = blocks.height)
+                 FROM blocks WHERE height = ?1",
+                [height as i64],
+                |row| Ok((Self::header_from_row(row)?, row.get::<_, i64>(7)? as usize)),
+            )
+            .optional()
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        match block {
+            Some((header, count)) => Ok(Some((header?, count))),
+            None => Ok(None),
+        }
+    }
+
+    /// Map a `blocks` row; hex fields that fail to decode are reported as
+    /// an invalid block rather than a database error.
+    fn header_from_row(row: &rusqlite::Row) -> rusqlite::Result<Result<BlockHeader, LedgerError>> {
+        let height = row.get::<_, i64>(0)? as u64;
+        let hash = |index| -> rusqlite::Result<Option<Hash256>> {
+            let hex_str: String = row.get(index)?;
+            Ok(hex::decode(hex_str)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok()))
+        };
+        let (Some(prev_hash), Some(tx_root), Some(state_root)) = (hash(1)?, hash(2)?, hash(3)?)
+        else {
+            return Ok(Err(LedgerError::InvalidBlock(format!(
+                "block {height} has a malformed hash"
+            ))));
+        };
+        Ok(Ok(BlockHeader {
+            height,
+            prev_hash,
+            tx_root,
+            state_root,
+            timestamp: row.get(4)?,
+            producer: row.get(5)?,
+            signature: row.get(6)?,
+        }))
+    }
+
+    /// Compute a transaction's effects on balances, supply and the
+    /// sender's nonce, running every check but changing nothing.
+    fn stage_tx(&self, tx: &Transaction) -> Result<StagedTx, LedgerError> {
+        let mut balances = self.balances.stage();
+        let mut supply = self.supply.clone();
+        Self::stage_effects(tx, &mut balances, &mut supply)?;
+        Ok(StagedTx {
+            balances: balances.into_changes(),
+            supply,
+        })
+    }
+
+    /// Apply `tx` on top of already staged state, so a block's
+    /// transactions can be staged together.
+    fn stage_effects(
+        tx: &Transaction,
+        balances: &mut StagedBalances,
+        supply: &mut SupplyTracker,
+    ) -> Result<(), LedgerError> {
+        if Self::uses_nonce(tx) {
+            balances.use_nonce(&tx.from, tx.nonce)?;
+        }
+        let totals = (supply.total_minted(), supply.total_burned());
         match tx.tx_type {
             TransactionType::Mint => {
-                self.supply.mint(tx.amount)?;
-                self.balances.credit(&tx.to, tx.amount);
+                supply.mint(tx.amount)?;
+                balances.credit(&tx.to, tx.amount);
             }
             TransactionType::Burn => {
-                self.balances.debit(&tx.from, tx.amount)?;
-                self.supply.burn(tx.amount);
+                balances.debit(&tx.from, tx.amount)?;
+                supply.burn(tx.amount);
             }
             TransactionType::Transfer
             | TransactionType::PushFee
@@ -132,77 +587,560 @@ impl Ledger {
             | TransactionType::BandwidthReward => {
                 if tx.from == Address::system() {
                     // Reward from system: just credit
-                    self.supply.mint(tx.amount)?;
-                    self.balances.credit(&tx.to, tx.amount);
+                    supply.mint(tx.amount)?;
+                    balances.credit(&tx.to, tx.amount);
                 } else {
-                    self.balances.transfer(&tx.from, &tx.to, tx.amount)?;
+                    balances.transfer(&tx.from, &tx.to, tx.amount)?;
                 }
             }
         }
+        if (supply.total_minted(), supply.total_burned()) != totals {
+            balances.set_supply(supply.total_minted(), supply.total_burned());
+        }
+        Ok(())
+    }
+
+    /// Make a staged transaction's effects visible and queue it for the
+    /// next block. Cannot fail, so the in-memory state never ends up
+    /// half-applied.
+    fn publish(&mut self, tx: &Transaction, staged: StagedTx) {
+        self.balances.commit(staged.balances);
+        self.supply = staged.supply;
+        let hash = tx.hash();
+        self.pending.push(hash);
+        self.log.push(hash);
+        self.balances.set_log(self.log.len(), self.log.root());
+    }
+
+    /// Signed transactions are sequenced by nonce. System transactions
+    /// are unsigned, and legacy ones predate nonces.
+    fn uses_nonce(tx: &Transaction) -> bool {
+        tx.from != Address::system() && !tx.is_legacy()
+    }
+
+    /// Check that `tx` would be accepted by [`Ledger::append`] right now,
+    /// without changing any state.
+    ///
+    /// Runs the same signature, duplicate and nonce checks, then checks
+    /// the sender can cover the debit. Use it to catch a bad transaction
+    /// before submitting it.
+    pub fn validate(&self, tx: &Transaction) -> Result<(), LedgerError> {
+        Self::check_version(tx)?;
+        Self::verify_signature(tx)?;
+        self.check_duplicate(&tx.tx_id)?;
+        self.stage_tx(tx).map(|_| ())
+    }
+
+    /// Reject a tx_id that is already stored. Served by the primary key
+    /// index, so no ids are kept in memory.
+    fn check_duplicate(&self, tx_id: &str) -> Result<(), LedgerError> {
+        let exists = self
+            .conn
+            .prepare_cached("SELECT 1 FROM transactions WHERE tx_id = ?1")
+            .and_then(|mut stmt| stmt.exists([tx_id]))
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        if exists {
+            return Err(LedgerError::DuplicateTransaction(tx_id.to_string()));
+        }
         Ok(())
     }
 
     /// Append a new transaction to the ledger.
     ///
     /// Validates:
-    /// - Signature is valid for the 'from' address
+    /// - Signature is valid for the 'from' address (for a multisig
+    ///   address: the policy hashes to it and meets its threshold)
     /// - No duplicate tx_id
+    /// - Signed transactions carry the sender's next nonce
     /// - Sufficient balance for debits
+    ///
+    /// Only current-version transactions are accepted; legacy ones are
+    /// honoured on replay but can no longer be appended.
     pub fn append(&mut self, tx: Transaction) -> Result<(), LedgerError> {
-        // Signature Verification (skip for system address)
-        if tx.from != Address::system() {
-            // 1. Verify that the pubkey hashes to the 'from' address
-            let pubkey_bytes = hex::decode(&tx.pubkey)
-                .map_err(|_| LedgerError::InvalidTransaction("Invalid hex in pubkey".to_string()))?;
-            let derived_addr = sha256_hex(&pubkey_bytes);
-            if derived_addr != tx.from.0 {
-                return Err(LedgerError::InvalidSignature);
-            }
+        Self::check_version(&tx)?;
+        Self::verify_signature(&tx)?;
+        self.append_verified(tx)
+    }
 
-            // 2. Verify the Ed25519 signature
-            let pk = PublicKey {
-                bytes: pubkey_bytes,
-            };
-            let sig_bytes = hex::decode(&tx.signature)
-                .map_err(|_| LedgerError::InvalidTransaction("Invalid hex in signature".to_string()))?;
-            if !pk.verify(&tx.signable_bytes(), &sig_bytes) {
-                return Err(LedgerError::InvalidSignature);
+    /// Append a block of transactions in order, with the same checks as
+    /// [`Ledger::append`] but with all signatures batch-verified first.
+    ///
+    /// Stops at the first rejected transaction and returns its error; the
+    /// transactions before it stay appended.
+    pub fn append_batch(&mut self, txs: Vec<Transaction>) -> Result<(), LedgerError> {
+        let mut bad_signature = Self::verify_signatures(&txs).err();
+        for (index, tx) in txs.into_iter().enumerate() {
+            Self::check_version(&tx)?;
+            if let Some((_, err)) = bad_signature.take_if(|(bad, _)| *bad == index) {
+                return Err(err);
             }
+            self.append_verified(tx)?;
         }
+        Ok(())
+    }
 
+    /// Append a transaction whose version and signature are already checked.
+    fn append_verified(&mut self, tx: Transaction) -> Result<(), LedgerError> {
         // Duplicate check
-        if self.tx_ids.contains(&tx.tx_id) {
-            return Err(LedgerError::DuplicateTransaction(tx.tx_id.clone()));
-        }
+        self.check_duplicate(&tx.tx_id)?;
 
-        // Apply to balances (validates balance sufficiency)
-        self.apply_tx(&tx)?;
+        // Stage balance effects (validates nonce and balance sufficiency)
+        let staged = self.stage_tx(&tx)?;
 
-        // Persist to SQLite
-        let tx_type_str = serde_json::to_string(&tx.tx_type)
-            .unwrap_or_default()
-            .trim_matches('"')
-            .to_string();
+        // Persist, then publish only once the row is committed
+        self.insert_tx(&tx)?;
+        self.publish(&tx, staged);
+        Ok(())
+    }
 
-        self.conn
+    /// Write `tx` in its own SQLite transaction; any failure rolls it back.
+    fn insert_tx(&mut self, tx: &Transaction) -> Result<(), LedgerError> {
+        let db_tx = self
+            .conn
+            .transaction()
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        Self::insert_tx_row(&db_tx, tx)?;
+        db_tx
+            .commit()
+            .map_err(|e| LedgerError::Database(e.to_string()))
+    }
+
+    fn insert_tx_row(conn: &Connection, tx: &Transaction) -> Result<(), LedgerError> {
+        let tx_type_str = to_json(&tx.tx_type)?.trim_matches('"').to_string();
+        let metadata = to_json(&tx.metadata)?;
+        let multisig = tx.multisig.as_ref().map(to_json).transpose()?;
+
+        conn
             .execute(
-                "INSERT INTO transactions (tx_id, tx_type, from_addr, to_addr, amount, metadata, timestamp, signature, pubkey)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                "INSERT INTO transactions (tx_id, tx_type, from_addr, to_addr, amount, metadata, timestamp, signature, pubkey, version, nonce, multisig)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
                 rusqlite::params![
                     tx.tx_id,
                     tx_type_str,
                     tx.from.0,
                     tx.to.0,
                     tx.amount as i64,
-                    serde_json::to_string(&tx.metadata).unwrap_or_default(),
+                    metadata,
                     tx.timestamp,
                     tx.signature,
                     tx.pubkey,
+                    tx.version,
+                    tx.nonce as i64,
+                    multisig,
                 ],
             )
             .map_err(|e| LedgerError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Write a header and its transaction positions.
+    fn insert_block(
+        conn: &Connection,
+        header: &BlockHeader,
+        tx_ids: impl Iterator<Item = impl AsRef<str>>,
+    ) -> Result<(), LedgerError> {
+        Self::insert_header(conn, header)?;
+        for (position, tx_id) in tx_ids.enumerate() {
+            conn.execute(
+                "INSERT INTO block_txs (height, position, tx_id) VALUES (?1, ?2, ?3)",
+                rusqlite::params![header.height as i64, position as i64, tx_id.as_ref()],
+            )
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn insert_header(conn: &Connection, header: &BlockHeader) -> Result<(), LedgerError> {
+        conn.execute(
+            "INSERT INTO blocks (height, hash, prev_hash, tx_root, state_root, timestamp, producer, signature)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                header.height as i64,
+                hex::encode(header.hash()),
+                hex::encode(header.prev_hash),
+                hex::encode(header.tx_root),
+                hex::encode(header.state_root),
+                header.timestamp,
+                header.producer,
+                header.signature,
+            ],
+        )
+        .map_err(|e| LedgerError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Rowid of the newest transaction (0 if there are none).
+    fn last_rowid(conn: &Connection) -> Result<i64, LedgerError> {
+        conn.query_row(
+            "SELECT COALESCE(MAX(rowid), 0) FROM transactions",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| LedgerError::Database(e.to_string()))
+    }
+
+    /// Seal every pending transaction into the next block, signed by
+    /// `producer`, which must be one of the ledger's producers. The block
+    /// may be empty.
+    ///
+    /// The header and its transaction positions are written in one SQLite
+    /// transaction, with a snapshot if one is due (see
+    /// [`Ledger::set_snapshot_interval`]); on error nothing is sealed.
+    pub fn seal_block(&mut self, producer: &Wallet) -> Result<BlockHeader, LedgerError> {
+        let timestamp = chrono::Utc::now()
+            .timestamp()
+            .max(self.head.as_ref().map_or(0, |head| head.timestamp));
+        let header = BlockHeader::sign(
+            self.next_height(),
+            self.head_hash(),
+            self.pending.root(),
+            self.balances.state_root(),
+            timestamp,
+            producer,
+        );
+        self.check_producer(&header)?;
+
+        let snapshot = (self.snapshot_interval > 0
+            && self.tx_count() - self.last_snapshot >= self.snapshot_interval)
+            .then(|| self.capture_snapshot(header.height));
+
+        let db_tx = self
+            .conn
+            .transaction()
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        Self::insert_header(&db_tx, &header)?;
+        // Every row after the last sealed one is pending, in order.
+        let sealed = db_tx
+            .execute(
+                "INSERT INTO block_txs (height, position, tx_id)
+                 SELECT ?1, ROW_NUMBER() OVER (ORDER BY rowid) - 1, tx_id
+                 FROM transactions WHERE rowid > ?2",
+                rusqlite::params![header.height as i64, self.sealed_rowid],
+            )
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        if sealed != self.pending.len() {
+            return Err(LedgerError::Database(format!(
+                "expected {} pending transactions, found {sealed}",
+                self.pending.len()
+            )));
+        }
+        let sealed_rowid = Self::last_rowid(&db_tx)?;
+        if let Some(snapshot) = &snapshot {
+            Self::insert_snapshot(&db_tx, snapshot)?;
+        }
+        db_tx
+            .commit()
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+
+        self.pending = MerkleAccumulator::new();
+        self.sealed_rowid = sealed_rowid;
+        self.head = Some(header.clone());
+        if snapshot.is_some() {
+            self.last_snapshot = self.tx_count();
+        }
+        Ok(header)
+    }
+
+    /// Append a block sealed elsewhere, after checking its header links to
+    /// ours, is signed by one of our producers, and commits to exactly these transactions and the
+    /// state they produce. Each transaction gets the same checks as
+    /// [`Ledger::append`].
+    ///
+    /// All or nothing: on any error the ledger is left as it was. Fails if
+    /// this ledger has unsealed transactions of its own.
+    pub fn import_block(&mut self, block: Block) -> Result<(), LedgerError> {
+        if !self.pending.is_empty() {
+            return Err(LedgerError::InvalidBlock(format!(
+                "{} local transactions are not sealed",
+                self.pending.len()
+            )));
+        }
+        let Block { header, txs } = block;
+        let hashes: Vec<Hash256> = txs.iter().map(Transaction::hash).collect();
+        self.check_header(&header, MerkleTree::build(hashes.clone()).root())?;
+        Self::verify_signatures(&txs).map_err(|(_, err)| err)?;
+
+        let mut balances = self.balances.stage();
+        let mut supply = self.supply.clone();
+        let mut seen = HashSet::new();
+        for tx in &txs {
+            Self::check_version(tx)?;
+            if !seen.insert(&tx.tx_id) {
+                return Err(LedgerError::DuplicateTransaction(tx.tx_id.clone()));
+            }
+            self.check_duplicate(&tx.tx_id)?;
+            Self::stage_effects(tx, &mut balances, &mut supply)?;
+        }
+        let mut log = self.log.clone();
+        for &hash in &hashes {
+            log.push(hash);
+        }
+        balances.set_log(log.len(), log.root());
+        Self::check_state_root(&header, balances.state_root())?;
+        let changes = balances.into_changes();
+
+        let db_tx = self
+            .conn
+            .transaction()
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        for tx in &txs {
+            Self::insert_tx_row(&db_tx, tx)?;
+        }
+        Self::insert_block(&db_tx, &header, txs.iter().map(|tx| &tx.tx_id))?;
+        let sealed_rowid = Self::last_rowid(&db_tx)?;
+        db_tx
+            .commit()
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+
+        self.sealed_rowid = sealed_rowid;
+        self.balances.commit(changes);
+        self.supply = supply;
+        self.log = log;
+        self.head = Some(header);
+        Ok(())
+    }
+
+    /// Check `header` extends the chain, is signed by an allowed producer,
+    /// and commits to transactions whose Merkle root is `tx_root`.
+    fn check_header(&self, header: &BlockHeader, tx_root: Hash256) -> Result<(), LedgerError> {
+        let invalid = |reason: &str| {
+            Err(LedgerError::InvalidBlock(format!(
+                "block {}: {reason}",
+                header.height
+            )))
+        };
+        if header.height != self.next_height() {
+            return invalid(&format!("expected height {}", self.next_height()));
+        }
+        if header.prev_hash != self.head_hash() {
+            return invalid("does not link to the previous header");
+        }
+        if self
+            .head
+            .as_ref()
+            .is_some_and(|head| header.timestamp < head.timestamp)
+        {
+            return invalid("timestamp is before the previous block");
+        }
+        self.check_producer(header)?;
+        if !header.verify_signature() {
+            return invalid("bad producer signature");
+        }
+        if tx_root != header.tx_root {
+            return invalid("transaction root mismatch");
+        }
+        Ok(())
+    }
+
+    /// The header's producer key, which its signature is checked against,
+    /// must be one the ledger trusts.
+    fn check_producer(&self, header: &BlockHeader) -> Result<(), LedgerError> {
+        if !self.producers.contains(&header.producer) {
+            return Err(LedgerError::InvalidBlock(format!(
+                "block {}: producer {} is not authorized",
+                header.height, header.producer
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_state_root(header: &BlockHeader, state_root: Hash256) -> Result<(), LedgerError> {
+        if state_root != header.state_root {
+            return Err(LedgerError::InvalidBlock(format!(
+                "block {}: state root mismatch",
+                header.height
+            )));
+        }
+        Ok(())
+    }
+
+    fn next_height(&self) -> u64 {
+        self.head.as_ref().map_or(0, |head| head.height + 1)
+    }
+
+    fn head_hash(&self) -> Hash256 {
+        self.head
+            .as_ref()
+            .map_or(GENESIS_PREV_HASH, BlockHeader::hash)
+    }
+
+    /// Latest sealed header.
+    pub fn head(&self) -> Option<&BlockHeader> {
+        self.head.as_ref()
+    }
+
+    /// Number of appended transactions not yet sealed into a block.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Get the header at `height`.
+    pub fn block_header(&self, height: u64) -> Result<BlockHeader, LedgerError> {
+        self.conn
+            .query_row(
+                "SELECT height, prev_hash, tx_root, state_root, timestamp, producer, signature
+                 FROM blocks WHERE height = ?1",
+                [height as i64],
+                Self::header_from_row,
+            )
+            .optional()
+            .map_err(|e| LedgerError::Database(e.to_string()))?
+            .ok_or(LedgerError::BlockNotFound(height))?
+    }
+
+    /// Get the block at `height` with its transactions, e.g. to serve it
+    /// to a peer's [`Ledger::import_block`].
+    pub fn block(&self, height: u64) -> Result<Block, LedgerError> {
+        let header = self.block_header(height)?;
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM block_txs b JOIN transactions t ON t.tx_id = b.tx_id
+                 WHERE b.height = ?1 ORDER BY b.position",
+                Self::TX_COLUMNS
+            ))
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        let txs = stmt
+            .query_map([height as i64], Self::tx_from_row)
+            .map_err(|e| LedgerError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| LedgerError::Database(e.to_string()))?;
+        Ok(Block { header, txs })
+    }
+
+    /// Header of the block containing `tx_id` and a Merkle proof of the
+    /// transaction against its `tx_root`. See
+    /// [`BlockHeader::verify_inclusion`].
+    pub fn inclusion_proof(
+        &self,
+        tx_id: &str,
+    ) -> Result<(BlockHeader, Vec<ProofStep>), LedgerError> {
+        let height = self
+            .conn
+            .query_row(
+                "SELECT height FROM block_txs WHERE tx_id = ?1",
+                [tx_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()
+            .map_err(|e| LedgerError::Database(e.to_string()))?
+            .ok_or_else(|| LedgerError::NotInBlock(tx_id.to_string()))?;
+        let block = self.block(height as u64)?;
+        let position = block
+            .txs
+            .iter()
+            .position(|tx| tx.tx_id == tx_id)
+            .ok_or_else(|| LedgerError::NotInBlock(tx_id.to_string()))?;
+        let tree = MerkleTree::build(block.txs.iter().map(Transaction::hash).collect());
+        let proof = tree
+            .proof(position)
+            .ok_or_else(|| LedgerError::NotInBlock(tx_id.to_string()))?;
+        Ok((block.header, proof))
+    }
+
+    fn check_version(tx: &Transaction) -> Result<(), LedgerError> {
+        if tx.version != TX_VERSION {
+            return Err(LedgerError::InvalidTransaction(format!(
+                "unsupported transaction version {}, expected {TX_VERSION}",
+                tx.version
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check the sender's signature (system transactions are unsigned).
+    fn verify_signature(tx: &Transaction) -> Result<(), LedgerError> {
+        if tx.from == Address::system() {
+            return Ok(());
+        }
+        if let Some(auth) = &tx.multisig {
+            return Self::verify_multisig(tx, auth);
+        }
+        let (pk, sig_bytes) = Self::decode_signer(tx)?;
+        if !pk.verify(&tx.signable_bytes(), &sig_bytes) {
+            return Err(LedgerError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Check the signatures of `txs` with one batch verification, returning
+    /// the first failing index and its error.
+    ///
+    /// Single-key signatures are batched; multisig transactions are rare
+    /// and checked one at a time.
+    fn verify_signatures(txs: &[Transaction]) -> Result<(), (usize, LedgerError)> {
+        let mut first_error = None;
+        let mut signed = Vec::new();
+        for (index, tx) in txs.iter().enumerate() {
+            if tx.from == Address::system() {
+                continue;
+            }
+            let checked = if tx.multisig.is_some() {
+                Self::verify_signature(tx).map(|_| None)
+            } else {
+                Self::decode_signer(tx).map(Some)
+            };
+            match checked {
+                Ok(Some((pk, sig_bytes))) => signed.push((index, pk, sig_bytes, tx.signable_bytes())),
+                Ok(None) => {}
+                Err(err) => {
+                    // Nothing after the first failure matters.
+                    first_error = Some((index, err));
+                    break;
+                }
+            }
+        }
+
+        let items: Vec<BatchItem> = signed
+            .iter()
+            .map(|(_, pk, sig_bytes, message)| BatchItem {
+                pubkey: pk,
+                message,
+                signature: sig_bytes,
+            })
+            .collect();
+        if let Err(invalid) = keys::verify_batch(&items) {
+            let index = signed[invalid[0]].0;
+            if first_error.as_ref().is_none_or(|(first, _)| index < *first) {
+                first_error = Some((index, LedgerError::InvalidSignature));
+            }
+        }
+
+        match first_error {
+            Some(failure) => Err(failure),
+            None => Ok(()),
+        }
+    }
+
+    /// Decode a single-key sender's public key and signature, checking the
+    /// key hashes to the 'from' address.
+    fn decode_signer(tx: &Transaction) -> Result<(PublicKey, Vec<u8>), LedgerError> {
+        let pubkey_bytes = hex::decode(&tx.pubkey)
+            .map_err(|_| LedgerError::InvalidTransaction("Invalid hex in pubkey".to_string()))?;
+        let derived_addr = sha256_hex(&pubkey_bytes);
+        if derived_addr != tx.from.0 {
+            return Err(LedgerError::InvalidSignature);
+        }
+        let sig_bytes = hex::decode(&tx.signature)
+            .map_err(|_| LedgerError::InvalidTransaction("Invalid hex in signature".to_string()))?;
+        Ok((
+            PublicKey {
+                bytes: pubkey_bytes,
+            },
+            sig_bytes,
+        ))
+    }
 
-        self.tx_ids.insert(tx.tx_id);
+    /// Check a multisig sender: the policy must derive the 'from' address
+    /// and enough members must have signed.
+    fn verify_multisig(tx: &Transaction, auth: &MultisigAuth) -> Result<(), LedgerError> {
+        let policy = auth.policy()?;
+        if policy.address() != tx.from {
+            return Err(LedgerError::InvalidSignature);
+        }
+        if !policy.verify(&tx.signable_bytes(), &auth.decoded_signatures()?) {
+            return Err(LedgerError::InvalidSignature);
+        }
         Ok(())
     }
 
@@ -211,6 +1149,11 @@ impl Ledger {
         self.balances.balance(addr)
     }
 
+    /// Nonce the next signed transaction from `addr` must carry.
+    pub fn next_nonce(&self, addr: &Address) -> u64 {
+        self.balances.next_nonce(addr)
+    }
+
     /// Get all balances.
     pub fn balances(&self) -> &BalanceTracker {
         &self.balances
@@ -221,7 +1164,12 @@ impl Ledger {
         &self.supply
     }
 
-    /// Build a Merkle tree over all transaction hashes.
+    /// Running Merkle accumulator over every transaction hash.
+    pub fn log(&self) -> &MerkleAccumulator {
+        &self.log
+    }
+
+    /// Build a Merkle tree over all transaction hashes, across blocks.
     pub fn merkle_tree(&self) -> Result<MerkleTree, LedgerError> {
         let txs = Self::load_all_txs(&self.conn)?;
         let hashes: Vec<[u8; 32]> = txs.iter().map(|tx| tx.hash()).collect();
@@ -230,10 +1178,22 @@ impl Ledger {
 
     /// Total number of transactions.
     pub fn tx_count(&self) -> usize {
-        self.tx_ids.len()
+        self.log.len()
     }
 }
 
+/// Parse JSON stored in column `idx`, failing the row on malformed data.
+fn json_column<T: serde::de::DeserializeOwned>(idx: usize, json: &str) -> rusqlite::Result<T> {
+    serde_json::from_str(json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+/// Serialize a transaction field for storage.
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, LedgerError> {
+    serde_json::to_string(value).map_err(|e| LedgerError::InvalidTransaction(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,12 +1208,15 @@ mod tests {
             amount,
             metadata: serde_json::json!({}),
             timestamp: 1700000000,
+            nonce: 0,
             signature: String::new(),
             pubkey: String::new(),
+            version: TX_VERSION,
+            multisig: None,
         }
     }
 
-    fn transfer_tx(kp: &KeyPair, to: &str, amount: MicroGitGold) -> Transaction {
+    fn transfer_tx(kp: &KeyPair, nonce: u64, to: &str, amount: MicroGitGold) -> Transaction {
         let mut tx = Transaction {
             tx_id: uuid::Uuid::new_v4().to_string(),
             tx_type: TransactionType::Transfer,
@@ -262,8 +1225,11 @@ mod tests {
             amount,
             metadata: serde_json::json!({}),
             timestamp: 1700000000,
+            nonce,
             signature: String::new(),
             pubkey: hex::encode(kp.public_key().bytes),
+            version: TX_VERSION,
+            multisig: None,
         };
         tx.signature = hex::encode(kp.sign(&tx.signable_bytes()));
         tx
@@ -284,7 +1250,7 @@ mod tests {
 
         ledger.append(mint_tx(&alice_addr.0, 1_000_000)).unwrap();
         ledger
-            .append(transfer_tx(&alice_kp, "bob", 400_000))
+            .append(transfer_tx(&alice_kp, 0, "bob", 400_000))
             .unwrap();
 
         assert_eq!(ledger.balance(&alice_addr), 600_000);
@@ -299,11 +1265,11 @@ mod tests {
 
         ledger.append(mint_tx(&alice_addr.0, 500_000)).unwrap();
         ledger
-            .append(transfer_tx(&alice_kp, "bob", 300_000))
+            .append(transfer_tx(&alice_kp, 0, "bob", 300_000))
             .unwrap();
 
         // Alice only has 200k left, can't send 300k
-        let result = ledger.append(transfer_tx(&alice_kp, "charlie", 300_000));
+        let result = ledger.append(transfer_tx(&alice_kp, 1, "charlie", 300_000));
         assert!(matches!(
             result,
             Err(LedgerError::InsufficientBalance { .. })
@@ -320,7 +1286,7 @@ mod tests {
         ledger.append(mint_tx(&alice_addr.0, 1_000_000)).unwrap();
 
         // Bob tries to spend Alice's money
-        let mut bad_tx = transfer_tx(&bob_kp, "mallory", 300_000);
+        let mut bad_tx = transfer_tx(&bob_kp, 0, "mallory", 300_000);
         bad_tx.from = alice_addr; // forge 'from' address
 
         let result = ledger.append(bad_tx);
@@ -335,15 +1301,18 @@ mod tests {
         ledger.append(tx).unwrap();
 
         let duplicate = Transaction {
-            tx_id: tx_id,
+            tx_id,
             tx_type: TransactionType::Mint,
             from: Address::system(),
             to: Address::new("alice"),
             amount: 999,
             metadata: serde_json::json!({}),
             timestamp: 1700000000,
+            nonce: 0,
             signature: String::new(),
             pubkey: String::new(),
+            version: TX_VERSION,
+            multisig: None,
         };
         assert!(matches!(
             ledger.append(duplicate),
@@ -367,8 +1336,11 @@ mod tests {
             amount: 100_000,
             metadata: serde_json::json!({}),
             timestamp: 1700000000,
+            nonce: 0,
             signature: String::new(),
             pubkey: hex::encode(alice_kp.public_key().bytes),
+            version: TX_VERSION,
+            multisig: None,
         };
         burn.signature = hex::encode(alice_kp.sign(&burn.signable_bytes()));
 
@@ -399,4 +1371,935 @@ mod tests {
         ledger.append(mint_tx("bob", 200)).unwrap();
         assert_eq!(ledger.tx_count(), 2);
     }
+
+    #[test]
+    fn test_append_batch_stops_at_bad_signature() {
+        let mut ledger = Ledger::in_memory().unwrap();
+        let alice_kp = KeyPair::generate();
+        let alice_addr = alice_kp.address();
+
+        let mut forged = transfer_tx(&alice_kp, 3, "mallory", 100);
+        forged.amount = 500;
+        ledger
+            .append_batch(vec![
+                mint_tx(&alice_addr.0, 1_000),
+                transfer_tx(&alice_kp, 0, "bob", 100),
+                transfer_tx(&alice_kp, 1, "bob", 100),
+            ])
+            .unwrap();
+        let result = ledger.append_batch(vec![
+            transfer_tx(&alice_kp, 2, "bob", 100),
+            forged,
+            transfer_tx(&alice_kp, 4, "bob", 100),
+        ]);
+        assert!(matches!(result, Err(LedgerError::InvalidSignature)));
+
+        // Everything before the forgery was applied, nothing after it.
+        assert_eq!(ledger.tx_count(), 4);
+        assert_eq!(ledger.balance(&Address::new("bob")), 300);
+        assert_eq!(ledger.balance(&Address::new("mallory")), 0);
+        assert_eq!(ledger.next_nonce(&alice_addr), 3);
+    }
+
+    #[test]
+    fn test_nonce_replay_rejected() {
+        let mut ledger = Ledger::in_memory().unwrap();
+        let alice_kp = KeyPair::generate();
+        let alice_addr = alice_kp.address();
+        ledger.append(mint_tx(&alice_addr.0, 1_000_000)).unwrap();
+        ledger
+            .append(transfer_tx(&alice_kp, 0, "bob", 100))
+            .unwrap();
+
+        // Same payment re-signed under a fresh tx_id, and a skipped nonce.
+        for nonce in [0, 2] {
+            let tx = transfer_tx(&alice_kp, nonce, "bob", 100);
+            assert!(matches!(
+                ledger.validate(&tx),
+                Err(LedgerError::InvalidNonce { expected: 1, .. })
+            ));
+            assert!(matches!(
+                ledger.append(tx),
+                Err(LedgerError::InvalidNonce { expected: 1, .. })
+            ));
+        }
+
+        // A rejected overspend does not consume the nonce.
+        assert!(matches!(
+            ledger.append(transfer_tx(&alice_kp, 1, "bob", 10_000_000)),
+            Err(LedgerError::InsufficientBalance { .. })
+        ));
+        assert_eq!(ledger.next_nonce(&alice_addr), 1);
+        ledger
+            .append(transfer_tx(&alice_kp, 1, "bob", 100))
+            .unwrap();
+        assert_eq!(ledger.next_nonce(&alice_addr), 2);
+        assert_eq!(ledger.balance(&Address::new("bob")), 200);
+    }
+
+    #[test]
+    fn test_multisig_policy_enforced() {
+        use crate::builder::TransactionBuilder;
+        use gitgold_crypto::multisig::MultisigPolicy;
+        use gitgold_crypto::wallet::Wallet;
+
+        let path =
+            std::env::temp_dir().join(format!("gitgold-ledger-multisig-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let members: Vec<Wallet> = (0..3).map(|_| Wallet::new()).collect();
+        let pks: Vec<_> = members.iter().map(Wallet::public_key).collect();
+        let policy = MultisigPolicy::new(2, &pks).unwrap();
+        let treasury = policy.address();
+
+        let mut ledger = Ledger::open(path.to_str().unwrap()).unwrap();
+        ledger.append(mint_tx(&treasury.0, 1_000)).unwrap();
+
+        // An outsider's own 1-of-1 policy doesn't hash to the treasury.
+        let outsider = Wallet::new();
+        let own = MultisigPolicy::new(1, &[outsider.public_key()]).unwrap();
+        let mut forged = TransactionBuilder::transfer(Address::new("mallory"), 1_000)
+            .build_multisig(&own)
+            .unwrap();
+        forged.from = treasury.clone();
+        forged.cosign(&outsider).unwrap();
+        assert!(matches!(
+            ledger.append(forged),
+            Err(LedgerError::InvalidSignature)
+        ));
+
+        let mut tx = TransactionBuilder::transfer(Address::new("bob"), 300)
+            .build_multisig(&policy)
+            .unwrap();
+        tx.cosign(&members[0]).unwrap();
+        tx.cosign(&members[2]).unwrap();
+        ledger.append(tx).unwrap();
+        drop(ledger);
+
+        // Co-signatures survive the round trip through SQLite.
+        let ledger = Ledger::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(ledger.balance(&treasury), 700);
+        assert_eq!(ledger.next_nonce(&treasury), 1);
+
+        // A corrupt authorization fails the load rather than reading back
+        // as a single-key transaction.
+        ledger
+            .conn
+            .execute(
+                "UPDATE transactions SET multisig = '{\"policy\":' WHERE multisig IS NOT NULL",
+                [],
+            )
+            .unwrap();
+        drop(ledger);
+        assert!(matches!(
+            Ledger::open(path.to_str().unwrap()),
+            Err(LedgerError::Database(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_enforces_nonces() {
+        let path =
+            std::env::temp_dir().join(format!("gitgold-ledger-nonce-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let alice_kp = KeyPair::generate();
+        let alice_addr = alice_kp.address();
+
+        let mut ledger = Ledger::open(path.to_str().unwrap()).unwrap();
+        ledger.append(mint_tx(&alice_addr.0, 1_000)).unwrap();
+        ledger
+            .append(transfer_tx(&alice_kp, 0, "bob", 100))
+            .unwrap();
+        drop(ledger);
+        let ledger = Ledger::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(ledger.next_nonce(&alice_addr), 1);
+
+        // Smuggle a replayed nonce straight into the table.
+        let replayed = transfer_tx(&alice_kp, 0, "bob", 100);
+        ledger
+            .conn
+            .execute(
+                "INSERT INTO transactions (tx_id, tx_type, from_addr, to_addr, amount, metadata, timestamp, signature, pubkey, version, nonce)
+                 VALUES (?1, 'Transfer', ?2, 'bob', 100, '{}', 1700000000, ?3, ?4, ?5, 0)",
+                rusqlite::params![
+                    replayed.tx_id,
+                    replayed.from.0,
+                    replayed.signature,
+                    replayed.pubkey,
+                    replayed.version,
+                ],
+            )
+            .unwrap();
+        drop(ledger);
+
+        assert!(matches!(
+            Ledger::open(path.to_str().unwrap()),
+            Err(LedgerError::InvalidNonce {
+                expected: 1,
+                actual: 0,
+                ..
+            })
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// Write `txs` into a ledger file using the pre-versioning schema.
+    fn write_legacy_db(path: &std::path::Path, txs: &[Transaction]) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE transactions (
+                tx_id TEXT PRIMARY KEY, tx_type TEXT NOT NULL, from_addr TEXT NOT NULL,
+                to_addr TEXT NOT NULL, amount INTEGER NOT NULL, metadata TEXT NOT NULL,
+                timestamp INTEGER NOT NULL, signature TEXT NOT NULL, pubkey TEXT NOT NULL
+            );",
+        )
+        .unwrap();
+        for tx in txs {
+            conn.execute(
+                "INSERT INTO transactions VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![
+                    tx.tx_id,
+                    serde_json::to_string(&tx.tx_type)
+                        .unwrap()
+                        .trim_matches('"')
+                        .to_string(),
+                    tx.from.0,
+                    tx.to.0,
+                    tx.amount as i64,
+                    serde_json::to_string(&tx.metadata).unwrap(),
+                    tx.timestamp,
+                    tx.signature,
+                    tx.pubkey,
+                ],
+            )
+            .unwrap();
+        }
+    }
+
+    fn legacy_transfer_tx(kp: &KeyPair, to: &str, amount: MicroGitGold) -> Transaction {
+        let mut tx = transfer_tx(kp, 0, to, amount);
+        tx.version = crate::transaction::LEGACY_TX_VERSION;
+        tx.signature = hex::encode(kp.sign(&tx.signable_bytes()));
+        tx
+    }
+
+    #[test]
+    fn test_legacy_ledger_migrates() {
+        let path =
+            std::env::temp_dir().join(format!("gitgold-ledger-legacy-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let alice_kp = KeyPair::generate();
+        let alice_addr = alice_kp.address();
+        let mut mint = mint_tx(&alice_addr.0, 1_000_000);
+        mint.version = crate::transaction::LEGACY_TX_VERSION;
+        write_legacy_db(&path, &[mint, legacy_transfer_tx(&alice_kp, "bob", 400_000)]);
+
+        let mut ledger = Ledger::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(ledger.balance(&alice_addr), 600_000);
+        assert_eq!(ledger.balance(&Address::new("bob")), 400_000);
+
+        // New appends must use the current encoding.
+        assert!(matches!(
+            ledger.append(legacy_transfer_tx(&alice_kp, "bob", 1)),
+            Err(LedgerError::InvalidTransaction(_))
+        ));
+        ledger
+            .append(transfer_tx(&alice_kp, 0, "bob", 100_000))
+            .unwrap();
+        drop(ledger);
+
+        // Mixed legacy and current rows replay together.
+        let ledger = Ledger::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(ledger.balance(&alice_addr), 500_000);
+        assert_eq!(ledger.tx_count(), 3);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_legacy_row_fails_replay() {
+        let path =
+            std::env::temp_dir().join(format!("gitgold-ledger-tampered-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let alice_kp = KeyPair::generate();
+        let mut mint = mint_tx(&alice_kp.address().0, 1_000_000);
+        mint.version = crate::transaction::LEGACY_TX_VERSION;
+        let mut transfer = legacy_transfer_tx(&alice_kp, "bob", 400_000);
+        transfer.amount = 900_000;
+        write_legacy_db(&path, &[mint, transfer]);
+
+        assert!(matches!(
+            Ledger::open(path.to_str().unwrap()),
+            Err(LedgerError::InvalidSignature)
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// Fault injection for the store. Temporary SQLite triggers make the
+    /// next writes fail at a chosen point, with no hooks in `Ledger` itself.
+    mod faults {
+        use rusqlite::Connection;
+
+        #[derive(Debug, Clone, Copy)]
+        pub enum Fault {
+            /// The INSERT itself is aborted.
+            Insert,
+            /// The INSERT succeeds but COMMIT fails on a deferred
+            /// foreign-key violation.
+            Commit,
+        }
+
+        pub fn inject(conn: &Connection, fault: Fault) {
+            clear(conn);
+            let sql = match fault {
+                Fault::Insert => {
+                    "CREATE TEMP TRIGGER fault_insert BEFORE INSERT ON main.transactions
+                     BEGIN SELECT RAISE(ABORT, 'injected insert fault'); END;"
+                }
+                Fault::Commit => {
+                    "PRAGMA foreign_keys = ON;
+                     CREATE TEMP TABLE fault_parent (id INTEGER PRIMARY KEY);
+                     CREATE TEMP TABLE fault_child (
+                         parent INTEGER REFERENCES fault_parent (id) DEFERRABLE INITIALLY DEFERRED
+                     );
+                     CREATE TEMP TRIGGER fault_commit AFTER INSERT ON main.transactions
+                     BEGIN INSERT INTO fault_child VALUES (1); END;"
+                }
+            };
+            conn.execute_batch(sql).unwrap();
+        }
+
+        pub fn clear(conn: &Connection) {
+            conn.execute_batch(
+                "DROP TRIGGER IF EXISTS temp.fault_insert;
+                 DROP TRIGGER IF EXISTS temp.fault_commit;
+                 DROP TABLE IF EXISTS temp.fault_child;
+                 DROP TABLE IF EXISTS temp.fault_parent;",
+            )
+            .unwrap();
+        }
+    }
+
+    /// Assert the in-memory state equals a fresh replay of the stored rows.
+    fn assert_in_sync(ledger: &Ledger, senders: &[Address]) {
+        let rows = Ledger::load_all_txs(&ledger.conn).unwrap();
+        let mut replica = Ledger::in_memory().unwrap();
+        for tx in rows.iter().cloned() {
+            replica.append(tx).unwrap();
+        }
+        assert_eq!(ledger.tx_count(), rows.len());
+        assert_eq!(ledger.log.root(), replica.log.root());
+        assert_eq!(ledger.balances().all_balances(), replica.balances().all_balances());
+        assert_eq!(ledger.supply().total_minted(), replica.supply().total_minted());
+        assert_eq!(ledger.supply().total_burned(), replica.supply().total_burned());
+        for addr in senders {
+            assert_eq!(ledger.next_nonce(addr), replica.next_nonce(addr));
+        }
+    }
+
+    #[test]
+    fn test_faults_leave_state_unchanged() {
+        use faults::Fault;
+
+        let mut ledger = Ledger::in_memory().unwrap();
+        let kps: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+        let senders: Vec<Address> = kps.iter().map(KeyPair::address).collect();
+        for addr in &senders {
+            ledger.append(mint_tx(&addr.0, 10_000)).unwrap();
+        }
+
+        let mut nonces = [0u64; 3];
+        for step in 0..16 {
+            let who = step % 3;
+            let fault = match step % 4 {
+                1 => Some(Fault::Insert),
+                3 => Some(Fault::Commit),
+                _ => None,
+            };
+            let tx = if step % 6 == 5 {
+                mint_tx(&senders[who].0, 500)
+            } else {
+                let mut tx = transfer_tx(&kps[who], nonces[who], "sink", 100 + step as u64);
+                if step % 8 == 7 {
+                    tx.tx_type = TransactionType::Burn;
+                    tx.to = Address::system();
+                    tx.signature = hex::encode(kps[who].sign(&tx.signable_bytes()));
+                }
+                tx
+            };
+
+            if let Some(fault) = fault {
+                faults::inject(&ledger.conn, fault);
+                assert!(matches!(
+                    ledger.append(tx.clone()),
+                    Err(LedgerError::Database(_))
+                ));
+                assert_in_sync(&ledger, &senders);
+                faults::clear(&ledger.conn);
+            }
+
+            // The same transaction goes through once the fault is gone.
+            ledger.append(tx.clone()).unwrap();
+            if tx.from != Address::system() {
+                nonces[who] += 1;
+            }
+            assert_in_sync(&ledger, &senders);
+        }
+        assert_eq!(ledger.tx_count(), 3 + 16);
+    }
+
+    #[test]
+    fn test_fault_during_batch_stops_cleanly() {
+        let mut ledger = Ledger::in_memory().unwrap();
+        let alice_kp = KeyPair::generate();
+        let alice_addr = alice_kp.address();
+        ledger.append(mint_tx(&alice_addr.0, 1_000)).unwrap();
+        ledger
+            .append(transfer_tx(&alice_kp, 0, "bob", 100))
+            .unwrap();
+
+        faults::inject(&ledger.conn, faults::Fault::Commit);
+        let result = ledger.append_batch(vec![
+            transfer_tx(&alice_kp, 1, "bob", 100),
+            transfer_tx(&alice_kp, 2, "bob", 100),
+        ]);
+        assert!(matches!(result, Err(LedgerError::Database(_))));
+        faults::clear(&ledger.conn);
+
+        assert_in_sync(&ledger, std::slice::from_ref(&alice_addr));
+        assert_eq!(ledger.balance(&alice_addr), 900);
+        assert_eq!(ledger.next_nonce(&alice_addr), 1);
+    }
+
+    #[test]
+    fn test_sealed_block_proves_inclusion() {
+        let producer = Wallet::new();
+        let mut ledger = Ledger::in_memory_with_producers(&[producer.public_key()]).unwrap();
+        let alice_kp = KeyPair::generate();
+        let txs = [
+            mint_tx(&alice_kp.address().0, 1_000),
+            transfer_tx(&alice_kp, 0, "bob", 100),
+            transfer_tx(&alice_kp, 1, "carol", 200),
+        ];
+        for tx in txs.iter().cloned() {
+            ledger.append(tx).unwrap();
+        }
+        assert_eq!(ledger.pending_count(), 3);
+
+        let header = ledger.seal_block(&producer).unwrap();
+        assert_eq!(header.height, 0);
+        assert_eq!(header.prev_hash, GENESIS_PREV_HASH);
+        assert_eq!(header.state_root, ledger.balances().state_root());
+        assert_eq!(ledger.pending_count(), 0);
+        assert_eq!(ledger.head(), Some(&header));
+
+        // A light client needs only the header, the transaction and the proof.
+        for (i, tx) in txs.iter().enumerate() {
+            let (proven, proof) = ledger.inclusion_proof(&tx.tx_id).unwrap();
+            assert_eq!(proven, header);
+            assert!(header.verify_inclusion(tx, &proof));
+            assert!(!header.verify_inclusion(&txs[(i + 1) % txs.len()], &proof));
+        }
+        let alice_proof = ledger.balances().balance_proof(&alice_kp.address());
+        assert!(header.verify_balance(&alice_kp.address(), 700, &alice_proof));
+        assert!(!header.verify_balance(&alice_kp.address(), 1_000, &alice_proof));
+
+        let pending = transfer_tx(&alice_kp, 2, "dave", 1);
+        ledger.append(pending.clone()).unwrap();
+        assert!(matches!(
+            ledger.inclusion_proof(&pending.tx_id),
+            Err(LedgerError::NotInBlock(_))
+        ));
+        assert!(matches!(
+            ledger.block_header(1),
+            Err(LedgerError::BlockNotFound(1))
+        ));
+    }
+
+    #[test]
+    fn test_block_chain_survives_reopen() {
+        let path =
+            std::env::temp_dir().join(format!("gitgold-ledger-blocks-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let producer = Wallet::new();
+        let alice_kp = KeyPair::generate();
+
+        let mut ledger =
+            Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()]).unwrap();
+        ledger.append(mint_tx(&alice_kp.address().0, 1_000)).unwrap();
+        ledger.seal_block(&producer).unwrap();
+        ledger.seal_block(&producer).unwrap(); // empty
+        ledger.append(transfer_tx(&alice_kp, 0, "bob", 300)).unwrap();
+        ledger.append(transfer_tx(&alice_kp, 1, "bob", 300)).unwrap();
+        let head = ledger.seal_block(&producer).unwrap();
+        ledger.append(transfer_tx(&alice_kp, 2, "bob", 1)).unwrap();
+        let block = ledger.block(2).unwrap();
+        drop(ledger);
+
+        let ledger =
+            Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()]).unwrap();
+        assert_eq!(ledger.head(), Some(&head));
+        assert_eq!(head.prev_hash, ledger.block_header(1).unwrap().hash());
+        assert_eq!(ledger.pending_count(), 1);
+        let hashes = |block: &Block| block.txs.iter().map(Transaction::hash).collect::<Vec<_>>();
+        assert_eq!(hashes(&ledger.block(2).unwrap()), hashes(&block));
+        assert!(ledger.block(1).unwrap().txs.is_empty());
+        assert_eq!(ledger.balance(&Address::new("bob")), 601);
+        drop(ledger);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_block_fails_replay() {
+        let path =
+            std::env::temp_dir().join(format!("gitgold-ledger-bad-block-{}.db", std::process::id()));
+        let producer = Wallet::new();
+        let alice_kp = KeyPair::generate();
+
+        let tampering = [
+            // Rewritten state root, with a re-signed header.
+            "UPDATE blocks SET state_root = '00' || substr(state_root, 3) WHERE height = 1",
+            // Header field changed without re-signing.
+            "UPDATE blocks SET timestamp = timestamp + 1 WHERE height = 0",
+            // Transactions reordered within a block.
+            "UPDATE block_txs SET position = position + 10 WHERE height = 1;
+             UPDATE block_txs SET position = 11 - position WHERE height = 1",
+            // Transaction dropped from a block.
+            "DELETE FROM block_txs WHERE height = 1 AND position = 1",
+        ];
+        for (case, sql) in tampering.into_iter().enumerate() {
+            let _ = std::fs::remove_file(&path);
+            let mut ledger =
+                Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()])
+                    .unwrap();
+            ledger.append(mint_tx(&alice_kp.address().0, 1_000)).unwrap();
+            ledger.seal_block(&producer).unwrap();
+            ledger.append(mint_tx("bob", 5)).unwrap();
+            ledger.append(mint_tx("carol", 7)).unwrap();
+            ledger.seal_block(&producer).unwrap();
+
+            ledger.conn.execute_batch(sql).unwrap();
+            if case == 0 {
+                let mut header = ledger.block_header(1).unwrap();
+                header = BlockHeader::sign(
+                    header.height,
+                    header.prev_hash,
+                    header.tx_root,
+                    header.state_root,
+                    header.timestamp,
+                    &producer,
+                );
+                ledger
+                    .conn
+                    .execute(
+                        "UPDATE blocks SET signature = ?1 WHERE height = 1",
+                        [&header.signature],
+                    )
+                    .unwrap();
+            }
+            drop(ledger);
+
+            assert!(
+                matches!(
+                    Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()]),
+                    Err(LedgerError::InvalidBlock(_))
+                ),
+                "case {case}"
+            );
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_import_block_follows_producer() {
+        let producer = Wallet::new();
+        let alice_kp = KeyPair::generate();
+        let mut ledger = Ledger::in_memory_with_producers(&[producer.public_key()]).unwrap();
+        ledger.append(mint_tx(&alice_kp.address().0, 1_000)).unwrap();
+        ledger.seal_block(&producer).unwrap();
+        ledger.append(transfer_tx(&alice_kp, 0, "bob", 250)).unwrap();
+        ledger.append(transfer_tx(&alice_kp, 1, "carol", 50)).unwrap();
+        ledger.seal_block(&producer).unwrap();
+
+        let mut follower = Ledger::in_memory_with_producers(&[producer.public_key()]).unwrap();
+        follower.import_block(ledger.block(0).unwrap()).unwrap();
+
+        // Rejected blocks leave the follower untouched.
+        let good = ledger.block(1).unwrap();
+        let mut forged = good.clone();
+        forged.txs[0].amount = 900;
+        let mut wrong_state = good.clone();
+        wrong_state.header = BlockHeader::sign(
+            1,
+            good.header.prev_hash,
+            good.header.tx_root,
+            [7u8; 32],
+            good.header.timestamp,
+            &producer,
+        );
+        let mut reordered = good.clone();
+        reordered.txs.swap(0, 1);
+        for bad in [forged, wrong_state, reordered, ledger.block(0).unwrap()] {
+            assert!(follower.import_block(bad).is_err());
+            assert_eq!(follower.tx_count(), 1);
+            assert_eq!(follower.head(), ledger.block_header(0).ok().as_ref());
+            assert_eq!(follower.next_nonce(&alice_kp.address()), 0);
+        }
+
+        follower.import_block(good).unwrap();
+        assert_eq!(follower.head(), ledger.head());
+        assert_eq!(
+            follower.balances().all_balances(),
+            ledger.balances().all_balances()
+        );
+        assert_eq!(follower.next_nonce(&alice_kp.address()), 2);
+
+        // A ledger with unsealed transactions cannot import.
+        follower.append(mint_tx("dave", 1)).unwrap();
+        ledger.seal_block(&producer).unwrap();
+        assert!(matches!(
+            follower.import_block(ledger.block(2).unwrap()),
+            Err(LedgerError::InvalidBlock(_))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_producer_rejected() {
+        let producer = Wallet::new();
+        let rogue = Wallet::new();
+        let mut follower = Ledger::in_memory_with_producers(&[producer.public_key()]).unwrap();
+
+        // A block any wallet can sign validly is still refused.
+        let mut rogue_ledger = Ledger::in_memory_with_producers(&[rogue.public_key()]).unwrap();
+        rogue_ledger.append(mint_tx("mallory", 1_000_000)).unwrap();
+        rogue_ledger.seal_block(&rogue).unwrap();
+        let block = rogue_ledger.block(0).unwrap();
+        assert!(block.header.verify_signature());
+        assert!(matches!(
+            follower.import_block(block),
+            Err(LedgerError::InvalidBlock(_))
+        ));
+        assert_eq!(follower.tx_count(), 0);
+        assert_eq!(follower.head(), None);
+
+        // Nor can this ledger seal with a wallet it doesn't trust.
+        follower.append(mint_tx("alice", 5)).unwrap();
+        assert!(matches!(
+            follower.seal_block(&rogue),
+            Err(LedgerError::InvalidBlock(_))
+        ));
+        assert_eq!(follower.pending_count(), 1);
+        follower.seal_block(&producer).unwrap();
+
+        // Stored blocks are checked against the producers given on open.
+        let path = std::env::temp_dir().join(format!(
+            "gitgold-ledger-producers-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_str().unwrap();
+        let mut ledger = Ledger::open_with_producers(path, &[producer.public_key()]).unwrap();
+        ledger.append(mint_tx("alice", 5)).unwrap();
+        ledger.seal_block(&producer).unwrap();
+        drop(ledger);
+        assert!(matches!(
+            Ledger::open_with_producers(path, &[rogue.public_key()]),
+            Err(LedgerError::InvalidBlock(_))
+        ));
+        assert!(matches!(
+            Ledger::open(path),
+            Err(LedgerError::InvalidBlock(_))
+        ));
+        assert!(
+            Ledger::open_with_producers(path, &[rogue.public_key(), producer.public_key()]).is_ok()
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_open_without_producers_needs_unsealed_ledger() {
+        let path = std::env::temp_dir().join(format!(
+            "gitgold-ledger-no-producers-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let path = path.to_str().unwrap();
+        let producer = Wallet::new();
+
+        // Pending transactions alone open fine.
+        let mut ledger = Ledger::open(path).unwrap();
+        ledger.append(mint_tx("alice", 5)).unwrap();
+        drop(ledger);
+        let ledger = Ledger::open(path).unwrap();
+        assert_eq!(ledger.pending_count(), 1);
+        drop(ledger);
+
+        // Once a block is sealed, only its producers can open the ledger.
+        let mut ledger = Ledger::open_with_producers(path, &[producer.public_key()]).unwrap();
+        ledger.seal_block(&producer).unwrap();
+        drop(ledger);
+        assert!(matches!(
+            Ledger::open(path),
+            Err(LedgerError::InvalidBlock(_))
+        ));
+        let ledger = Ledger::open_with_producers(path, &[producer.public_key()]).unwrap();
+        assert_eq!(ledger.head().map(|h| h.height), Some(0));
+        drop(ledger);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// Balances, nonces, supply and seen ids must match between two ledgers.
+    fn assert_same_state(a: &Ledger, b: &Ledger) {
+        assert_eq!(a.balances().state_root(), b.balances().state_root());
+        assert_eq!(a.balances().all_nonces(), b.balances().all_nonces());
+        assert_eq!(a.supply().total_minted(), b.supply().total_minted());
+        assert_eq!(a.supply().total_burned(), b.supply().total_burned());
+        assert_eq!(a.tx_count(), b.tx_count());
+        assert_eq!(a.log.root(), b.log.root());
+        assert_eq!(a.pending.len(), b.pending.len());
+        assert_eq!(a.pending.root(), b.pending.root());
+        assert_eq!(a.sealed_rowid, b.sealed_rowid);
+        assert_eq!(a.head(), b.head());
+    }
+
+    #[test]
+    fn test_replay_spans_chunks() {
+        let path =
+            std::env::temp_dir().join(format!("gitgold-ledger-chunks-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let producer = Wallet::new();
+        let open = || {
+            Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()]).unwrap()
+        };
+
+        // One block ending just before a chunk boundary, one straddling
+        // it, and a pending tail in the next chunk.
+        let mut ledger = open();
+        ledger.set_snapshot_interval(0);
+        let mut minted = 0;
+        for end in [REPLAY_CHUNK - 1, REPLAY_CHUNK + 2, REPLAY_CHUNK + 5] {
+            let txs = (minted..end).map(|i| mint_tx(&format!("acct-{}", i % 7), 1));
+            ledger.append_batch(txs.collect()).unwrap();
+            minted = end;
+            if end < REPLAY_CHUNK + 5 {
+                ledger.seal_block(&producer).unwrap();
+            }
+        }
+        assert_eq!(ledger.pending_count(), 3);
+
+        let reopened = open();
+        assert_same_state(&ledger, &reopened);
+        assert_eq!(reopened.head().unwrap().height, 1);
+        drop(reopened);
+
+        // Sealing the reopened tail picks up exactly the pending rows.
+        drop(ledger);
+        let mut ledger = open();
+        ledger.seal_block(&producer).unwrap();
+        assert_eq!(ledger.block(2).unwrap().txs.len(), 3);
+        assert_same_state(&ledger, &open());
+        drop(ledger);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_open_resumes_from_snapshot() {
+        let path =
+            std::env::temp_dir().join(format!("gitgold-ledger-snapshot-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let producer = Wallet::new();
+        let alice_kp = KeyPair::generate();
+        let alice = alice_kp.address();
+
+        let mut ledger =
+            Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()]).unwrap();
+        ledger.set_snapshot_interval(3);
+        ledger.append(mint_tx(&alice.0, 1_000)).unwrap();
+        ledger.append(transfer_tx(&alice_kp, 0, "bob", 100)).unwrap();
+        ledger.seal_block(&producer).unwrap(); // 2 txs: not due yet
+        ledger.append(transfer_tx(&alice_kp, 1, "bob", 100)).unwrap();
+        ledger.seal_block(&producer).unwrap(); // snapshot at 3
+        let burn = {
+            let mut tx = transfer_tx(&alice_kp, 2, "", 50);
+            tx.tx_type = TransactionType::Burn;
+            tx.to = Address::system();
+            tx.signature = hex::encode(alice_kp.sign(&tx.signable_bytes()));
+            tx
+        };
+        ledger.append(burn).unwrap();
+        ledger.append(transfer_tx(&alice_kp, 3, "carol", 10)).unwrap();
+        assert_eq!(ledger.last_snapshot, 3);
+        assert_eq!(ledger.log.root(), ledger.merkle_tree().unwrap().root());
+        drop(ledger);
+
+        let mut resumed =
+            Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()]).unwrap();
+        assert_eq!(resumed.last_snapshot, 3);
+
+        // Same state as a full replay.
+        resumed.conn.execute_batch("DELETE FROM snapshots").unwrap();
+        let full =
+            Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()]).unwrap();
+        assert_eq!(full.last_snapshot, 0);
+        assert_same_state(&resumed, &full);
+        assert_eq!(resumed.balance(&alice), 740);
+        assert_eq!(resumed.supply().total_burned(), 50);
+
+        // Snapshotted ids still count as seen, and nonces carry on.
+        let replayed = Ledger::load_all_txs(&resumed.conn).unwrap();
+        assert!(matches!(
+            resumed.append(replayed[1].clone()),
+            Err(LedgerError::DuplicateTransaction(_))
+        ));
+        resumed.append(transfer_tx(&alice_kp, 4, "bob", 1)).unwrap();
+        drop(resumed);
+        drop(full);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bad_snapshots_are_skipped() {
+        let path = std::env::temp_dir().join(format!(
+            "gitgold-ledger-bad-snapshot-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let producer = Wallet::new();
+        let open = || {
+            Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()]).unwrap()
+        };
+
+        let mut ledger = open();
+        for (i, who) in ["alice", "bob", "carol", "dave", "erin"].iter().enumerate() {
+            ledger.append(mint_tx(who, 100 + i as u64)).unwrap();
+            ledger.seal_block(&producer).unwrap();
+            ledger.snapshot().unwrap();
+        }
+        let expected = ledger.balances().state_root();
+        let count = |ledger: &Ledger| -> i64 {
+            ledger
+                .conn
+                .query_row("SELECT COUNT(*) FROM snapshots", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count(&ledger), SNAPSHOTS_KEPT);
+        drop(ledger);
+        assert_eq!(open().last_snapshot, 5);
+
+        // Each damaged snapshot is skipped for the next older one, and the
+        // state is the same whichever is used.
+        let damage = [
+            (5, "UPDATE snapshots SET checksum = '00' || substr(checksum, 3) WHERE tx_count = 5"),
+            (4, "UPDATE snapshots SET data = substr(data, 2) WHERE tx_count = 4"),
+        ];
+        for (tx_count, sql) in damage {
+            let ledger = open();
+            ledger.conn.execute_batch(sql).unwrap();
+            drop(ledger);
+            let ledger = open();
+            assert_eq!(ledger.last_snapshot as i64, tx_count - 1);
+            assert_eq!(ledger.balances().state_root(), expected);
+            assert_eq!(ledger.tx_count(), 5);
+        }
+
+        // A snapshot from a block signed by a producer we no longer trust
+        // is skipped too; full replay then fails on that block.
+        let other = Wallet::new();
+        assert!(
+            Ledger::open_with_producers(path.to_str().unwrap(), &[other.public_key()]).is_err()
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_snapshots_only_at_block_boundaries() {
+        let producer = Wallet::new();
+        let mut ledger = Ledger::in_memory_with_producers(&[producer.public_key()]).unwrap();
+        assert!(matches!(
+            ledger.snapshot(),
+            Err(LedgerError::InvalidBlock(_))
+        ));
+        ledger.append(mint_tx("alice", 100)).unwrap();
+        assert!(matches!(
+            ledger.snapshot(),
+            Err(LedgerError::InvalidBlock(_))
+        ));
+        ledger.seal_block(&producer).unwrap();
+        ledger.snapshot().unwrap();
+        assert_eq!(ledger.last_snapshot, 1);
+    }
+
+    #[test]
+    fn test_forged_snapshot_rejected() {
+        let path = std::env::temp_dir().join(format!(
+            "gitgold-ledger-forged-snapshot-{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let producer = Wallet::new();
+        let open = || {
+            Ledger::open_with_producers(path.to_str().unwrap(), &[producer.public_key()]).unwrap()
+        };
+        let alice_kp = KeyPair::generate();
+        let alice = alice_kp.address();
+
+        let mut ledger = open();
+        ledger.append(mint_tx(&alice.0, 1_000)).unwrap();
+        ledger.seal_block(&producer).unwrap();
+        ledger.snapshot().unwrap();
+        ledger
+            .append(transfer_tx(&alice_kp, 0, "bob", 100))
+            .unwrap();
+        ledger.seal_block(&producer).unwrap();
+        ledger.snapshot().unwrap();
+        drop(ledger);
+        let genuine = open();
+        assert_eq!(genuine.last_snapshot, 2);
+
+        // Rewrite the newest snapshot so it is internally consistent (its
+        // state hashes to its own state root, and the checksum matches)
+        // but isn't the state block 1 signed.
+        let (data, checksum): (Vec<u8>, String) = genuine
+            .conn
+            .query_row(
+                "SELECT data, checksum FROM snapshots WHERE tx_count = 2",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        let snapshot = Snapshot::decode(&data, &checksum).unwrap();
+        let forgeries: [fn(&mut Snapshot); 3] = [
+            |s| {
+                s.balances.push((Address::new("mallory"), 1_000_000));
+                s.total_minted += 1_000_000;
+            },
+            |s| s.nonces.clear(),
+            |s| s.total_burned += 1,
+        ];
+        for forge in forgeries {
+            let mut forged = snapshot.clone();
+            forge(&mut forged);
+            let log = forged.log().unwrap();
+            let mut tracker =
+                BalanceTracker::from_parts(forged.balances.clone(), forged.nonces.clone());
+            tracker.set_supply(forged.total_minted, forged.total_burned);
+            tracker.set_log(log.len(), log.root());
+            forged.state_root = tracker.state_root();
+            assert!(forged.balance_tracker().is_some());
+
+            let (data, checksum) = forged.encode().unwrap();
+            genuine
+                .conn
+                .execute(
+                    "UPDATE snapshots SET data = ?1, checksum = ?2 WHERE tx_count = 2",
+                    rusqlite::params![data, checksum],
+                )
+                .unwrap();
+            let ledger = open();
+            assert_eq!(ledger.last_snapshot, 1);
+            assert_eq!(ledger.balance(&Address::new("mallory")), 0);
+            assert_same_state(&ledger, &genuine);
+        }
+        drop(genuine);
+        std::fs::remove_file(&path).unwrap();
+    }
 }
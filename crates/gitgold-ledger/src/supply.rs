@@ -54,6 +54,12 @@ impl SupplyTracker {
         self.total_burned
     }
 
+    /// Restore minted and burned totals, e.g. from a snapshot.
+    pub fn restore_totals(&mut self, total_minted: MicroGitGold, total_burned: MicroGitGold) {
+        self.total_minted = total_minted;
+        self.total_burned = total_burned;
+    }
+
     /// Compute the emission allowance for a given year (0-indexed).
     /// Returns amount in micro-GC that can be emitted that year.
     pub fn annual_emission(&self, year: u32) -> MicroGitGold {
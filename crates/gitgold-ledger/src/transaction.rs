@@ -1,7 +1,22 @@
+use gitgold_core::error::LedgerError;
 use gitgold_core::types::{Address, Hash256, MicroGitGold, TransactionType};
 use gitgold_crypto::hash::sha256;
+use gitgold_crypto::keys::PublicKey;
+use gitgold_crypto::multisig::MultisigPolicy;
+use gitgold_crypto::wallet::Wallet;
 use serde::{Deserialize, Serialize};
 
+/// Current transaction version: canonical signing encoding.
+pub const TX_VERSION: u8 = 1;
+
+/// Version of transactions signed before the canonical encoding existed.
+/// They still verify, but new ones are no longer accepted.
+pub const LEGACY_TX_VERSION: u8 = 0;
+
+/// Domain separator prefixed to every canonical encoding, so transaction
+/// signatures can't be passed off as signatures over other messages.
+const TX_DOMAIN: &[u8] = b"gitgold/tx";
+
 /// A transaction on the GitGold ledger.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
@@ -19,25 +34,242 @@ pub struct Transaction {
     pub metadata: serde_json::Value,
     /// Unix timestamp in seconds.
     pub timestamp: i64,
-    /// Ed25519 signature over signable_bytes (hex-encoded).
+    /// Per-sender sequence number: the sender's first transaction uses 0,
+    /// each later one the next integer. Unused by system transactions.
+    #[serde(default)]
+    pub nonce: u64,
+    /// Ed25519 signature over signable_bytes (hex-encoded). Empty for
+    /// multisig senders.
     pub signature: String,
-    /// Ed25519 public key of the sender (hex-encoded).
+    /// Ed25519 public key of the sender (hex-encoded). Empty for multisig
+    /// senders.
     pub pubkey: String,
+    /// Signing encoding version ([`TX_VERSION`]). Missing in transactions
+    /// serialized before versioning, which are [`LEGACY_TX_VERSION`].
+    #[serde(default)]
+    pub version: u8,
+    /// Policy and co-signatures when `from` is a multisig address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<MultisigAuth>,
+}
+
+/// Authorization for a transaction sent from a [`MultisigPolicy`] address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigAuth {
+    /// Number of signatures required.
+    pub threshold: u8,
+    /// Member public keys (hex-encoded), in signer-index order.
+    pub pubkeys: Vec<String>,
+    /// Collected signatures, sorted by signer.
+    pub signatures: Vec<CosignerSignature>,
+}
+
+/// One member's signature over a multisig transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CosignerSignature {
+    /// Index of the signing key in [`MultisigAuth::pubkeys`].
+    pub signer: u8,
+    /// Ed25519 signature over signable_bytes (hex-encoded).
+    pub signature: String,
+}
+
+impl MultisigAuth {
+    /// Unsigned authorization for `policy`.
+    pub fn new(policy: &MultisigPolicy) -> Self {
+        Self {
+            threshold: policy.threshold() as u8,
+            pubkeys: policy
+                .pubkeys()
+                .iter()
+                .map(|pk| hex::encode(&pk.bytes))
+                .collect(),
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Rebuild the policy. Keys must be listed in canonical order so that
+    /// signer indices mean the same thing to every verifier.
+    pub fn policy(&self) -> Result<MultisigPolicy, LedgerError> {
+        let pubkeys = self
+            .pubkeys
+            .iter()
+            .map(|hex_key| {
+                hex::decode(hex_key)
+                    .map(|bytes| PublicKey { bytes })
+                    .map_err(|_| {
+                        LedgerError::InvalidTransaction("Invalid hex in multisig key".to_string())
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let policy = MultisigPolicy::new(self.threshold as usize, &pubkeys)
+            .map_err(|e| LedgerError::InvalidTransaction(e.to_string()))?;
+        if policy
+            .pubkeys()
+            .iter()
+            .map(|pk| &pk.bytes)
+            .ne(pubkeys.iter().map(|pk| &pk.bytes))
+        {
+            return Err(LedgerError::InvalidTransaction(
+                "multisig keys not in canonical order".to_string(),
+            ));
+        }
+        Ok(policy)
+    }
+
+    /// Decoded `(signer, signature)` pairs for [`MultisigPolicy::verify`].
+    pub fn decoded_signatures(&self) -> Result<Vec<(u8, Vec<u8>)>, LedgerError> {
+        self.signatures
+            .iter()
+            .map(|s| {
+                hex::decode(&s.signature)
+                    .map(|sig| (s.signer, sig))
+                    .map_err(|_| {
+                        LedgerError::InvalidTransaction("Invalid hex in signature".to_string())
+                    })
+            })
+            .collect()
+    }
 }
 
 impl Transaction {
     /// Compute the bytes that should be signed.
+    ///
+    /// For [`TX_VERSION`] this is the canonical encoding (integers
+    /// big-endian, strings u32-length-prefixed):
+    ///
+    /// ```text
+    /// "gitgold/tx" | version u8 | tx_id | tx_type u8 | from | to | amount u64
+    /// | timestamp i64 | nonce u64 | metadata (canonical JSON) | pubkey
+    /// [| threshold u8 | key count u32 | multisig keys]
+    /// ```
+    ///
+    /// The bracketed policy is present only for multisig senders. Every
+    /// field except the signatures is covered and none can bleed into its
+    /// neighbour.
+    /// Legacy transactions keep their original unseparated encoding, which
+    /// omits `tx_type` and `nonce`, so existing signatures and hashes stay
+    /// valid.
     pub fn signable_bytes(&self) -> Vec<u8> {
+        if self.version == LEGACY_TX_VERSION {
+            return self.legacy_signable_bytes();
+        }
+        let mut out = Vec::with_capacity(256);
+        out.extend_from_slice(TX_DOMAIN);
+        out.push(self.version);
+        put_str(&mut out, &self.tx_id);
+        out.push(type_tag(&self.tx_type));
+        put_str(&mut out, &self.from.0);
+        put_str(&mut out, &self.to.0);
+        out.extend_from_slice(&self.amount.to_be_bytes());
+        out.extend_from_slice(&self.timestamp.to_be_bytes());
+        out.extend_from_slice(&self.nonce.to_be_bytes());
+        put_str(&mut out, &canonical_json(&self.metadata));
+        put_str(&mut out, &self.pubkey);
+        if let Some(auth) = &self.multisig {
+            out.push(auth.threshold);
+            out.extend_from_slice(&(auth.pubkeys.len() as u32).to_be_bytes());
+            for key in &auth.pubkeys {
+                put_str(&mut out, key);
+            }
+        }
+        out
+    }
+
+    /// Add (or replace) `wallet`'s signature on a multisig transaction.
+    /// Fails if the transaction is not multisig or the wallet is not a
+    /// member of its policy.
+    pub fn cosign(&mut self, wallet: &Wallet) -> Result<(), LedgerError> {
+        let message = self.signable_bytes();
+        let auth = self.multisig.as_mut().ok_or_else(|| {
+            LedgerError::InvalidTransaction("not a multisig transaction".to_string())
+        })?;
+        let signer = auth
+            .policy()?
+            .signer_index(&wallet.public_key())
+            .ok_or_else(|| {
+                LedgerError::InvalidTransaction("wallet is not a member of the policy".to_string())
+            })?;
+        let signature = hex::encode(wallet.sign(&message));
+        auth.signatures.retain(|s| s.signer != signer);
+        auth.signatures
+            .push(CosignerSignature { signer, signature });
+        auth.signatures.sort_by_key(|s| s.signer);
+        Ok(())
+    }
+
+    /// Compute the SHA-256 hash of this transaction.
+    pub fn hash(&self) -> Hash256 {
+        sha256(&self.signable_bytes())
+    }
+
+    /// Whether this transaction predates the canonical encoding.
+    pub fn is_legacy(&self) -> bool {
+        self.version == LEGACY_TX_VERSION
+    }
+
+    fn legacy_signable_bytes(&self) -> Vec<u8> {
         format!(
             "{}{}{}{}{}{}{}",
             self.tx_id, self.from, self.to, self.amount, self.timestamp, self.metadata, self.pubkey
         )
         .into_bytes()
     }
+}
 
-    /// Compute the SHA-256 hash of this transaction.
-    pub fn hash(&self) -> Hash256 {
-        sha256(&self.signable_bytes())
+/// Stable one-byte tag per transaction type. Never renumber.
+fn type_tag(tx_type: &TransactionType) -> u8 {
+    match tx_type {
+        TransactionType::PushFee => 1,
+        TransactionType::PullFee => 2,
+        TransactionType::StorageReward => 3,
+        TransactionType::ChallengeReward => 4,
+        TransactionType::BandwidthReward => 5,
+        TransactionType::Transfer => 6,
+        TransactionType::Burn => 7,
+        TransactionType::Mint => 8,
+    }
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+/// Compact JSON with object keys sorted at every level, independent of how
+/// `serde_json` happens to order maps in this build.
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
     }
 }
 
@@ -54,8 +286,11 @@ mod tests {
             amount: 1_000_000,
             metadata: serde_json::json!({}),
             timestamp: 1700000000,
+            nonce: 0,
             signature: String::new(),
             pubkey: String::new(),
+            version: TX_VERSION,
+            multisig: None,
         }
     }
 
@@ -80,4 +315,98 @@ mod tests {
         let b2 = tx.signable_bytes();
         assert_eq!(b1, b2);
     }
+
+    #[test]
+    fn test_tx_type_is_signed() {
+        let transfer = test_tx();
+        let mut push_fee = test_tx();
+        push_fee.tx_type = TransactionType::PushFee;
+        assert_ne!(transfer.signable_bytes(), push_fee.signable_bytes());
+        assert_ne!(transfer.hash(), push_fee.hash());
+    }
+
+    #[test]
+    fn test_nonce_is_signed() {
+        let first = test_tx();
+        let mut second = test_tx();
+        second.nonce = 1;
+        assert_ne!(first.signable_bytes(), second.signable_bytes());
+    }
+
+    #[test]
+    fn test_field_boundaries_unambiguous() {
+        // Legacy encoding: "ab" + "c" and "a" + "bc" concatenate identically.
+        let mut a = test_tx();
+        a.from = Address::new("ab");
+        a.to = Address::new("c");
+        let mut b = test_tx();
+        b.from = Address::new("a");
+        b.to = Address::new("bc");
+        assert_ne!(a.signable_bytes(), b.signable_bytes());
+
+        a.version = LEGACY_TX_VERSION;
+        b.version = LEGACY_TX_VERSION;
+        assert_eq!(a.signable_bytes(), b.signable_bytes());
+    }
+
+    #[test]
+    fn test_metadata_key_order_irrelevant() {
+        let mut a = test_tx();
+        a.metadata = serde_json::from_str(r#"{"b": 1, "a": {"y": [1, 2], "x": null}}"#).unwrap();
+        let mut b = test_tx();
+        b.metadata = serde_json::from_str(r#"{"a": {"x": null, "y": [1, 2]}, "b": 1}"#).unwrap();
+        assert_eq!(a.signable_bytes(), b.signable_bytes());
+        assert_eq!(
+            canonical_json(&a.metadata),
+            r#"{"a":{"x":null,"y":[1,2]},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn test_legacy_encoding_unchanged() {
+        let mut tx = test_tx();
+        tx.version = LEGACY_TX_VERSION;
+        assert_eq!(
+            tx.signable_bytes(),
+            b"tx-001aaabbb10000001700000000{}".to_vec()
+        );
+        assert!(tx.is_legacy());
+    }
+
+    #[test]
+    fn test_unversioned_json_is_legacy() {
+        let json = r#"{"tx_id":"t","tx_type":"Mint","from":"0","to":"a","amount":1,
+            "metadata":{},"timestamp":0,"signature":"","pubkey":""}"#;
+        let tx: Transaction = serde_json::from_str(json).unwrap();
+        assert_eq!(tx.version, LEGACY_TX_VERSION);
+        assert_eq!(tx.nonce, 0);
+        assert!(tx.multisig.is_none());
+    }
+
+    #[test]
+    fn test_cosign_collects_sorted_signatures() {
+        let wallets: Vec<Wallet> = (0..3).map(|_| Wallet::new()).collect();
+        let pks: Vec<_> = wallets.iter().map(Wallet::public_key).collect();
+        let policy = MultisigPolicy::new(2, &pks).unwrap();
+        let mut tx = test_tx();
+        tx.from = policy.address();
+        tx.multisig = Some(MultisigAuth::new(&policy));
+
+        tx.cosign(&wallets[2]).unwrap();
+        tx.cosign(&wallets[0]).unwrap();
+        tx.cosign(&wallets[0]).unwrap();
+        let auth = tx.multisig.as_ref().unwrap();
+        assert_eq!(auth.signatures.len(), 2);
+        assert!(auth.signatures[0].signer < auth.signatures[1].signer);
+        assert!(policy.verify(&tx.signable_bytes(), &auth.decoded_signatures().unwrap()));
+
+        assert!(matches!(
+            tx.cosign(&Wallet::new()),
+            Err(LedgerError::InvalidTransaction(_))
+        ));
+        assert!(matches!(
+            test_tx().cosign(&wallets[0]),
+            Err(LedgerError::InvalidTransaction(_))
+        ));
+    }
 }
@@ -1,5 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use gitgold_storage::chunk::{chunk_data, reassemble_chunks, DEFAULT_CHUNK_SIZE};
+use gitgold_storage::chunk::{
+    chunk_content_defined, chunk_data, reassemble_chunks, CdcParams, DEFAULT_CHUNK_SIZE,
+};
 
 fn bench_chunk_data(c: &mut Criterion) {
     let sizes: &[(usize, &str)] = &[
@@ -52,5 +54,71 @@ fn bench_roundtrip(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_chunk_data, bench_reassemble, bench_roundtrip,);
+fn bench_content_defined(c: &mut Criterion) {
+    // Varied bytes so the rolling hash finds real cut points.
+    let data: Vec<u8> = (0..10_000_000u64)
+        .map(|i| (i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 56) as u8)
+        .collect();
+    let params = CdcParams::default();
+
+    let mut group = c.benchmark_group("chunk_10MB");
+    group.bench_function("fixed", |bench| {
+        bench.iter(|| chunk_data(black_box(&data), DEFAULT_CHUNK_SIZE))
+    });
+    group.bench_function("content_defined", |bench| {
+        bench.iter(|| chunk_content_defined(black_box(&data), &params).unwrap())
+    });
+    group.finish();
+}
+
+#[cfg(feature = "parallel")]
+fn bench_encode_pipeline(c: &mut Criterion) {
+    use gitgold_core::types::RedundancyMode;
+    use gitgold_crypto::codec::codec_for;
+    use gitgold_storage::pipeline::{
+        decode_chunks, decode_chunks_par, encode_chunks, encode_chunks_par,
+    };
+
+    // 4 MB in 512 KB chunks, k=5 n=9 as in the default config.
+    let data = vec![0x5Au8; 4 * 1024 * 1024];
+    let chunks = chunk_data(&data, DEFAULT_CHUNK_SIZE);
+    let (k, n) = (5, 9);
+
+    let mut group = c.benchmark_group("encode_chunks_4MB_k5n9");
+    group.sample_size(10);
+    group.throughput(criterion::Throughput::Bytes(data.len() as u64));
+    for (mode, label) in [
+        (RedundancyMode::Confidential, "shamir"),
+        (RedundancyMode::Efficient, "reed_solomon"),
+        (RedundancyMode::Hybrid, "hybrid"),
+    ] {
+        let codec = codec_for(mode);
+        group.bench_function(BenchmarkId::new("encode_serial", label), |bench| {
+            bench.iter(|| encode_chunks(black_box(&chunks), codec, k, n).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("encode_parallel", label), |bench| {
+            bench.iter(|| encode_chunks_par(black_box(&chunks), codec, k, n).unwrap())
+        });
+        let encoded = encode_chunks(&chunks, codec, k, n).unwrap();
+        group.bench_function(BenchmarkId::new("decode_serial", label), |bench| {
+            bench.iter(|| decode_chunks(black_box(&encoded), codec, k).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("decode_parallel", label), |bench| {
+            bench.iter(|| decode_chunks_par(black_box(&encoded), codec, k).unwrap())
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "parallel"))]
+fn bench_encode_pipeline(_: &mut Criterion) {}
+
+criterion_group!(
+    benches,
+    bench_chunk_data,
+    bench_reassemble,
+    bench_roundtrip,
+    bench_content_defined,
+    bench_encode_pipeline,
+);
 criterion_main!(benches);
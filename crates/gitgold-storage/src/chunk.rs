@@ -1,3 +1,8 @@
+use gitgold_core::config::GitGoldConfig;
+use gitgold_core::error::StorageError;
+use gitgold_core::types::Hash256;
+use gitgold_crypto::hash::sha256;
+
 /// Default chunk size: 512 KB.
 pub const DEFAULT_CHUNK_SIZE: usize = 512 * 1024;
 
@@ -36,6 +41,139 @@ pub fn reassemble_chunks(mut chunks: Vec<(u32, Vec<u8>)>) -> Result<Vec<u8>, Str
     Ok(result)
 }
 
+/// Size bounds for content-defined chunking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CdcParams {
+    pub min_size: usize,
+    pub avg_size: usize,
+    pub max_size: usize,
+}
+
+impl CdcParams {
+    /// Bounds from the `cdc_*` fields of the config.
+    pub fn from_config(config: &GitGoldConfig) -> Self {
+        Self {
+            min_size: config.cdc_min_size,
+            avg_size: config.cdc_avg_size,
+            max_size: config.cdc_max_size,
+        }
+    }
+
+    /// Check `0 < min_size <= avg_size <= max_size` and `avg_size >= 2`
+    /// (the average picks the cut masks, which need at least one bit).
+    pub fn validate(&self) -> Result<(), StorageError> {
+        let Self {
+            min_size,
+            avg_size,
+            max_size,
+        } = *self;
+        if min_size == 0 || min_size > avg_size || avg_size > max_size {
+            return Err(StorageError::InvalidChunkParams(format!(
+                "CDC sizes must satisfy 0 < min <= avg <= max, got {min_size}/{avg_size}/{max_size}"
+            )));
+        }
+        if avg_size < 2 {
+            return Err(StorageError::InvalidChunkParams(format!(
+                "CDC average size must be at least 2, got {avg_size}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for CdcParams {
+    fn default() -> Self {
+        Self::from_config(&GitGoldConfig::default())
+    }
+}
+
+/// A content-defined chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentChunk {
+    /// SHA-256 of `data`. Identical content gets the same id across pushes.
+    pub id: Hash256,
+    /// Position of the chunk in the input, for `reassemble_chunks`.
+    pub index: u32,
+    pub data: Vec<u8>,
+}
+
+/// Gear table for the rolling hash: 256 fixed pseudo-random words
+/// (splitmix64), so cut points are identical on every node.
+const GEAR: [u64; 256] = build_gear();
+
+const fn build_gear() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0x6769_7467_6f6c_6463; // "gitgoldc"
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+/// Split data at content-defined boundaries (FastCDC).
+///
+/// A gear rolling hash picks cut points from the bytes themselves, so an
+/// insertion only changes the chunks around it and the rest keep their
+/// ids. Chunks are between `min_size` and `max_size` bytes (the last may be
+/// shorter); normalized chunking keeps most of them near `avg_size`.
+///
+/// Fails with `InvalidChunkParams` unless `params` pass
+/// [`CdcParams::validate`].
+pub fn chunk_content_defined(
+    data: &[u8],
+    params: &CdcParams,
+) -> Result<Vec<ContentChunk>, StorageError> {
+    params.validate()?;
+
+    // Stricter mask (one more bit) below the average, looser above it. The
+    // high bits of the gear hash depend on the most recent 64 bytes.
+    let bits = params.avg_size.ilog2();
+    let mask = |bits: u32| -> u64 {
+        let bits = bits.clamp(1, 63);
+        ((1u64 << bits) - 1) << (64 - bits)
+    };
+    let (mask_small, mask_large) = (mask(bits + 1), mask(bits - 1));
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < data.len() {
+        let len = cut_point(&data[start..], params, mask_small, mask_large);
+        let chunk = &data[start..start + len];
+        chunks.push(ContentChunk {
+            id: sha256(chunk),
+            index: chunks.len() as u32,
+            data: chunk.to_vec(),
+        });
+        start += len;
+    }
+    Ok(chunks)
+}
+
+/// Length of the next chunk at the start of `data`.
+fn cut_point(data: &[u8], params: &CdcParams, mask_small: u64, mask_large: u64) -> usize {
+    if data.len() <= params.min_size {
+        return data.len();
+    }
+    let end = data.len().min(params.max_size);
+    let normal = end.min(params.avg_size);
+
+    let mut hash = 0u64;
+    for (i, &byte) in data.iter().enumerate().take(end).skip(params.min_size) {
+        hash = (hash << 1).wrapping_add(GEAR[byte as usize]);
+        let mask = if i < normal { mask_small } else { mask_large };
+        if hash & mask == 0 {
+            return i + 1;
+        }
+    }
+    end
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,4 +235,107 @@ mod tests {
         let reassembled = reassemble_chunks(chunks).unwrap();
         assert!(reassembled.is_empty());
     }
+
+    /// Deterministic pseudo-random bytes (xorshift64).
+    fn noise(len: usize, seed: u64) -> Vec<u8> {
+        let mut x = seed;
+        (0..len)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                x as u8
+            })
+            .collect()
+    }
+
+    const SMALL: CdcParams = CdcParams {
+        min_size: 2 * 1024,
+        avg_size: 8 * 1024,
+        max_size: 32 * 1024,
+    };
+
+    #[test]
+    fn test_cdc_roundtrip_and_bounds() {
+        let data = noise(1_000_000, 1);
+        let chunks = chunk_content_defined(&data, &SMALL).unwrap();
+        let (last, body) = chunks.split_last().unwrap();
+        assert!(body
+            .iter()
+            .all(|c| c.data.len() >= SMALL.min_size && c.data.len() <= SMALL.max_size));
+        assert!(last.data.len() <= SMALL.max_size);
+
+        let avg = data.len() / chunks.len();
+        assert!(avg > SMALL.min_size && avg < SMALL.max_size, "avg {avg}");
+
+        let pairs = chunks.into_iter().map(|c| (c.index, c.data)).collect();
+        assert_eq!(reassemble_chunks(pairs).unwrap(), data);
+    }
+
+    #[test]
+    fn test_cdc_insert_preserves_most_chunks() {
+        let data = noise(500_000, 7);
+        let mut shifted = data.clone();
+        shifted.insert(100, 0xFF);
+
+        let before: std::collections::HashSet<Hash256> = chunk_content_defined(&data, &SMALL)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        let after = chunk_content_defined(&shifted, &SMALL).unwrap();
+        let reused = after.iter().filter(|c| before.contains(&c.id)).count();
+        // Only the chunk containing the insertion (and at most its
+        // neighbour) should change.
+        assert!(reused + 2 >= after.len(), "{reused} of {}", after.len());
+
+        // Fixed-size chunking shares nothing after the shift.
+        let fixed_before = chunk_data(&data, 8 * 1024);
+        let fixed_after = chunk_data(&shifted, 8 * 1024);
+        assert_ne!(fixed_before[1].1, fixed_after[1].1);
+    }
+
+    #[test]
+    fn test_cdc_ids_are_content_hashes() {
+        let data = noise(100_000, 3);
+        let chunks = chunk_content_defined(&data, &SMALL).unwrap();
+        assert_eq!(chunks, chunk_content_defined(&data, &SMALL).unwrap());
+        assert!(chunks.iter().all(|c| c.id == sha256(&c.data)));
+    }
+
+    #[test]
+    fn test_cdc_low_entropy_hits_max_size() {
+        let chunks = chunk_content_defined(&vec![0u8; 100_000], &SMALL).unwrap();
+        assert!(chunks[..chunks.len() - 1]
+            .iter()
+            .all(|c| c.data.len() == SMALL.max_size));
+        assert!(chunk_content_defined(b"", &SMALL).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cdc_rejects_bad_params() {
+        let data = noise(10_000, 5);
+        let bad = [(0, 8, 32), (16, 8, 32), (2, 64, 32), (1, 1, 32), (0, 0, 0)];
+        for (min_size, avg_size, max_size) in bad {
+            let params = CdcParams {
+                min_size,
+                avg_size,
+                max_size,
+            };
+            assert!(matches!(
+                chunk_content_defined(&data, &params),
+                Err(StorageError::InvalidChunkParams(_))
+            ));
+        }
+
+        // The smallest average still chunks.
+        let tiny = CdcParams {
+            min_size: 1,
+            avg_size: 2,
+            max_size: 4,
+        };
+        let chunks = chunk_content_defined(&data, &tiny).unwrap();
+        let pairs = chunks.into_iter().map(|c| (c.index, c.data)).collect();
+        assert_eq!(reassemble_chunks(pairs).unwrap(), data);
+    }
 }
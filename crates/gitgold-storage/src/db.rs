@@ -1,4 +1,5 @@
 use gitgold_core::error::StorageError;
+use gitgold_crypto::shamir::Share;
 use rusqlite::Connection;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -14,6 +15,10 @@ pub struct FragmentRecord {
     pub data_hash: String,
     pub stored_at: i64,
     pub last_challenged: Option<i64>,
+    /// Share-set generation; bumped each time the fragment is reshared to
+    /// a new k/n. Shares from different generations never combine: reads
+    /// only see the current one.
+    pub generation: u32,
 }
 
 /// SQLite-backed fragment store.
@@ -38,6 +43,9 @@ impl FragmentStore {
     }
 
     /// Store a fragment. Replaces any existing fragment with the same key.
+    ///
+    /// Writes go to generation 0, so once a fragment has been reshared this
+    /// fails with `StaleGeneration`; use [`Self::replace_shares`] instead.
     pub fn store_fragment(
         &self,
         repo_hash: &str,
@@ -45,22 +53,107 @@ impl FragmentStore {
         share_id: u32,
         data: &[u8],
     ) -> Result<(), StorageError> {
-        let data_hash = gitgold_crypto::hash::sha256_hex(data);
-        let now = unix_now();
+        insert_fragment(&self.conn, repo_hash, fragment_id, share_id, data)
+    }
 
-        self.conn
-            .execute(
-                "INSERT OR REPLACE INTO fragments
-                 (repo_hash, fragment_id, share_id, data, data_hash, stored_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                rusqlite::params![repo_hash, fragment_id, share_id, data, data_hash, now],
+    /// Store every share of one chunk, keyed by share id, in one transaction.
+    ///
+    /// Shares are persisted in their self-describing binary encoding, so this
+    /// works for any `ShareCodec` output (Shamir shares or Reed-Solomon shards).
+    /// Like [`Self::store_fragment`], it refuses reshared fragments.
+    pub fn store_shares(
+        &self,
+        repo_hash: &str,
+        fragment_id: u32,
+        shares: &[Share],
+    ) -> Result<(), StorageError> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        for share in shares {
+            insert_fragment(&tx, repo_hash, fragment_id, share.id, &share.to_bytes()?)?;
+        }
+        tx.commit()
+            .map_err(|e| StorageError::Database(e.to_string()))
+    }
+
+    /// Atomically replace every share of a fragment with a redistributed
+    /// share set, recording its `generation`.
+    ///
+    /// Old shares are removed first, so ids the new set does not reuse
+    /// cannot linger and be mixed with the new generation. A `generation`
+    /// not above the stored one is rejected inside the same transaction,
+    /// so a delayed or replayed reshare cannot roll a fragment back.
+    pub fn replace_shares(
+        &self,
+        repo_hash: &str,
+        fragment_id: u32,
+        generation: u32,
+        shares: &[Share],
+    ) -> Result<(), StorageError> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        let current = current_generation(&tx, repo_hash, fragment_id)?;
+        if let Some(current) = current.filter(|&current| generation <= current) {
+            return Err(StorageError::StaleGeneration {
+                repo_hash: repo_hash.to_string(),
+                fragment_id,
+                generation,
+                current,
+            });
+        }
+        tx.execute(
+            "DELETE FROM fragments WHERE repo_hash = ?1 AND fragment_id = ?2",
+            rusqlite::params![repo_hash, fragment_id],
+        )
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let now = unix_now();
+        for share in shares {
+            let data = share.to_bytes()?;
+            let data_hash = gitgold_crypto::hash::sha256_hex(&data);
+            tx.execute(
+                "INSERT INTO fragments
+                 (repo_hash, fragment_id, share_id, data, data_hash, stored_at, generation)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![repo_hash, fragment_id, share.id, data, data_hash, now, generation],
             )
             .map_err(|e| StorageError::Database(e.to_string()))?;
+        }
+        tx.commit()
+            .map_err(|e| StorageError::Database(e.to_string()))
+    }
 
-        Ok(())
+    /// Current share-set generation of a fragment (0 until first reshared).
+    pub fn fragment_generation(
+        &self,
+        repo_hash: &str,
+        fragment_id: u32,
+    ) -> Result<u32, StorageError> {
+        current_generation(&self.conn, repo_hash, fragment_id)?.ok_or_else(|| {
+            StorageError::FragmentNotFound {
+                repo_hash: repo_hash.to_string(),
+                fragment_id,
+            }
+        })
+    }
+
+    /// Retrieve and decode a share stored with `store_shares`.
+    pub fn get_share(
+        &self,
+        repo_hash: &str,
+        fragment_id: u32,
+        share_id: u32,
+    ) -> Result<Share, StorageError> {
+        let record = self.get_fragment(repo_hash, fragment_id, share_id)?;
+        Share::from_bytes(&record.data).map_err(|e| StorageError::InvalidShare(e.to_string()))
     }
 
     /// Retrieve a specific fragment by (repo_hash, fragment_id, share_id).
+    /// Only the fragment's current generation is visible.
     pub fn get_fragment(
         &self,
         repo_hash: &str,
@@ -69,9 +162,12 @@ impl FragmentStore {
     ) -> Result<FragmentRecord, StorageError> {
         self.conn
             .query_row(
-                "SELECT repo_hash, fragment_id, share_id, data, data_hash, stored_at, last_challenged
+                "SELECT repo_hash, fragment_id, share_id, data, data_hash, stored_at, last_challenged,
+                        generation
                  FROM fragments
-                 WHERE repo_hash = ?1 AND fragment_id = ?2 AND share_id = ?3",
+                 WHERE repo_hash = ?1 AND fragment_id = ?2 AND share_id = ?3
+                   AND generation = (SELECT MAX(generation) FROM fragments
+                                     WHERE repo_hash = ?1 AND fragment_id = ?2)",
                 rusqlite::params![repo_hash, fragment_id, share_id],
                 |row| {
                     Ok(FragmentRecord {
@@ -82,6 +178,7 @@ impl FragmentStore {
                         data_hash: row.get(4)?,
                         stored_at: row.get(5)?,
                         last_challenged: row.get(6)?,
+                        generation: row.get(7)?,
                     })
                 },
             )
@@ -94,14 +191,19 @@ impl FragmentStore {
             })
     }
 
-    /// List all fragments for a given repo_hash.
+    /// List all fragments for a given repo_hash, each at its current
+    /// generation.
     pub fn list_fragments(&self, repo_hash: &str) -> Result<Vec<FragmentRecord>, StorageError> {
         let mut stmt = self
             .conn
             .prepare(
-                "SELECT repo_hash, fragment_id, share_id, data, data_hash, stored_at, last_challenged
-                 FROM fragments
+                "SELECT repo_hash, fragment_id, share_id, data, data_hash, stored_at, last_challenged,
+                        generation
+                 FROM fragments f
                  WHERE repo_hash = ?1
+                   AND generation = (SELECT MAX(generation) FROM fragments
+                                     WHERE repo_hash = f.repo_hash
+                                       AND fragment_id = f.fragment_id)
                  ORDER BY fragment_id, share_id",
             )
             .map_err(|e| StorageError::Database(e.to_string()))?;
@@ -116,6 +218,7 @@ impl FragmentStore {
                     data_hash: row.get(4)?,
                     stored_at: row.get(5)?,
                     last_challenged: row.get(6)?,
+                    generation: row.get(7)?,
                 })
             })
             .map_err(|e| StorageError::Database(e.to_string()))?
@@ -176,6 +279,52 @@ impl FragmentStore {
     }
 }
 
+/// Highest stored generation of a fragment, if it has any shares.
+fn current_generation(
+    conn: &Connection,
+    repo_hash: &str,
+    fragment_id: u32,
+) -> Result<Option<u32>, StorageError> {
+    conn.query_row(
+        "SELECT MAX(generation) FROM fragments
+         WHERE repo_hash = ?1 AND fragment_id = ?2",
+        rusqlite::params![repo_hash, fragment_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| StorageError::Database(e.to_string()))
+}
+
+/// Write one generation-0 fragment row, refusing reshared fragments so old
+/// and new shares never sit side by side.
+fn insert_fragment(
+    conn: &Connection,
+    repo_hash: &str,
+    fragment_id: u32,
+    share_id: u32,
+    data: &[u8],
+) -> Result<(), StorageError> {
+    if let Some(current) = current_generation(conn, repo_hash, fragment_id)?.filter(|&g| g > 0) {
+        return Err(StorageError::StaleGeneration {
+            repo_hash: repo_hash.to_string(),
+            fragment_id,
+            generation: 0,
+            current,
+        });
+    }
+    let data_hash = gitgold_crypto::hash::sha256_hex(data);
+    let now = unix_now();
+
+    conn.execute(
+        "INSERT OR REPLACE INTO fragments
+         (repo_hash, fragment_id, share_id, data, data_hash, stored_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![repo_hash, fragment_id, share_id, data, data_hash, now],
+    )
+    .map_err(|e| StorageError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
 fn unix_now() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -248,6 +397,149 @@ mod tests {
         assert!(record.last_challenged.is_some());
     }
 
+    #[test]
+    fn test_store_shares() {
+        let store = test_store();
+        let shares = gitgold_crypto::reed_solomon::encode(&[9u8; 300], 3, 5).unwrap();
+        store.store_shares("repo1", 0, &shares).unwrap();
+
+        let frags = store.list_fragments("repo1").unwrap();
+        assert_eq!(frags.len(), 5);
+
+        let share = store.get_share("repo1", 0, 4).unwrap();
+        assert_eq!(share.id, 4);
+        assert_eq!(share.data, shares[3].data);
+        assert_eq!(share.secret_len, 300);
+    }
+
+    #[test]
+    fn test_get_share_rejects_raw_fragment() {
+        let store = test_store();
+        store.store_fragment("repo1", 0, 1, b"not an encoded share").unwrap();
+        assert!(matches!(
+            store.get_share("repo1", 0, 1),
+            Err(StorageError::InvalidShare(_))
+        ));
+    }
+
+    #[test]
+    fn test_replace_shares_records_generation() {
+        use gitgold_crypto::reshare::redistribute;
+        use gitgold_crypto::shamir::{reconstruct, split};
+
+        let store = test_store();
+        let secret = b"hot repo moving to faster pulls";
+        let shares = split(secret, 5, 9).unwrap();
+        store.store_shares("repo1", 0, &shares).unwrap();
+        assert_eq!(store.fragment_generation("repo1", 0).unwrap(), 0);
+
+        let new_shares = redistribute(&shares[..5], 3, 5).unwrap();
+        store.replace_shares("repo1", 0, 1, &new_shares).unwrap();
+
+        assert_eq!(store.fragment_generation("repo1", 0).unwrap(), 1);
+        let frags = store.list_fragments("repo1").unwrap();
+        assert_eq!(frags.len(), 5);
+        assert!(frags.iter().all(|f| f.generation == 1));
+
+        let fetched: Vec<Share> = [2, 4, 5]
+            .iter()
+            .map(|&id| store.get_share("repo1", 0, id).unwrap())
+            .collect();
+        assert_eq!(reconstruct(&fetched, 3).unwrap(), secret);
+
+        assert!(matches!(
+            store.fragment_generation("repo1", 7),
+            Err(StorageError::FragmentNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_replace_shares_rejects_stale_generation() {
+        use gitgold_crypto::reshare::redistribute;
+        use gitgold_crypto::shamir::split;
+
+        let store = test_store();
+        let shares = split(b"generation guard", 3, 5).unwrap();
+        store.store_shares("repo1", 0, &shares).unwrap();
+
+        let gen2 = redistribute(&shares[..3], 3, 5).unwrap();
+        store.replace_shares("repo1", 0, 2, &gen2).unwrap();
+
+        // Replaying an older or equal generation leaves gen 2 untouched.
+        for generation in [0, 1, 2] {
+            let stale = redistribute(&shares[..3], 2, 4).unwrap();
+            assert!(matches!(
+                store.replace_shares("repo1", 0, generation, &stale),
+                Err(StorageError::StaleGeneration { current: 2, .. })
+            ));
+        }
+        let frags = store.list_fragments("repo1").unwrap();
+        assert_eq!(frags.len(), 5);
+        assert!(frags.iter().all(|f| f.generation == 2));
+        for share in &gen2 {
+            let stored = store.get_share("repo1", 0, share.id).unwrap();
+            assert_eq!(stored.data, share.data);
+        }
+
+        store.replace_shares("repo1", 0, 3, &gen2).unwrap();
+        assert_eq!(store.fragment_generation("repo1", 0).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_generations_never_mix() {
+        use gitgold_crypto::reshare::redistribute;
+        use gitgold_crypto::shamir::{reconstruct, split};
+
+        let store = test_store();
+        let secret = b"old and new shares stay apart";
+        let shares = split(secret, 3, 5).unwrap();
+        store.store_shares("repo1", 0, &shares).unwrap();
+        let new_shares = redistribute(&shares[..3], 2, 3).unwrap();
+        store.replace_shares("repo1", 0, 1, &new_shares).unwrap();
+
+        // Re-storing the original split is refused and writes nothing.
+        assert!(matches!(
+            store.store_shares("repo1", 0, &shares),
+            Err(StorageError::StaleGeneration {
+                generation: 0,
+                current: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            store.store_fragment("repo1", 0, 4, b"raw"),
+            Err(StorageError::StaleGeneration { .. })
+        ));
+        let frags = store.list_fragments("repo1").unwrap();
+        assert_eq!(frags.len(), 3);
+        assert!(frags.iter().all(|f| f.generation == 1));
+
+        // Rows left behind at an older generation are invisible to reads.
+        store
+            .conn
+            .execute(
+                "INSERT INTO fragments
+                 (repo_hash, fragment_id, share_id, data, data_hash, stored_at, generation)
+                 VALUES ('repo1', 0, 5, ?1, '', 0, 0)",
+                [shares[4].to_bytes().unwrap()],
+            )
+            .unwrap();
+        assert!(matches!(
+            store.get_share("repo1", 0, 5),
+            Err(StorageError::FragmentNotFound { .. })
+        ));
+        assert_eq!(store.list_fragments("repo1").unwrap().len(), 3);
+        let fetched: Vec<Share> = [1, 3]
+            .iter()
+            .map(|&id| store.get_share("repo1", 0, id).unwrap())
+            .collect();
+        assert_eq!(reconstruct(&fetched, 2).unwrap(), secret);
+
+        // A fresh fragment still takes generation-0 writes, atomically.
+        store.store_shares("repo1", 1, &shares).unwrap();
+        assert_eq!(store.fragment_generation("repo1", 1).unwrap(), 0);
+    }
+
     #[test]
     fn test_replace_fragment() {
         let store = test_store();
@@ -1,3 +1,5 @@
 pub mod chunk;
 pub mod db;
+pub mod pipeline;
 pub mod schema;
+pub mod stream;
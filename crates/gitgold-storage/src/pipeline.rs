@@ -0,0 +1,101 @@
+use gitgold_core::error::StorageError;
+use gitgold_crypto::codec::ShareCodec;
+use gitgold_crypto::shamir::Share;
+
+/// Encode every chunk of a chunk set into `n` shares.
+///
+/// Output is in the same order as `chunks`.
+pub fn encode_chunks(
+    chunks: &[(u32, Vec<u8>)],
+    codec: &dyn ShareCodec,
+    k: usize,
+    n: usize,
+) -> Result<Vec<(u32, Vec<Share>)>, StorageError> {
+    chunks
+        .iter()
+        .map(|(index, data)| Ok((*index, codec.encode(data, k, n)?)))
+        .collect()
+}
+
+/// Parallel [`encode_chunks`]: one rayon task per chunk.
+///
+/// Output order matches `chunks` exactly, so the result is interchangeable
+/// with the serial version.
+#[cfg(feature = "parallel")]
+pub fn encode_chunks_par(
+    chunks: &[(u32, Vec<u8>)],
+    codec: &dyn ShareCodec,
+    k: usize,
+    n: usize,
+) -> Result<Vec<(u32, Vec<Share>)>, StorageError> {
+    use rayon::prelude::*;
+    chunks
+        .par_iter()
+        .map(|(index, data)| Ok((*index, codec.encode(data, k, n)?)))
+        .collect()
+}
+
+/// Decode every chunk from its shares. Output is in input order and can be
+/// passed to [`crate::chunk::reassemble_chunks`].
+pub fn decode_chunks(
+    encoded: &[(u32, Vec<Share>)],
+    codec: &dyn ShareCodec,
+    k: usize,
+) -> Result<Vec<(u32, Vec<u8>)>, StorageError> {
+    encoded
+        .iter()
+        .map(|(index, shares)| Ok((*index, codec.decode(shares, k)?)))
+        .collect()
+}
+
+/// Parallel [`decode_chunks`]: one rayon task per chunk, same output order.
+#[cfg(feature = "parallel")]
+pub fn decode_chunks_par(
+    encoded: &[(u32, Vec<Share>)],
+    codec: &dyn ShareCodec,
+    k: usize,
+) -> Result<Vec<(u32, Vec<u8>)>, StorageError> {
+    use rayon::prelude::*;
+    encoded
+        .par_iter()
+        .map(|(index, shares)| Ok((*index, codec.decode(shares, k)?)))
+        .collect()
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use super::*;
+    use crate::chunk::{chunk_data, reassemble_chunks};
+    use gitgold_core::types::RedundancyMode;
+    use gitgold_crypto::codec::codec_for;
+
+    #[test]
+    fn test_parallel_matches_serial_order() {
+        let data: Vec<u8> = (0..20_000).map(|i| (i * 13 % 256) as u8).collect();
+        let chunks = chunk_data(&data, 1024);
+        for mode in [
+            RedundancyMode::Confidential,
+            RedundancyMode::Efficient,
+            RedundancyMode::Hybrid,
+        ] {
+            let codec = codec_for(mode);
+            let encoded = encode_chunks_par(&chunks, codec, 3, 5).unwrap();
+            let indices: Vec<u32> = encoded.iter().map(|(i, _)| *i).collect();
+            assert_eq!(indices, (0..chunks.len() as u32).collect::<Vec<_>>());
+
+            let decoded = decode_chunks_par(&encoded, codec, 3).unwrap();
+            assert_eq!(decoded, decode_chunks(&encoded, codec, 3).unwrap());
+            assert_eq!(reassemble_chunks(decoded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_parallel_error_propagates() {
+        let chunks = vec![(0, b"fine".to_vec()), (1, Vec::new())];
+        let codec = codec_for(RedundancyMode::Confidential);
+        assert!(matches!(
+            encode_chunks_par(&chunks, codec, 2, 3),
+            Err(StorageError::Sharing(_))
+        ));
+    }
+}
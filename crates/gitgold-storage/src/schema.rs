@@ -12,6 +12,7 @@ pub fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
             data_hash   TEXT NOT NULL,
             stored_at   INTEGER NOT NULL,
             last_challenged INTEGER,
+            generation  INTEGER NOT NULL DEFAULT 0,
             PRIMARY KEY (repo_hash, fragment_id, share_id)
         );
 
@@ -31,6 +32,16 @@ pub fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
             ON challenges (repo_hash, fragment_id);
         ",
     )?;
+
+    // Databases created before share redistribution lack `generation`.
+    let has_generation = conn
+        .prepare("SELECT 1 FROM pragma_table_info('fragments') WHERE name = 'generation'")?
+        .exists([])?;
+    if !has_generation {
+        conn.execute_batch(
+            "ALTER TABLE fragments ADD COLUMN generation INTEGER NOT NULL DEFAULT 0;",
+        )?;
+    }
     Ok(())
 }
 
@@ -54,6 +65,25 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn test_schema_adds_generation_to_old_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE fragments (
+                repo_hash TEXT NOT NULL, fragment_id INTEGER NOT NULL,
+                share_id INTEGER NOT NULL, data BLOB NOT NULL, data_hash TEXT NOT NULL,
+                stored_at INTEGER NOT NULL, last_challenged INTEGER,
+                PRIMARY KEY (repo_hash, fragment_id, share_id));
+             INSERT INTO fragments VALUES ('r', 0, 1, x'00', 'h', 0, NULL);",
+        )
+        .unwrap();
+        init_schema(&conn).unwrap();
+        let generation: u32 = conn
+            .query_row("SELECT generation FROM fragments", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(generation, 0);
+    }
+
     #[test]
     fn test_schema_idempotent() {
         let conn = Connection::open_in_memory().unwrap();
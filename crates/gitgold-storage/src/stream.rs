@@ -0,0 +1,301 @@
+use std::io::{ErrorKind, Read, Write};
+
+use gitgold_core::error::StorageError;
+use gitgold_crypto::codec::ShareCodec;
+use gitgold_crypto::shamir::Share;
+
+/// Streaming counterpart of [`crate::chunk::chunk_data`].
+///
+/// Yields `(chunk_index, chunk_data)` pairs read from `reader`, holding at
+/// most one chunk in memory. Every chunk is `chunk_size` bytes except the
+/// last, which may be shorter. Empty input yields nothing.
+pub struct ChunkReader<R> {
+    reader: R,
+    chunk_size: usize,
+    next_index: u32,
+    done: bool,
+}
+
+impl<R: Read> ChunkReader<R> {
+    /// Panics if `chunk_size` is zero.
+    pub fn new(reader: R, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be non-zero");
+        Self {
+            reader,
+            chunk_size,
+            next_index: 0,
+            done: false,
+        }
+    }
+
+    /// Fill a buffer up to `chunk_size`, stopping early only at EOF.
+    fn read_chunk(&mut self) -> Result<Vec<u8>, StorageError> {
+        let mut buf = vec![0u8; self.chunk_size];
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.reader.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(StorageError::Io(e.to_string())),
+            }
+        }
+        buf.truncate(filled);
+        Ok(buf)
+    }
+}
+
+impl<R: Read> Iterator for ChunkReader<R> {
+    type Item = Result<(u32, Vec<u8>), StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let chunk = match self.read_chunk() {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+        if chunk.len() < self.chunk_size {
+            self.done = true;
+        }
+        if chunk.is_empty() {
+            return None;
+        }
+        let index = self.next_index;
+        self.next_index += 1;
+        Some(Ok((index, chunk)))
+    }
+}
+
+/// Chunks a reader and encodes each chunk into `n` shares as it goes.
+///
+/// Yields `(chunk_index, shares)`; memory is one chunk plus its shares.
+pub struct ShareEncoder<'a, R> {
+    chunks: ChunkReader<R>,
+    codec: &'a dyn ShareCodec,
+    k: usize,
+    n: usize,
+}
+
+impl<'a, R: Read> ShareEncoder<'a, R> {
+    /// Encode chunks of `chunk_size` bytes with `codec` at threshold `k` of `n`.
+    pub fn new(
+        reader: R,
+        chunk_size: usize,
+        codec: &'a dyn ShareCodec,
+        k: usize,
+        n: usize,
+    ) -> Self {
+        Self {
+            chunks: ChunkReader::new(reader, chunk_size),
+            codec,
+            k,
+            n,
+        }
+    }
+}
+
+impl<R: Read> Iterator for ShareEncoder<'_, R> {
+    type Item = Result<(u32, Vec<Share>), StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.chunks.next()?;
+        Some(item.and_then(|(index, chunk)| {
+            let shares = self.codec.encode(&chunk, self.k, self.n)?;
+            Ok((index, shares))
+        }))
+    }
+}
+
+/// Streaming counterpart of [`crate::chunk::reassemble_chunks`].
+///
+/// Decodes each chunk from its shares and writes it straight to the
+/// underlying writer. Chunks must be pushed in index order starting at 0,
+/// so nothing is buffered beyond the chunk being decoded.
+pub struct StreamReassembler<'a, W> {
+    writer: W,
+    codec: &'a dyn ShareCodec,
+    k: usize,
+    next_index: u32,
+    bytes_written: u64,
+}
+
+impl<'a, W: Write> StreamReassembler<'a, W> {
+    /// Reassemble into `writer`, decoding with `codec` at threshold `k`.
+    pub fn new(writer: W, codec: &'a dyn ShareCodec, k: usize) -> Self {
+        Self {
+            writer,
+            codec,
+            k,
+            next_index: 0,
+            bytes_written: 0,
+        }
+    }
+
+    /// Decode chunk `index` from `shares` and append it to the output.
+    pub fn push(&mut self, index: u32, shares: &[Share]) -> Result<(), StorageError> {
+        if index != self.next_index {
+            return Err(StorageError::OutOfOrderChunk {
+                expected: self.next_index,
+                found: index,
+            });
+        }
+        let chunk = self.codec.decode(shares, self.k)?;
+        self.writer
+            .write_all(&chunk)
+            .map_err(|e| StorageError::Io(e.to_string()))?;
+        self.next_index += 1;
+        self.bytes_written += chunk.len() as u64;
+        Ok(())
+    }
+
+    /// Number of chunks written so far.
+    pub fn chunks_written(&self) -> u32 {
+        self.next_index
+    }
+
+    /// Total bytes written so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn finish(mut self) -> Result<W, StorageError> {
+        self.writer
+            .flush()
+            .map_err(|e| StorageError::Io(e.to_string()))?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::chunk_data;
+    use gitgold_core::types::RedundancyMode;
+    use gitgold_crypto::codec::codec_for;
+    use std::cell::Cell;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_chunk_reader_matches_chunk_data() {
+        let data: Vec<u8> = (0..2000).map(|i| (i % 251) as u8).collect();
+        let streamed: Vec<(u32, Vec<u8>)> = ChunkReader::new(Cursor::new(&data), 512)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(streamed, chunk_data(&data, 512));
+    }
+
+    #[test]
+    fn test_chunk_reader_exact_multiple_and_empty() {
+        let chunks: Vec<_> = ChunkReader::new(Cursor::new(vec![1u8; 1024]), 512).collect();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(ChunkReader::new(Cursor::new(Vec::new()), 512).count(), 0);
+    }
+
+    #[test]
+    fn test_chunk_reader_short_reads() {
+        // A reader returning one byte per call must still fill whole chunks.
+        struct Trickle<'a>(&'a [u8]);
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0.is_empty() || buf.is_empty() {
+                    return Ok(0);
+                }
+                buf[0] = self.0[0];
+                self.0 = &self.0[1..];
+                Ok(1)
+            }
+        }
+        let data = vec![9u8; 100];
+        let sizes: Vec<usize> = ChunkReader::new(Trickle(&data), 40)
+            .map(|c| c.unwrap().1.len())
+            .collect();
+        assert_eq!(sizes, vec![40, 40, 20]);
+    }
+
+    #[test]
+    fn test_stream_roundtrip_both_modes() {
+        let data: Vec<u8> = (0..5000).map(|i| (i * 7 % 256) as u8).collect();
+        for mode in [
+            RedundancyMode::Confidential,
+            RedundancyMode::Efficient,
+            RedundancyMode::Hybrid,
+        ] {
+            let codec = codec_for(mode);
+            let mut reassembler = StreamReassembler::new(Vec::new(), codec, 3);
+            for item in ShareEncoder::new(Cursor::new(&data), 1024, codec, 3, 5) {
+                let (index, shares) = item.unwrap();
+                assert_eq!(shares.len(), 5);
+                reassembler.push(index, &shares[2..]).unwrap();
+            }
+            assert_eq!(reassembler.chunks_written(), 5);
+            assert_eq!(reassembler.bytes_written(), data.len() as u64);
+            assert_eq!(reassembler.finish().unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_large_input_never_materialized() {
+        // 8 MB of input is read through a 64 KB window: the source fails
+        // any read reaching more than one chunk past what has been yielded.
+        struct Window<R> {
+            inner: R,
+            read: Rc<Cell<u64>>,
+            allowed: Rc<Cell<u64>>,
+        }
+        impl<R: Read> Read for Window<R> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let room = (self.allowed.get() - self.read.get()) as usize;
+                if buf.len() > room {
+                    return Err(std::io::Error::other("read past the window"));
+                }
+                let read = self.inner.read(buf)?;
+                self.read.set(self.read.get() + read as u64);
+                Ok(read)
+            }
+        }
+
+        const CHUNK: u64 = 64 * 1024;
+        let len = 8 * 1024 * 1024;
+        let read = Rc::new(Cell::new(0));
+        let allowed = Rc::new(Cell::new(CHUNK));
+        let reader = Window {
+            inner: std::io::repeat(0x5A).take(len),
+            read: Rc::clone(&read),
+            allowed: Rc::clone(&allowed),
+        };
+        let mut total = 0u64;
+        for item in ChunkReader::new(reader, CHUNK as usize) {
+            let (_, chunk) = item.unwrap();
+            total += chunk.len() as u64;
+            // Nothing is held back beyond the chunk just yielded.
+            assert_eq!(read.get(), total);
+            allowed.set(total + CHUNK);
+        }
+        assert_eq!(total, len);
+    }
+
+    #[test]
+    fn test_out_of_order_rejected() {
+        let codec = codec_for(RedundancyMode::Efficient);
+        let encoded: Vec<(u32, Vec<Share>)> =
+            ShareEncoder::new(Cursor::new(vec![3u8; 300]), 100, codec, 2, 3)
+                .collect::<Result<_, _>>()
+                .unwrap();
+        let mut reassembler = StreamReassembler::new(Vec::new(), codec, 2);
+        reassembler.push(0, &encoded[0].1).unwrap();
+        assert!(matches!(
+            reassembler.push(2, &encoded[2].1),
+            Err(StorageError::OutOfOrderChunk {
+                expected: 1,
+                found: 2
+            })
+        ));
+    }
+}
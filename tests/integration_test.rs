@@ -3,13 +3,16 @@ use gitgold_challenge::proof::ChallengeProof;
 use gitgold_challenge::validator::validate_challenge_response;
 use gitgold_core::config::GitGoldConfig;
 use gitgold_core::error::LedgerError;
-use gitgold_core::types::{Address, TransactionType};
+use gitgold_core::types::{Address, RedundancyMode, TransactionType};
 use gitgold_crypto::hash::sha256_hex;
+use gitgold_crypto::codec::codec_for;
 use gitgold_crypto::keys::KeyPair;
 use gitgold_crypto::shamir;
+use gitgold_crypto::wallet::Wallet;
+use gitgold_ledger::builder::TransactionBuilder;
 use gitgold_ledger::merkle::MerkleTree;
 use gitgold_ledger::store::Ledger;
-use gitgold_ledger::transaction::Transaction;
+use gitgold_ledger::transaction::{Transaction, TX_VERSION};
 use gitgold_storage::chunk::{chunk_data, reassemble_chunks};
 use gitgold_storage::db::FragmentStore;
 
@@ -35,11 +38,7 @@ fn test_full_storage_roundtrip() {
         assert_eq!(shares.len(), config.n);
 
         // 3. Store each share as a fragment
-        for share in &shares {
-            store
-                .store_fragment(repo_hash, *chunk_idx, share.id, &share.data)
-                .unwrap();
-        }
+        store.store_shares(repo_hash, *chunk_idx, &shares).unwrap();
     }
 
     // Verify all fragments stored
@@ -51,18 +50,12 @@ fn test_full_storage_roundtrip() {
     for (chunk_idx, _) in &chunks {
         let mut retrieved_shares = Vec::new();
         for share_id in 1..=(config.k as u32) {
-            let frag = store.get_fragment(repo_hash, *chunk_idx, share_id).unwrap();
-            retrieved_shares.push(shamir::Share {
-                id: frag.share_id,
-                data: frag.data,
-            });
+            retrieved_shares.push(store.get_share(repo_hash, *chunk_idx, share_id).unwrap());
         }
 
-        // 5. Reconstruct
+        // 5. Reconstruct (shares record the chunk length, so no trimming)
         let recovered = shamir::reconstruct(&retrieved_shares, config.k).unwrap();
-        // Trim to original chunk size
-        let original_chunk = &chunks.iter().find(|(i, _)| i == chunk_idx).unwrap().1;
-        reconstructed_chunks.push((*chunk_idx, recovered[..original_chunk.len()].to_vec()));
+        reconstructed_chunks.push((*chunk_idx, recovered));
     }
 
     // 6. Reassemble and verify
@@ -71,6 +64,63 @@ fn test_full_storage_roundtrip() {
     assert_eq!(sha256_hex(&reassembled), original_hash);
 }
 
+/// Efficient (Reed-Solomon) redundancy: shards stored and restored like Shamir shares
+#[test]
+fn test_efficient_mode_storage_roundtrip() {
+    let config = GitGoldConfig {
+        redundancy: RedundancyMode::Efficient,
+        ..GitGoldConfig::default()
+    };
+    let codec = codec_for(config.redundancy);
+
+    let original: Vec<u8> = (0..1_200_000).map(|i| ((i * 7 + 3) % 256) as u8).collect();
+    let chunks = chunk_data(&original, config.chunk_size);
+    let store = FragmentStore::in_memory().unwrap();
+
+    for (chunk_idx, chunk_bytes) in &chunks {
+        let shares = codec.encode(chunk_bytes, config.k, config.n).unwrap();
+        store.store_shares("rs-repo", *chunk_idx, &shares).unwrap();
+    }
+
+    // Stored bytes are ~n/k of the original, not n times.
+    let stored: usize = store
+        .list_fragments("rs-repo")
+        .unwrap()
+        .iter()
+        .map(|f| f.data.len())
+        .sum();
+    assert!(stored < original.len() * 2);
+
+    // Restore each chunk from the last k shards (mostly parity).
+    let mut recovered_chunks = Vec::new();
+    for (chunk_idx, original_chunk) in &chunks {
+        let shares: Vec<shamir::Share> = ((config.n - config.k + 1) as u32..=config.n as u32)
+            .map(|sid| store.get_share("rs-repo", *chunk_idx, sid).unwrap())
+            .collect();
+        let recovered = codec.decode(&shares, config.k).unwrap();
+        assert_eq!(recovered.len(), original_chunk.len());
+        recovered_chunks.push((*chunk_idx, recovered));
+    }
+
+    assert_eq!(reassemble_chunks(recovered_chunks).unwrap(), original);
+}
+
+/// Chunks ending in zero bytes survive the share envelope exactly
+#[test]
+fn test_trailing_zero_chunk_exact_roundtrip() {
+    let mut chunk = b"pack data".to_vec();
+    chunk.extend_from_slice(&[0u8; 40]);
+
+    let store = FragmentStore::in_memory().unwrap();
+    let shares = shamir::split(&chunk, 3, 5).unwrap();
+    store.store_shares("zeros", 0, &shares).unwrap();
+
+    let retrieved: Vec<shamir::Share> = (2..=4)
+        .map(|sid| store.get_share("zeros", 0, sid).unwrap())
+        .collect();
+    assert_eq!(shamir::reconstruct(&retrieved, 3).unwrap(), chunk);
+}
+
 /// Shamir: using different k-of-n subsets all produce the same result
 #[test]
 fn test_shamir_any_subset() {
@@ -102,8 +152,8 @@ fn test_shamir_any_subset() {
 #[test]
 fn test_ledger_with_merkle_proofs() {
     let mut ledger = Ledger::in_memory().unwrap();
-    let alice_kp = KeyPair::generate();
-    let alice = alice_kp.address();
+    let alice_wallet = Wallet::new();
+    let alice = alice_wallet.address();
     let bob = Address::new("bob");
 
     // Mint tokens to Alice
@@ -116,39 +166,28 @@ fn test_ledger_with_merkle_proofs() {
             amount: 10_000_000,
             metadata: serde_json::json!({"reason": "initial allocation"}),
             timestamp: 1700000000,
+            nonce: 0,
             signature: String::new(),
             pubkey: String::new(),
+            version: TX_VERSION,
+            multisig: None,
         })
         .unwrap();
 
     // Alice transfers to Bob
-    let mut transfer_tx = Transaction {
-        tx_id: "tx-transfer-1".to_string(),
-        tx_type: TransactionType::Transfer,
-        from: alice.clone(),
-        to: bob.clone(),
-        amount: 3_000_000,
-        metadata: serde_json::json!({}),
-        timestamp: 1700000001,
-        signature: String::new(),
-        pubkey: hex::encode(alice_kp.public_key().bytes),
-    };
-    transfer_tx.signature = hex::encode(alice_kp.sign(&transfer_tx.signable_bytes()));
+    let transfer_tx = TransactionBuilder::transfer(bob.clone(), 3_000_000)
+        .tx_id("tx-transfer-1")
+        .timestamp(1700000001)
+        .sign_checked(&alice_wallet, &ledger)
+        .unwrap();
     ledger.append(transfer_tx).unwrap();
 
     // Alice burns some tokens
-    let mut burn_tx = Transaction {
-        tx_id: "tx-burn-1".to_string(),
-        tx_type: TransactionType::Burn,
-        from: alice.clone(),
-        to: Address::system(),
-        amount: 1_000_000,
-        metadata: serde_json::json!({}),
-        timestamp: 1700000002,
-        signature: String::new(),
-        pubkey: hex::encode(alice_kp.public_key().bytes),
-    };
-    burn_tx.signature = hex::encode(alice_kp.sign(&burn_tx.signable_bytes()));
+    let burn_tx = TransactionBuilder::burn(1_000_000)
+        .tx_id("tx-burn-1")
+        .timestamp(1700000002)
+        .sign_checked(&alice_wallet, &ledger)
+        .unwrap();
     ledger.append(burn_tx).unwrap();
 
     // Verify balances
@@ -184,8 +223,11 @@ fn test_ledger_security() {
             amount: 1_000_000,
             metadata: serde_json::json!({}),
             timestamp: 1700000000,
+            nonce: 0,
             signature: String::new(),
             pubkey: String::new(),
+            version: TX_VERSION,
+            multisig: None,
         })
         .unwrap();
 
@@ -198,8 +240,11 @@ fn test_ledger_security() {
         amount: 800_000,
         metadata: serde_json::json!({}),
         timestamp: 1700000001,
+        nonce: 0,
         signature: String::new(),
         pubkey: hex::encode(alice_kp.public_key().bytes),
+        version: TX_VERSION,
+        multisig: None,
     };
     spend_tx.signature = hex::encode(alice_kp.sign(&spend_tx.signable_bytes()));
     ledger.append(spend_tx).unwrap();
@@ -213,8 +258,11 @@ fn test_ledger_security() {
         amount: 300_000,
         metadata: serde_json::json!({}),
         timestamp: 1700000002,
+        nonce: 1,
         signature: String::new(),
         pubkey: hex::encode(alice_kp.public_key().bytes),
+        version: TX_VERSION,
+        multisig: None,
     };
     spend_tx2.signature = hex::encode(alice_kp.sign(&spend_tx2.signable_bytes()));
     let result = ledger.append(spend_tx2);
@@ -223,6 +271,27 @@ fn test_ledger_security() {
         Err(LedgerError::InsufficientBalance { .. })
     ));
 
+    // Replay the first spend under a fresh tx_id -- should fail on its nonce
+    let mut replayed = Transaction {
+        tx_id: "spend-1-replay".to_string(),
+        tx_type: TransactionType::Transfer,
+        from: alice.clone(),
+        to: Address::new("bob"),
+        amount: 100_000,
+        metadata: serde_json::json!({}),
+        timestamp: 1700000001,
+        nonce: 0,
+        signature: String::new(),
+        pubkey: hex::encode(alice_kp.public_key().bytes),
+        version: TX_VERSION,
+        multisig: None,
+    };
+    replayed.signature = hex::encode(alice_kp.sign(&replayed.signable_bytes()));
+    assert!(matches!(
+        ledger.append(replayed),
+        Err(LedgerError::InvalidNonce { expected: 1, actual: 0, .. })
+    ));
+
     // Try duplicate tx_id -- should fail
     let result = ledger.append(Transaction {
         tx_id: "mint-1".to_string(), // duplicate!
@@ -232,8 +301,11 @@ fn test_ledger_security() {
         amount: 999,
         metadata: serde_json::json!({}),
         timestamp: 1700000003,
+        nonce: 0,
         signature: String::new(),
         pubkey: String::new(),
+        version: TX_VERSION,
+        multisig: None,
     });
     assert!(matches!(
         result,
@@ -322,8 +394,11 @@ fn test_store_challenge_reward_flow() {
                 "speed_bonus": result.speed_bonus,
             }),
             timestamp: 1700000000,
+            nonce: 0,
             signature: String::new(),
             pubkey: String::new(),
+            version: TX_VERSION,
+            multisig: None,
         })
         .unwrap();
 
@@ -369,27 +444,19 @@ fn test_multi_chunk_storage_roundtrip() {
     // Split and store all chunks
     for (chunk_idx, chunk_bytes) in &chunks {
         let shares = shamir::split(chunk_bytes, k, n).unwrap();
-        for share in &shares {
-            store
-                .store_fragment("bigrepo", *chunk_idx, share.id, &share.data)
-                .unwrap();
-        }
+        store.store_shares("bigrepo", *chunk_idx, &shares).unwrap();
     }
 
     // Retrieve using only k shares per chunk and reconstruct
     let mut recovered_chunks = Vec::new();
-    for (chunk_idx, original_chunk) in &chunks {
+    for (chunk_idx, _) in &chunks {
         let mut shares = Vec::new();
         // Use shares 3, 4, 5 (not 1, 2 -- proving any subset works)
         for sid in (n as u32 - k as u32 + 1)..=(n as u32) {
-            let frag = store.get_fragment("bigrepo", *chunk_idx, sid).unwrap();
-            shares.push(shamir::Share {
-                id: frag.share_id,
-                data: frag.data,
-            });
+            shares.push(store.get_share("bigrepo", *chunk_idx, sid).unwrap());
         }
         let recovered = shamir::reconstruct(&shares, k).unwrap();
-        recovered_chunks.push((*chunk_idx, recovered[..original_chunk.len()].to_vec()));
+        recovered_chunks.push((*chunk_idx, recovered));
     }
 
     let reassembled = reassemble_chunks(recovered_chunks).unwrap();